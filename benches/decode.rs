@@ -0,0 +1,31 @@
+use std::fs;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use rust_chip8::chip8::{self, Chip8};
+use rust_chip8::null_display_driver::NullDisplayDriver;
+
+// The same ROM run with instructions decoded on every fetch, and through the decode cache
+fn decoding(c: &mut Criterion) {
+    let rom = fs::read("roms/BRIX").unwrap();
+
+    for (name, decode_cache) in [("BRIX 60 frames, decode every fetch", false), ("BRIX 60 frames, decode cache", true)] {
+        c.bench_function(name, |b| {
+            b.iter(|| {
+                let mut chip8 = Chip8::new(NullDisplayDriver::new());
+                chip8.set_decode_cache(decode_cache);
+                chip8.seed_rng(0);
+                chip8.load_default_font();
+                chip8.load_rom(&rom).unwrap();
+
+                for _ in 0..60 {
+                    chip8.run_frame(chip8::INSTRUCTIONS_PER_FRAME).unwrap();
+                }
+                black_box(chip8.display_driver().frames_changed())
+            })
+        });
+    }
+}
+
+criterion_group!(benches, decoding);
+criterion_main!(benches);
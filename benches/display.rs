@@ -0,0 +1,60 @@
+use std::fs;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use rust_chip8::chip8::Chip8;
+use rust_chip8::display::{self, Display, WIDTH, HEIGHT};
+use rust_chip8::null_display_driver::NullDisplayDriver;
+
+// The packed rows against the original bool per pixel screen, drawing the same sprite
+fn sprite_blitting(c: &mut Criterion) {
+    let mut group = c.benchmark_group("draw 8x15 sprite");
+
+    let mut display = Display::new(NullDisplayDriver::new());
+    group.bench_function("packed rows", |b| {
+        let mut x = 0u8;
+        b.iter(|| {
+            display.begin_draw();
+            for row in 0..15 {
+                display.set_pixels(black_box(x), row, black_box(0b1011_0110), 0);
+            }
+            x = x.wrapping_add(3);
+            black_box(display.did_collide())
+        })
+    });
+
+    let mut screen = [false; WIDTH * HEIGHT];
+    group.bench_function("bool per pixel", |b| {
+        let mut x = 0u8;
+        b.iter(|| {
+            let mut collision = false;
+            for row in 0..15 {
+                collision |= display::reference_set_pixels(&mut screen, black_box(x), row, black_box(0b1011_0110), 0);
+            }
+            x = x.wrapping_add(3);
+            black_box(collision)
+        })
+    });
+
+    group.finish();
+}
+
+fn rom_frames(c: &mut Criterion) {
+    let rom = fs::read("roms/IBM Logo.ch8").unwrap();
+
+    c.bench_function("IBM logo 60 frames", |b| {
+        b.iter(|| {
+            let mut chip8 = Chip8::new(NullDisplayDriver::new());
+            chip8.load_default_font();
+            chip8.load_rom(&rom).unwrap();
+
+            for _ in 0..60 * 12 {
+                chip8.cycle().unwrap();
+            }
+            black_box(chip8.display_driver().frames_changed())
+        })
+    });
+}
+
+criterion_group!(benches, sprite_blitting, rom_frames);
+criterion_main!(benches);
@@ -0,0 +1,28 @@
+// Runs BRIX from its recompiled Rust source, with no ROM file needed at runtime, and prints
+// the screen after the given number of frames (600 by default).
+//
+//   cargo run --example brix -- 600
+//
+// Regenerate the source with `cargo run -- --recompile examples/recompiled/brix.rs roms/BRIX`.
+
+use std::env;
+
+use rust_chip8::chip8::{self, Chip8};
+use rust_chip8::display;
+use rust_chip8::null_display_driver::NullDisplayDriver;
+
+#[path = "recompiled/brix.rs"]
+mod brix;
+
+fn main() {
+    let frames: u32 = env::args().nth(1).map_or(600, |frames| frames.parse().expect("Invalid frame count"));
+
+    let mut chip8 = Chip8::new(NullDisplayDriver::new());
+    brix::load(&mut chip8);
+
+    for _ in 0..frames {
+        brix::run_frame(&mut chip8, chip8::INSTRUCTIONS_PER_FRAME).unwrap();
+    }
+
+    print!("{}", display::to_ascii(chip8.framebuffer()));
+}
@@ -0,0 +1,869 @@
+// Recompiled from BRIX by rust-chip8 --recompile. Each basic block reachable from 0x200
+// through jumps, calls and skips is a native function, with its instructions written out in
+// Rust for the quirks below. BNNN and anything else the analysis couldn't follow are
+// interpreted, as are blocks the ROM has overwritten, blocks that wouldn't fit in what's left
+// of the frame and every instruction when the machine's quirks have been changed.
+
+use rust_chip8::chip8::{self, Chip8};
+use rust_chip8::cpu::Fault;
+use rust_chip8::display::DisplayDriver;
+use rust_chip8::quirks::Quirks;
+
+pub const QUIRKS: Quirks = Quirks { shift_uses_vy: false, load_store_increments_index: false, logic_resets_vf: false, jump_uses_vx: false, index_overflow_sets_vf: true };
+
+pub const ROM: [u8; 280] = [
+    0x6E, 0x05, 0x65, 0x00, 0x6B, 0x06, 0x6A, 0x00, 0xA3, 0x0C, 0xDA, 0xB1, 0x7A, 0x04, 0x3A, 0x40,
+    0x12, 0x08, 0x7B, 0x02, 0x3B, 0x12, 0x12, 0x06, 0x6C, 0x20, 0x6D, 0x1F, 0xA3, 0x10, 0xDC, 0xD1,
+    0x22, 0xF6, 0x60, 0x00, 0x61, 0x00, 0xA3, 0x12, 0xD0, 0x11, 0x70, 0x08, 0xA3, 0x0E, 0xD0, 0x11,
+    0x60, 0x40, 0xF0, 0x15, 0xF0, 0x07, 0x30, 0x00, 0x12, 0x34, 0xC6, 0x0F, 0x67, 0x1E, 0x68, 0x01,
+    0x69, 0xFF, 0xA3, 0x0E, 0xD6, 0x71, 0xA3, 0x10, 0xDC, 0xD1, 0x60, 0x04, 0xE0, 0xA1, 0x7C, 0xFE,
+    0x60, 0x06, 0xE0, 0xA1, 0x7C, 0x02, 0x60, 0x3F, 0x8C, 0x02, 0xDC, 0xD1, 0xA3, 0x0E, 0xD6, 0x71,
+    0x86, 0x84, 0x87, 0x94, 0x60, 0x3F, 0x86, 0x02, 0x61, 0x1F, 0x87, 0x12, 0x47, 0x1F, 0x12, 0xAC,
+    0x46, 0x00, 0x68, 0x01, 0x46, 0x3F, 0x68, 0xFF, 0x47, 0x00, 0x69, 0x01, 0xD6, 0x71, 0x3F, 0x01,
+    0x12, 0xAA, 0x47, 0x1F, 0x12, 0xAA, 0x60, 0x05, 0x80, 0x75, 0x3F, 0x00, 0x12, 0xAA, 0x60, 0x01,
+    0xF0, 0x18, 0x80, 0x60, 0x61, 0xFC, 0x80, 0x12, 0xA3, 0x0C, 0xD0, 0x71, 0x60, 0xFE, 0x89, 0x03,
+    0x22, 0xF6, 0x75, 0x01, 0x22, 0xF6, 0x45, 0x60, 0x12, 0xDE, 0x12, 0x46, 0x69, 0xFF, 0x80, 0x60,
+    0x80, 0xC5, 0x3F, 0x01, 0x12, 0xCA, 0x61, 0x02, 0x80, 0x15, 0x3F, 0x01, 0x12, 0xE0, 0x80, 0x15,
+    0x3F, 0x01, 0x12, 0xEE, 0x80, 0x15, 0x3F, 0x01, 0x12, 0xE8, 0x60, 0x20, 0xF0, 0x18, 0xA3, 0x0E,
+    0x7E, 0xFF, 0x80, 0xE0, 0x80, 0x04, 0x61, 0x00, 0xD0, 0x11, 0x3E, 0x00, 0x12, 0x30, 0x12, 0xDE,
+    0x78, 0xFF, 0x48, 0xFE, 0x68, 0xFF, 0x12, 0xEE, 0x78, 0x01, 0x48, 0x02, 0x68, 0x01, 0x60, 0x04,
+    0xF0, 0x18, 0x69, 0xFF, 0x12, 0x70, 0xA3, 0x14, 0xF5, 0x33, 0xF2, 0x65, 0xF1, 0x29, 0x63, 0x37,
+    0x64, 0x00, 0xD3, 0x45, 0x73, 0x05, 0xF2, 0x29, 0xD3, 0x45, 0x00, 0xEE, 0xE0, 0x00, 0x80, 0x00,
+    0xFC, 0x00, 0xAA, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+pub fn load<D: DisplayDriver>(chip8: &mut Chip8<D>) {
+    chip8.set_quirks(QUIRKS);
+    chip8.load_default_font();
+    chip8.load_rom(&ROM).expect("ROM fits in memory");
+}
+
+fn unchanged<D: DisplayDriver>(chip8: &Chip8<D>, address: u16, length: usize) -> bool {
+    let offset = (address - chip8::PROGRAM_START) as usize;
+    chip8.memory().read_bytes(address, length) == &ROM[offset..offset + length]
+}
+
+// Like Chip8::run_frame, running recompiled blocks wherever they can be
+pub fn run_frame<D: DisplayDriver>(chip8: &mut Chip8<D>, instructions: u32) -> Result<(), Fault> {
+    if chip8.cpu().quirks() != QUIRKS {
+        return chip8.run_frame(instructions);
+    }
+
+    let mut remaining = instructions;
+
+    while remaining > 0 {
+        chip8.precycle();
+
+        remaining -= match chip8.cpu().program_counter() {
+            0x200 if remaining >= 3 && unchanged(chip8, 0x200, 6) => block_200(chip8)?,
+            0x206 if unchanged(chip8, 0x206, 2) => block_206(chip8)?,
+            0x208 if remaining >= 4 && unchanged(chip8, 0x208, 8) => block_208(chip8)?,
+            0x210 if unchanged(chip8, 0x210, 2) => block_210(chip8)?,
+            0x212 if remaining >= 2 && unchanged(chip8, 0x212, 4) => block_212(chip8)?,
+            0x216 if unchanged(chip8, 0x216, 2) => block_216(chip8)?,
+            0x218 if remaining >= 5 && unchanged(chip8, 0x218, 10) => block_218(chip8)?,
+            0x222 if remaining >= 7 && unchanged(chip8, 0x222, 14) => block_222(chip8)?,
+            0x230 if remaining >= 2 && unchanged(chip8, 0x230, 4) => block_230(chip8)?,
+            0x234 if remaining >= 2 && unchanged(chip8, 0x234, 4) => block_234(chip8)?,
+            0x238 if unchanged(chip8, 0x238, 2) => block_238(chip8)?,
+            0x23A if remaining >= 6 && unchanged(chip8, 0x23A, 12) => block_23a(chip8)?,
+            0x246 if remaining >= 4 && unchanged(chip8, 0x246, 8) => block_246(chip8)?,
+            0x24E if unchanged(chip8, 0x24E, 2) => block_24e(chip8)?,
+            0x250 if remaining >= 2 && unchanged(chip8, 0x250, 4) => block_250(chip8)?,
+            0x254 if unchanged(chip8, 0x254, 2) => block_254(chip8)?,
+            0x256 if remaining >= 12 && unchanged(chip8, 0x256, 24) => block_256(chip8)?,
+            0x26E if unchanged(chip8, 0x26E, 2) => block_26e(chip8)?,
+            0x270 if unchanged(chip8, 0x270, 2) => block_270(chip8)?,
+            0x272 if unchanged(chip8, 0x272, 2) => block_272(chip8)?,
+            0x274 if unchanged(chip8, 0x274, 2) => block_274(chip8)?,
+            0x276 if unchanged(chip8, 0x276, 2) => block_276(chip8)?,
+            0x278 if unchanged(chip8, 0x278, 2) => block_278(chip8)?,
+            0x27A if unchanged(chip8, 0x27A, 2) => block_27a(chip8)?,
+            0x27C if remaining >= 2 && unchanged(chip8, 0x27C, 4) => block_27c(chip8)?,
+            0x280 if unchanged(chip8, 0x280, 2) => block_280(chip8)?,
+            0x282 if unchanged(chip8, 0x282, 2) => block_282(chip8)?,
+            0x284 if unchanged(chip8, 0x284, 2) => block_284(chip8)?,
+            0x286 if remaining >= 3 && unchanged(chip8, 0x286, 6) => block_286(chip8)?,
+            0x28C if unchanged(chip8, 0x28C, 2) => block_28c(chip8)?,
+            0x28E if remaining >= 10 && unchanged(chip8, 0x28E, 20) => block_28e(chip8)?,
+            0x2A2 if remaining >= 2 && unchanged(chip8, 0x2A2, 4) => block_2a2(chip8)?,
+            0x2A6 if unchanged(chip8, 0x2A6, 2) => block_2a6(chip8)?,
+            0x2A8 if unchanged(chip8, 0x2A8, 2) => block_2a8(chip8)?,
+            0x2AA if unchanged(chip8, 0x2AA, 2) => block_2aa(chip8)?,
+            0x2AC if remaining >= 4 && unchanged(chip8, 0x2AC, 8) => block_2ac(chip8)?,
+            0x2B4 if unchanged(chip8, 0x2B4, 2) => block_2b4(chip8)?,
+            0x2B6 if remaining >= 3 && unchanged(chip8, 0x2B6, 6) => block_2b6(chip8)?,
+            0x2BC if unchanged(chip8, 0x2BC, 2) => block_2bc(chip8)?,
+            0x2BE if remaining >= 2 && unchanged(chip8, 0x2BE, 4) => block_2be(chip8)?,
+            0x2C2 if unchanged(chip8, 0x2C2, 2) => block_2c2(chip8)?,
+            0x2C4 if remaining >= 2 && unchanged(chip8, 0x2C4, 4) => block_2c4(chip8)?,
+            0x2C8 if unchanged(chip8, 0x2C8, 2) => block_2c8(chip8)?,
+            0x2CA if remaining >= 9 && unchanged(chip8, 0x2CA, 18) => block_2ca(chip8)?,
+            0x2DC if unchanged(chip8, 0x2DC, 2) => block_2dc(chip8)?,
+            0x2DE if unchanged(chip8, 0x2DE, 2) => block_2de(chip8)?,
+            0x2E0 if remaining >= 2 && unchanged(chip8, 0x2E0, 4) => block_2e0(chip8)?,
+            0x2E4 if unchanged(chip8, 0x2E4, 2) => block_2e4(chip8)?,
+            0x2E6 if unchanged(chip8, 0x2E6, 2) => block_2e6(chip8)?,
+            0x2E8 if remaining >= 2 && unchanged(chip8, 0x2E8, 4) => block_2e8(chip8)?,
+            0x2EC if unchanged(chip8, 0x2EC, 2) => block_2ec(chip8)?,
+            0x2EE if remaining >= 4 && unchanged(chip8, 0x2EE, 8) => block_2ee(chip8)?,
+            0x2F6 if remaining >= 11 && unchanged(chip8, 0x2F6, 22) => block_2f6(chip8)?,
+            _ => {
+                chip8.cycle()?;
+                1
+            },
+        };
+    }
+    chip8.timer_cycle();
+    Ok(())
+}
+
+fn block_200<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x200: 6E05
+        m.v_registers[0xE] = 0x05;
+        // 0x202: 6500
+        m.v_registers[0x5] = 0x00;
+        // 0x204: 6B06
+        m.v_registers[0xB] = 0x06;
+        m.program_counter = 0x206;
+        Ok(())
+    })?;
+    Ok(3)
+}
+
+fn block_206<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x206: 6A00
+        m.v_registers[0xA] = 0x00;
+        m.program_counter = 0x208;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_208<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x208: A30C
+        m.i_register = 0x30C;
+        // 0x20A: DAB1
+        let (x, y) = (m.v_registers[0xA], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..1u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x20C: 7A04
+        m.v_registers[0xA] = m.v_registers[0xA].wrapping_add(0x04);
+        // 0x20E: 3A40
+        m.program_counter = if m.v_registers[0xA] == 0x40 { 0x212 } else { 0x210 };
+        Ok(())
+    })?;
+    Ok(4)
+}
+
+fn block_210<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x210: 1208
+        m.program_counter = 0x208;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_212<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x212: 7B02
+        m.v_registers[0xB] = m.v_registers[0xB].wrapping_add(0x02);
+        // 0x214: 3B12
+        m.program_counter = if m.v_registers[0xB] == 0x12 { 0x218 } else { 0x216 };
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_216<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x216: 1206
+        m.program_counter = 0x206;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_218<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x218: 6C20
+        m.v_registers[0xC] = 0x20;
+        // 0x21A: 6D1F
+        m.v_registers[0xD] = 0x1F;
+        // 0x21C: A310
+        m.i_register = 0x310;
+        // 0x21E: DCD1
+        let (x, y) = (m.v_registers[0xC], m.v_registers[0xD]);
+        m.display.begin_draw();
+        for row in 0..1u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x220: 22F6
+        m.call(0x220, 0x2F6)?;
+        Ok(())
+    })?;
+    Ok(5)
+}
+
+fn block_222<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x222: 6000
+        m.v_registers[0x0] = 0x00;
+        // 0x224: 6100
+        m.v_registers[0x1] = 0x00;
+        // 0x226: A312
+        m.i_register = 0x312;
+        // 0x228: D011
+        let (x, y) = (m.v_registers[0x0], m.v_registers[0x1]);
+        m.display.begin_draw();
+        for row in 0..1u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x22A: 7008
+        m.v_registers[0x0] = m.v_registers[0x0].wrapping_add(0x08);
+        // 0x22C: A30E
+        m.i_register = 0x30E;
+        // 0x22E: D011
+        let (x, y) = (m.v_registers[0x0], m.v_registers[0x1]);
+        m.display.begin_draw();
+        for row in 0..1u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        m.program_counter = 0x230;
+        Ok(())
+    })?;
+    Ok(7)
+}
+
+fn block_230<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x230: 6040
+        m.v_registers[0x0] = 0x40;
+        // 0x232: F015
+        m.delay_timer = m.v_registers[0x0];
+        m.program_counter = 0x234;
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_234<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x234: F007
+        m.v_registers[0x0] = m.delay_timer;
+        // 0x236: 3000
+        m.program_counter = if m.v_registers[0x0] == 0x00 { 0x23A } else { 0x238 };
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_238<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x238: 1234
+        m.program_counter = 0x234;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_23a<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x23A: C60F
+        m.v_registers[0x6] = m.random() & 0x0F;
+        // 0x23C: 671E
+        m.v_registers[0x7] = 0x1E;
+        // 0x23E: 6801
+        m.v_registers[0x8] = 0x01;
+        // 0x240: 69FF
+        m.v_registers[0x9] = 0xFF;
+        // 0x242: A30E
+        m.i_register = 0x30E;
+        // 0x244: D671
+        let (x, y) = (m.v_registers[0x6], m.v_registers[0x7]);
+        m.display.begin_draw();
+        for row in 0..1u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        m.program_counter = 0x246;
+        Ok(())
+    })?;
+    Ok(6)
+}
+
+fn block_246<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x246: A310
+        m.i_register = 0x310;
+        // 0x248: DCD1
+        let (x, y) = (m.v_registers[0xC], m.v_registers[0xD]);
+        m.display.begin_draw();
+        for row in 0..1u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x24A: 6004
+        m.v_registers[0x0] = 0x04;
+        // 0x24C: E0A1
+        m.program_counter = if !m.keyboard.is_key_pressed(m.v_registers[0x0]) { 0x250 } else { 0x24E };
+        Ok(())
+    })?;
+    Ok(4)
+}
+
+fn block_24e<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x24E: 7CFE
+        m.v_registers[0xC] = m.v_registers[0xC].wrapping_add(0xFE);
+        m.program_counter = 0x250;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_250<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x250: 6006
+        m.v_registers[0x0] = 0x06;
+        // 0x252: E0A1
+        m.program_counter = if !m.keyboard.is_key_pressed(m.v_registers[0x0]) { 0x256 } else { 0x254 };
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_254<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x254: 7C02
+        m.v_registers[0xC] = m.v_registers[0xC].wrapping_add(0x02);
+        m.program_counter = 0x256;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_256<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x256: 603F
+        m.v_registers[0x0] = 0x3F;
+        // 0x258: 8C02
+        m.v_registers[0xC] &= m.v_registers[0x0];
+        // 0x25A: DCD1
+        let (x, y) = (m.v_registers[0xC], m.v_registers[0xD]);
+        m.display.begin_draw();
+        for row in 0..1u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x25C: A30E
+        m.i_register = 0x30E;
+        // 0x25E: D671
+        let (x, y) = (m.v_registers[0x6], m.v_registers[0x7]);
+        m.display.begin_draw();
+        for row in 0..1u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x260: 8684
+        let (value, carry) = m.v_registers[0x6].overflowing_add(m.v_registers[0x8]);
+        m.v_registers[0x6] = value;
+        m.v_registers[0xF] = carry as u8;
+        // 0x262: 8794
+        let (value, carry) = m.v_registers[0x7].overflowing_add(m.v_registers[0x9]);
+        m.v_registers[0x7] = value;
+        m.v_registers[0xF] = carry as u8;
+        // 0x264: 603F
+        m.v_registers[0x0] = 0x3F;
+        // 0x266: 8602
+        m.v_registers[0x6] &= m.v_registers[0x0];
+        // 0x268: 611F
+        m.v_registers[0x1] = 0x1F;
+        // 0x26A: 8712
+        m.v_registers[0x7] &= m.v_registers[0x1];
+        // 0x26C: 471F
+        m.program_counter = if m.v_registers[0x7] != 0x1F { 0x270 } else { 0x26E };
+        Ok(())
+    })?;
+    Ok(12)
+}
+
+fn block_26e<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x26E: 12AC
+        m.program_counter = 0x2AC;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_270<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x270: 4600
+        m.program_counter = if m.v_registers[0x6] != 0x00 { 0x274 } else { 0x272 };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_272<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x272: 6801
+        m.v_registers[0x8] = 0x01;
+        m.program_counter = 0x274;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_274<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x274: 463F
+        m.program_counter = if m.v_registers[0x6] != 0x3F { 0x278 } else { 0x276 };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_276<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x276: 68FF
+        m.v_registers[0x8] = 0xFF;
+        m.program_counter = 0x278;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_278<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x278: 4700
+        m.program_counter = if m.v_registers[0x7] != 0x00 { 0x27C } else { 0x27A };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_27a<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x27A: 6901
+        m.v_registers[0x9] = 0x01;
+        m.program_counter = 0x27C;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_27c<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x27C: D671
+        let (x, y) = (m.v_registers[0x6], m.v_registers[0x7]);
+        m.display.begin_draw();
+        for row in 0..1u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x27E: 3F01
+        m.program_counter = if m.v_registers[0xF] == 0x01 { 0x282 } else { 0x280 };
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_280<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x280: 12AA
+        m.program_counter = 0x2AA;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_282<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x282: 471F
+        m.program_counter = if m.v_registers[0x7] != 0x1F { 0x286 } else { 0x284 };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_284<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x284: 12AA
+        m.program_counter = 0x2AA;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_286<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x286: 6005
+        m.v_registers[0x0] = 0x05;
+        // 0x288: 8075
+        let (value, borrow) = m.v_registers[0x0].overflowing_sub(m.v_registers[0x7]);
+        m.v_registers[0x0] = value;
+        m.v_registers[0xF] = !borrow as u8;
+        // 0x28A: 3F00
+        m.program_counter = if m.v_registers[0xF] == 0x00 { 0x28E } else { 0x28C };
+        Ok(())
+    })?;
+    Ok(3)
+}
+
+fn block_28c<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x28C: 12AA
+        m.program_counter = 0x2AA;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_28e<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x28E: 6001
+        m.v_registers[0x0] = 0x01;
+        // 0x290: F018
+        m.sound_timer = m.v_registers[0x0];
+        // 0x292: 8060
+        m.v_registers[0x0] = m.v_registers[0x6];
+        // 0x294: 61FC
+        m.v_registers[0x1] = 0xFC;
+        // 0x296: 8012
+        m.v_registers[0x0] &= m.v_registers[0x1];
+        // 0x298: A30C
+        m.i_register = 0x30C;
+        // 0x29A: D071
+        let (x, y) = (m.v_registers[0x0], m.v_registers[0x7]);
+        m.display.begin_draw();
+        for row in 0..1u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x29C: 60FE
+        m.v_registers[0x0] = 0xFE;
+        // 0x29E: 8903
+        m.v_registers[0x9] ^= m.v_registers[0x0];
+        // 0x2A0: 22F6
+        m.call(0x2A0, 0x2F6)?;
+        Ok(())
+    })?;
+    Ok(10)
+}
+
+fn block_2a2<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2A2: 7501
+        m.v_registers[0x5] = m.v_registers[0x5].wrapping_add(0x01);
+        // 0x2A4: 22F6
+        m.call(0x2A4, 0x2F6)?;
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_2a6<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2A6: 4560
+        m.program_counter = if m.v_registers[0x5] != 0x60 { 0x2AA } else { 0x2A8 };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2a8<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2A8: 12DE
+        m.program_counter = 0x2DE;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2aa<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2AA: 1246
+        m.program_counter = 0x246;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2ac<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2AC: 69FF
+        m.v_registers[0x9] = 0xFF;
+        // 0x2AE: 8060
+        m.v_registers[0x0] = m.v_registers[0x6];
+        // 0x2B0: 80C5
+        let (value, borrow) = m.v_registers[0x0].overflowing_sub(m.v_registers[0xC]);
+        m.v_registers[0x0] = value;
+        m.v_registers[0xF] = !borrow as u8;
+        // 0x2B2: 3F01
+        m.program_counter = if m.v_registers[0xF] == 0x01 { 0x2B6 } else { 0x2B4 };
+        Ok(())
+    })?;
+    Ok(4)
+}
+
+fn block_2b4<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2B4: 12CA
+        m.program_counter = 0x2CA;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2b6<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2B6: 6102
+        m.v_registers[0x1] = 0x02;
+        // 0x2B8: 8015
+        let (value, borrow) = m.v_registers[0x0].overflowing_sub(m.v_registers[0x1]);
+        m.v_registers[0x0] = value;
+        m.v_registers[0xF] = !borrow as u8;
+        // 0x2BA: 3F01
+        m.program_counter = if m.v_registers[0xF] == 0x01 { 0x2BE } else { 0x2BC };
+        Ok(())
+    })?;
+    Ok(3)
+}
+
+fn block_2bc<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2BC: 12E0
+        m.program_counter = 0x2E0;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2be<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2BE: 8015
+        let (value, borrow) = m.v_registers[0x0].overflowing_sub(m.v_registers[0x1]);
+        m.v_registers[0x0] = value;
+        m.v_registers[0xF] = !borrow as u8;
+        // 0x2C0: 3F01
+        m.program_counter = if m.v_registers[0xF] == 0x01 { 0x2C4 } else { 0x2C2 };
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_2c2<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2C2: 12EE
+        m.program_counter = 0x2EE;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2c4<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2C4: 8015
+        let (value, borrow) = m.v_registers[0x0].overflowing_sub(m.v_registers[0x1]);
+        m.v_registers[0x0] = value;
+        m.v_registers[0xF] = !borrow as u8;
+        // 0x2C6: 3F01
+        m.program_counter = if m.v_registers[0xF] == 0x01 { 0x2CA } else { 0x2C8 };
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_2c8<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2C8: 12E8
+        m.program_counter = 0x2E8;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2ca<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2CA: 6020
+        m.v_registers[0x0] = 0x20;
+        // 0x2CC: F018
+        m.sound_timer = m.v_registers[0x0];
+        // 0x2CE: A30E
+        m.i_register = 0x30E;
+        // 0x2D0: 7EFF
+        m.v_registers[0xE] = m.v_registers[0xE].wrapping_add(0xFF);
+        // 0x2D2: 80E0
+        m.v_registers[0x0] = m.v_registers[0xE];
+        // 0x2D4: 8004
+        let (value, carry) = m.v_registers[0x0].overflowing_add(m.v_registers[0x0]);
+        m.v_registers[0x0] = value;
+        m.v_registers[0xF] = carry as u8;
+        // 0x2D6: 6100
+        m.v_registers[0x1] = 0x00;
+        // 0x2D8: D011
+        let (x, y) = (m.v_registers[0x0], m.v_registers[0x1]);
+        m.display.begin_draw();
+        for row in 0..1u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x2DA: 3E00
+        m.program_counter = if m.v_registers[0xE] == 0x00 { 0x2DE } else { 0x2DC };
+        Ok(())
+    })?;
+    Ok(9)
+}
+
+fn block_2dc<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2DC: 1230
+        m.program_counter = 0x230;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2de<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2DE: 12DE
+        m.program_counter = 0x2DE;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2e0<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2E0: 78FF
+        m.v_registers[0x8] = m.v_registers[0x8].wrapping_add(0xFF);
+        // 0x2E2: 48FE
+        m.program_counter = if m.v_registers[0x8] != 0xFE { 0x2E6 } else { 0x2E4 };
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_2e4<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2E4: 68FF
+        m.v_registers[0x8] = 0xFF;
+        m.program_counter = 0x2E6;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2e6<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2E6: 12EE
+        m.program_counter = 0x2EE;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2e8<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2E8: 7801
+        m.v_registers[0x8] = m.v_registers[0x8].wrapping_add(0x01);
+        // 0x2EA: 4802
+        m.program_counter = if m.v_registers[0x8] != 0x02 { 0x2EE } else { 0x2EC };
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_2ec<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2EC: 6801
+        m.v_registers[0x8] = 0x01;
+        m.program_counter = 0x2EE;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2ee<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2EE: 6004
+        m.v_registers[0x0] = 0x04;
+        // 0x2F0: F018
+        m.sound_timer = m.v_registers[0x0];
+        // 0x2F2: 69FF
+        m.v_registers[0x9] = 0xFF;
+        // 0x2F4: 1270
+        m.program_counter = 0x270;
+        Ok(())
+    })?;
+    Ok(4)
+}
+
+fn block_2f6<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2F6: A314
+        m.i_register = 0x314;
+        // 0x2F8: F533
+        let value = m.v_registers[0x5];
+        m.memory.write_byte(m.i_register, value / 100);
+        m.memory.write_byte(m.i_register + 1, value / 10 % 10);
+        m.memory.write_byte(m.i_register + 2, value % 10);
+        // 0x2FA: F265
+        for register in 0..=0x2 {
+            m.v_registers[register] = m.memory.read_byte(m.i_register + register as u16);
+        }
+        // 0x2FC: F129
+        m.i_register = 0x050 + m.v_registers[0x1] as u16 * 5;
+        // 0x2FE: 6337
+        m.v_registers[0x3] = 0x37;
+        // 0x300: 6400
+        m.v_registers[0x4] = 0x00;
+        // 0x302: D345
+        let (x, y) = (m.v_registers[0x3], m.v_registers[0x4]);
+        m.display.begin_draw();
+        for row in 0..5u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x304: 7305
+        m.v_registers[0x3] = m.v_registers[0x3].wrapping_add(0x05);
+        // 0x306: F229
+        m.i_register = 0x050 + m.v_registers[0x2] as u16 * 5;
+        // 0x308: D345
+        let (x, y) = (m.v_registers[0x3], m.v_registers[0x4]);
+        m.display.begin_draw();
+        for row in 0..5u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x30A: 00EE
+        m.ret(0x30A)?;
+        Ok(())
+    })?;
+    Ok(11)
+}
@@ -0,0 +1,1411 @@
+// Recompiled from outlaw.ch8 by rust-chip8 --recompile. Each basic block reachable from 0x200
+// through jumps, calls and skips is a native function, with its instructions written out in
+// Rust for the quirks below. BNNN and anything else the analysis couldn't follow are
+// interpreted, as are blocks the ROM has overwritten, blocks that wouldn't fit in what's left
+// of the frame and every instruction when the machine's quirks have been changed.
+
+use rust_chip8::chip8::{self, Chip8};
+use rust_chip8::cpu::Fault;
+use rust_chip8::display::DisplayDriver;
+use rust_chip8::instructions::Instruction;
+use rust_chip8::quirks::Quirks;
+
+pub const QUIRKS: Quirks = Quirks { shift_uses_vy: false, load_store_increments_index: false, logic_resets_vf: false, jump_uses_vx: false, index_overflow_sets_vf: true };
+
+pub const ROM: [u8; 512] = [
+    0x13, 0xB4, 0x38, 0x38, 0x3B, 0x1B, 0x1B, 0x1B, 0xDF, 0xDE, 0xD8, 0xD8, 0xF8, 0x78, 0x18, 0x1C,
+    0x0C, 0x00, 0x00, 0x70, 0xF8, 0x70, 0x67, 0x7C, 0x60, 0x60, 0x78, 0x28, 0xEC, 0x18, 0x3E, 0x1C,
+    0x18, 0x7E, 0x99, 0x99, 0x99, 0x5A, 0x3C, 0x66, 0xC3, 0x18, 0x3E, 0x1C, 0x18, 0x7E, 0x99, 0x99,
+    0x99, 0x5A, 0x3C, 0x24, 0x36, 0x00, 0x00, 0x06, 0x1F, 0x0E, 0xE6, 0x3E, 0x06, 0x06, 0x1E, 0x14,
+    0x37, 0x18, 0x7C, 0x38, 0x18, 0x7E, 0x99, 0x99, 0x99, 0x5A, 0x3C, 0x66, 0xC3, 0x18, 0x7C, 0x38,
+    0x18, 0x7E, 0x99, 0x99, 0x99, 0x5A, 0x3C, 0x24, 0x6C, 0xFF, 0xFF, 0x80, 0x60, 0x06, 0xE0, 0x9E,
+    0x00, 0xEE, 0x69, 0x01, 0x60, 0x05, 0xE0, 0xA1, 0x69, 0x02, 0x60, 0x08, 0xE0, 0xA1, 0x69, 0x03,
+    0x8B, 0xE0, 0x7B, 0x09, 0x8A, 0xD0, 0x7A, 0x05, 0xA2, 0x5B, 0xDB, 0xA1, 0x00, 0xEE, 0xDB, 0xA1,
+    0x7B, 0x01, 0x49, 0x02, 0x7A, 0xFF, 0x49, 0x03, 0x7A, 0x01, 0x4A, 0x01, 0x69, 0x03, 0x4A, 0x1E,
+    0x69, 0x02, 0x4B, 0x3F, 0x69, 0x00, 0x49, 0x00, 0x00, 0xEE, 0xDB, 0xA1, 0x4F, 0x00, 0x00, 0xEE,
+    0x69, 0x00, 0x00, 0xEE, 0x33, 0x00, 0x00, 0xEE, 0x63, 0x01, 0xC0, 0x03, 0x40, 0x01, 0x63, 0x02,
+    0x40, 0x02, 0x63, 0x03, 0x85, 0x80, 0x75, 0xFF, 0x84, 0x70, 0x74, 0x05, 0xA2, 0x5B, 0xD5, 0x41,
+    0x00, 0xEE, 0xD5, 0x41, 0x75, 0xFF, 0x43, 0x02, 0x74, 0xFF, 0x43, 0x03, 0x74, 0x01, 0x44, 0x01,
+    0x63, 0x03, 0x44, 0x1E, 0x63, 0x02, 0x45, 0x00, 0x63, 0x00, 0x43, 0x00, 0x00, 0xEE, 0xD5, 0x41,
+    0x4F, 0x00, 0x00, 0xEE, 0x63, 0x00, 0x00, 0xEE, 0x81, 0xE0, 0x82, 0xD0, 0x60, 0x07, 0xE0, 0xA1,
+    0x71, 0xFF, 0x60, 0x09, 0xE0, 0xA1, 0x71, 0x01, 0x60, 0x05, 0xE0, 0xA1, 0x72, 0xFF, 0x60, 0x08,
+    0xE0, 0xA1, 0x72, 0x01, 0x60, 0x00, 0x51, 0xE0, 0x60, 0x18, 0x52, 0xD0, 0x60, 0x18, 0x4C, 0x18,
+    0x60, 0x0C, 0x6F, 0x06, 0xEF, 0xA1, 0x60, 0x00, 0x41, 0x00, 0x61, 0x01, 0x41, 0x15, 0x61, 0x14,
+    0x42, 0x00, 0x62, 0x01, 0x42, 0x12, 0x62, 0x11, 0xA2, 0x11, 0xFC, 0x1E, 0xDE, 0xDC, 0x8E, 0x10,
+    0x8D, 0x20, 0x8C, 0x00, 0xA2, 0x11, 0xFC, 0x1E, 0xDE, 0xDC, 0x00, 0xEE, 0x71, 0xFF, 0x00, 0xEE,
+    0x71, 0x01, 0x00, 0xEE, 0x72, 0xFF, 0x00, 0xEE, 0x72, 0xFF, 0x00, 0xEE, 0x72, 0x01, 0x00, 0xEE,
+    0x72, 0x01, 0x00, 0xEE, 0x80, 0x00, 0x00, 0xEE, 0x22, 0xA4, 0x00, 0xEE, 0xC0, 0x1C, 0xB3, 0x3C,
+    0x81, 0x80, 0x82, 0x70, 0x23, 0x5C, 0x60, 0x00, 0x51, 0x80, 0x60, 0x18, 0x52, 0x70, 0x60, 0x18,
+    0x46, 0x18, 0x60, 0x0C, 0x41, 0x23, 0x61, 0x24, 0x41, 0x38, 0x61, 0x37, 0x42, 0x00, 0x62, 0x01,
+    0x42, 0x12, 0x62, 0x11, 0xA2, 0x35, 0xF6, 0x1E, 0xD8, 0x7C, 0x88, 0x10, 0x87, 0x20, 0x86, 0x00,
+    0xA2, 0x35, 0xF6, 0x1E, 0xD8, 0x7C, 0x00, 0xEE, 0xD8, 0x7C, 0xA2, 0x5B, 0xDB, 0xA1, 0x60, 0x20,
+    0xF0, 0x18, 0xF0, 0x15, 0xF0, 0x07, 0x30, 0x00, 0x13, 0xA4, 0x13, 0xB4, 0xDE, 0xDC, 0xA2, 0x5B,
+    0xD5, 0x41, 0x13, 0x9E, 0x6E, 0x05, 0x6D, 0x0A, 0x6C, 0x00, 0x68, 0x33, 0x67, 0x0A, 0x66, 0x00,
+    0x69, 0x00, 0x63, 0x00, 0x00, 0xE0, 0xA2, 0x02, 0x60, 0x1C, 0x61, 0x09, 0xD0, 0x1F, 0xA2, 0x59,
+    0x60, 0x00, 0x61, 0x1F, 0xD0, 0x12, 0x70, 0x08, 0x30, 0x40, 0x13, 0xD4, 0xA2, 0x11, 0xDE, 0xDC,
+    0xA2, 0x35, 0xD8, 0x7C, 0x22, 0xE8, 0x3F, 0x00, 0x13, 0xAC, 0x23, 0x60, 0x3F, 0x00, 0x13, 0x98,
+    0xA2, 0x5B, 0x39, 0x00, 0x22, 0x7E, 0x49, 0x00, 0x22, 0x5C, 0x33, 0x00, 0x22, 0xC2, 0x13, 0xE4,
+];
+
+pub fn load<D: DisplayDriver>(chip8: &mut Chip8<D>) {
+    chip8.set_quirks(QUIRKS);
+    chip8.load_default_font();
+    chip8.load_rom(&ROM).expect("ROM fits in memory");
+}
+
+fn unchanged<D: DisplayDriver>(chip8: &Chip8<D>, address: u16, length: usize) -> bool {
+    let offset = (address - chip8::PROGRAM_START) as usize;
+    chip8.memory().read_bytes(address, length) == &ROM[offset..offset + length]
+}
+
+// Like Chip8::run_frame, running recompiled blocks wherever they can be
+pub fn run_frame<D: DisplayDriver>(chip8: &mut Chip8<D>, instructions: u32) -> Result<(), Fault> {
+    if chip8.cpu().quirks() != QUIRKS {
+        return chip8.run_frame(instructions);
+    }
+
+    let mut remaining = instructions;
+
+    while remaining > 0 {
+        chip8.precycle();
+
+        remaining -= match chip8.cpu().program_counter() {
+            0x200 if unchanged(chip8, 0x200, 2) => block_200(chip8)?,
+            0x25C if remaining >= 2 && unchanged(chip8, 0x25C, 4) => block_25c(chip8)?,
+            0x260 if unchanged(chip8, 0x260, 2) => block_260(chip8)?,
+            0x262 if remaining >= 3 && unchanged(chip8, 0x262, 6) => block_262(chip8)?,
+            0x268 if unchanged(chip8, 0x268, 2) => block_268(chip8)?,
+            0x26A if remaining >= 2 && unchanged(chip8, 0x26A, 4) => block_26a(chip8)?,
+            0x26E if unchanged(chip8, 0x26E, 2) => block_26e(chip8)?,
+            0x270 if remaining >= 7 && unchanged(chip8, 0x270, 14) => block_270(chip8)?,
+            0x27E if remaining >= 3 && unchanged(chip8, 0x27E, 6) => block_27e(chip8)?,
+            0x284 if unchanged(chip8, 0x284, 2) => block_284(chip8)?,
+            0x286 if unchanged(chip8, 0x286, 2) => block_286(chip8)?,
+            0x288 if unchanged(chip8, 0x288, 2) => block_288(chip8)?,
+            0x28A if unchanged(chip8, 0x28A, 2) => block_28a(chip8)?,
+            0x28C if unchanged(chip8, 0x28C, 2) => block_28c(chip8)?,
+            0x28E if unchanged(chip8, 0x28E, 2) => block_28e(chip8)?,
+            0x290 if unchanged(chip8, 0x290, 2) => block_290(chip8)?,
+            0x292 if unchanged(chip8, 0x292, 2) => block_292(chip8)?,
+            0x294 if unchanged(chip8, 0x294, 2) => block_294(chip8)?,
+            0x296 if unchanged(chip8, 0x296, 2) => block_296(chip8)?,
+            0x298 if unchanged(chip8, 0x298, 2) => block_298(chip8)?,
+            0x29A if remaining >= 2 && unchanged(chip8, 0x29A, 4) => block_29a(chip8)?,
+            0x29E if unchanged(chip8, 0x29E, 2) => block_29e(chip8)?,
+            0x2A0 if remaining >= 2 && unchanged(chip8, 0x2A0, 4) => block_2a0(chip8)?,
+            0x2C2 if remaining >= 3 && unchanged(chip8, 0x2C2, 6) => block_2c2(chip8)?,
+            0x2C8 if unchanged(chip8, 0x2C8, 2) => block_2c8(chip8)?,
+            0x2CA if unchanged(chip8, 0x2CA, 2) => block_2ca(chip8)?,
+            0x2CC if unchanged(chip8, 0x2CC, 2) => block_2cc(chip8)?,
+            0x2CE if unchanged(chip8, 0x2CE, 2) => block_2ce(chip8)?,
+            0x2D0 if unchanged(chip8, 0x2D0, 2) => block_2d0(chip8)?,
+            0x2D2 if unchanged(chip8, 0x2D2, 2) => block_2d2(chip8)?,
+            0x2D4 if unchanged(chip8, 0x2D4, 2) => block_2d4(chip8)?,
+            0x2D6 if unchanged(chip8, 0x2D6, 2) => block_2d6(chip8)?,
+            0x2D8 if unchanged(chip8, 0x2D8, 2) => block_2d8(chip8)?,
+            0x2DA if unchanged(chip8, 0x2DA, 2) => block_2da(chip8)?,
+            0x2DC if unchanged(chip8, 0x2DC, 2) => block_2dc(chip8)?,
+            0x2DE if remaining >= 2 && unchanged(chip8, 0x2DE, 4) => block_2de(chip8)?,
+            0x2E2 if unchanged(chip8, 0x2E2, 2) => block_2e2(chip8)?,
+            0x2E4 if remaining >= 2 && unchanged(chip8, 0x2E4, 4) => block_2e4(chip8)?,
+            0x2E8 if remaining >= 4 && unchanged(chip8, 0x2E8, 8) => block_2e8(chip8)?,
+            0x2F0 if unchanged(chip8, 0x2F0, 2) => block_2f0(chip8)?,
+            0x2F2 if remaining >= 2 && unchanged(chip8, 0x2F2, 4) => block_2f2(chip8)?,
+            0x2F6 if unchanged(chip8, 0x2F6, 2) => block_2f6(chip8)?,
+            0x2F8 if remaining >= 2 && unchanged(chip8, 0x2F8, 4) => block_2f8(chip8)?,
+            0x2FC if unchanged(chip8, 0x2FC, 2) => block_2fc(chip8)?,
+            0x2FE if remaining >= 2 && unchanged(chip8, 0x2FE, 4) => block_2fe(chip8)?,
+            0x302 if unchanged(chip8, 0x302, 2) => block_302(chip8)?,
+            0x304 if remaining >= 2 && unchanged(chip8, 0x304, 4) => block_304(chip8)?,
+            0x308 if unchanged(chip8, 0x308, 2) => block_308(chip8)?,
+            0x30A if unchanged(chip8, 0x30A, 2) => block_30a(chip8)?,
+            0x30C if unchanged(chip8, 0x30C, 2) => block_30c(chip8)?,
+            0x30E if unchanged(chip8, 0x30E, 2) => block_30e(chip8)?,
+            0x310 if unchanged(chip8, 0x310, 2) => block_310(chip8)?,
+            0x312 if remaining >= 2 && unchanged(chip8, 0x312, 4) => block_312(chip8)?,
+            0x316 if unchanged(chip8, 0x316, 2) => block_316(chip8)?,
+            0x318 if unchanged(chip8, 0x318, 2) => block_318(chip8)?,
+            0x31A if unchanged(chip8, 0x31A, 2) => block_31a(chip8)?,
+            0x31C if unchanged(chip8, 0x31C, 2) => block_31c(chip8)?,
+            0x31E if unchanged(chip8, 0x31E, 2) => block_31e(chip8)?,
+            0x320 if unchanged(chip8, 0x320, 2) => block_320(chip8)?,
+            0x322 if unchanged(chip8, 0x322, 2) => block_322(chip8)?,
+            0x324 if unchanged(chip8, 0x324, 2) => block_324(chip8)?,
+            0x326 if unchanged(chip8, 0x326, 2) => block_326(chip8)?,
+            0x328 if remaining >= 10 && unchanged(chip8, 0x328, 20) => block_328(chip8)?,
+            0x35C if remaining >= 2 && unchanged(chip8, 0x35C, 4) => block_35c(chip8)?,
+            0x360 if remaining >= 3 && unchanged(chip8, 0x360, 6) => block_360(chip8)?,
+            0x366 if remaining >= 2 && unchanged(chip8, 0x366, 4) => block_366(chip8)?,
+            0x36A if unchanged(chip8, 0x36A, 2) => block_36a(chip8)?,
+            0x36C if unchanged(chip8, 0x36C, 2) => block_36c(chip8)?,
+            0x36E if unchanged(chip8, 0x36E, 2) => block_36e(chip8)?,
+            0x370 if unchanged(chip8, 0x370, 2) => block_370(chip8)?,
+            0x372 if unchanged(chip8, 0x372, 2) => block_372(chip8)?,
+            0x374 if unchanged(chip8, 0x374, 2) => block_374(chip8)?,
+            0x376 if unchanged(chip8, 0x376, 2) => block_376(chip8)?,
+            0x378 if unchanged(chip8, 0x378, 2) => block_378(chip8)?,
+            0x37A if unchanged(chip8, 0x37A, 2) => block_37a(chip8)?,
+            0x37C if unchanged(chip8, 0x37C, 2) => block_37c(chip8)?,
+            0x37E if unchanged(chip8, 0x37E, 2) => block_37e(chip8)?,
+            0x380 if unchanged(chip8, 0x380, 2) => block_380(chip8)?,
+            0x382 if unchanged(chip8, 0x382, 2) => block_382(chip8)?,
+            0x384 if remaining >= 10 && unchanged(chip8, 0x384, 20) => block_384(chip8)?,
+            0x398 if remaining >= 3 && unchanged(chip8, 0x398, 6) => block_398(chip8)?,
+            0x39E if remaining >= 3 && unchanged(chip8, 0x39E, 6) => block_39e(chip8)?,
+            0x3A4 if remaining >= 2 && unchanged(chip8, 0x3A4, 4) => block_3a4(chip8)?,
+            0x3A8 if unchanged(chip8, 0x3A8, 2) => block_3a8(chip8)?,
+            0x3AA if unchanged(chip8, 0x3AA, 2) => block_3aa(chip8)?,
+            0x3AC if remaining >= 4 && unchanged(chip8, 0x3AC, 8) => block_3ac(chip8)?,
+            0x3B4 if remaining >= 16 && unchanged(chip8, 0x3B4, 32) => block_3b4(chip8)?,
+            0x3D4 if remaining >= 3 && unchanged(chip8, 0x3D4, 6) => block_3d4(chip8)?,
+            0x3DA if unchanged(chip8, 0x3DA, 2) => block_3da(chip8)?,
+            0x3DC if remaining >= 4 && unchanged(chip8, 0x3DC, 8) => block_3dc(chip8)?,
+            0x3E4 if unchanged(chip8, 0x3E4, 2) => block_3e4(chip8)?,
+            0x3E6 if unchanged(chip8, 0x3E6, 2) => block_3e6(chip8)?,
+            0x3E8 if unchanged(chip8, 0x3E8, 2) => block_3e8(chip8)?,
+            0x3EA if unchanged(chip8, 0x3EA, 2) => block_3ea(chip8)?,
+            0x3EC if unchanged(chip8, 0x3EC, 2) => block_3ec(chip8)?,
+            0x3EE if unchanged(chip8, 0x3EE, 2) => block_3ee(chip8)?,
+            0x3F0 if remaining >= 2 && unchanged(chip8, 0x3F0, 4) => block_3f0(chip8)?,
+            0x3F4 if unchanged(chip8, 0x3F4, 2) => block_3f4(chip8)?,
+            0x3F6 if unchanged(chip8, 0x3F6, 2) => block_3f6(chip8)?,
+            0x3F8 if unchanged(chip8, 0x3F8, 2) => block_3f8(chip8)?,
+            0x3FA if unchanged(chip8, 0x3FA, 2) => block_3fa(chip8)?,
+            0x3FC if unchanged(chip8, 0x3FC, 2) => block_3fc(chip8)?,
+            0x3FE if unchanged(chip8, 0x3FE, 2) => block_3fe(chip8)?,
+            _ => {
+                chip8.cycle()?;
+                1
+            },
+        };
+    }
+    chip8.timer_cycle();
+    Ok(())
+}
+
+fn block_200<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x200: 13B4
+        m.program_counter = 0x3B4;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_25c<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x25C: 6006
+        m.v_registers[0x0] = 0x06;
+        // 0x25E: E09E
+        m.program_counter = if m.keyboard.is_key_pressed(m.v_registers[0x0]) { 0x262 } else { 0x260 };
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_260<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x260: 00EE
+        m.ret(0x260)?;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_262<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x262: 6901
+        m.v_registers[0x9] = 0x01;
+        // 0x264: 6005
+        m.v_registers[0x0] = 0x05;
+        // 0x266: E0A1
+        m.program_counter = if !m.keyboard.is_key_pressed(m.v_registers[0x0]) { 0x26A } else { 0x268 };
+        Ok(())
+    })?;
+    Ok(3)
+}
+
+fn block_268<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x268: 6902
+        m.v_registers[0x9] = 0x02;
+        m.program_counter = 0x26A;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_26a<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x26A: 6008
+        m.v_registers[0x0] = 0x08;
+        // 0x26C: E0A1
+        m.program_counter = if !m.keyboard.is_key_pressed(m.v_registers[0x0]) { 0x270 } else { 0x26E };
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_26e<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x26E: 6903
+        m.v_registers[0x9] = 0x03;
+        m.program_counter = 0x270;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_270<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x270: 8BE0
+        m.v_registers[0xB] = m.v_registers[0xE];
+        // 0x272: 7B09
+        m.v_registers[0xB] = m.v_registers[0xB].wrapping_add(0x09);
+        // 0x274: 8AD0
+        m.v_registers[0xA] = m.v_registers[0xD];
+        // 0x276: 7A05
+        m.v_registers[0xA] = m.v_registers[0xA].wrapping_add(0x05);
+        // 0x278: A25B
+        m.i_register = 0x25B;
+        // 0x27A: DBA1
+        let (x, y) = (m.v_registers[0xB], m.v_registers[0xA]);
+        m.display.begin_draw();
+        for row in 0..1u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x27C: 00EE
+        m.ret(0x27C)?;
+        Ok(())
+    })?;
+    Ok(7)
+}
+
+fn block_27e<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x27E: DBA1
+        let (x, y) = (m.v_registers[0xB], m.v_registers[0xA]);
+        m.display.begin_draw();
+        for row in 0..1u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x280: 7B01
+        m.v_registers[0xB] = m.v_registers[0xB].wrapping_add(0x01);
+        // 0x282: 4902
+        m.program_counter = if m.v_registers[0x9] != 0x02 { 0x286 } else { 0x284 };
+        Ok(())
+    })?;
+    Ok(3)
+}
+
+fn block_284<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x284: 7AFF
+        m.v_registers[0xA] = m.v_registers[0xA].wrapping_add(0xFF);
+        m.program_counter = 0x286;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_286<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x286: 4903
+        m.program_counter = if m.v_registers[0x9] != 0x03 { 0x28A } else { 0x288 };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_288<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x288: 7A01
+        m.v_registers[0xA] = m.v_registers[0xA].wrapping_add(0x01);
+        m.program_counter = 0x28A;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_28a<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x28A: 4A01
+        m.program_counter = if m.v_registers[0xA] != 0x01 { 0x28E } else { 0x28C };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_28c<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x28C: 6903
+        m.v_registers[0x9] = 0x03;
+        m.program_counter = 0x28E;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_28e<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x28E: 4A1E
+        m.program_counter = if m.v_registers[0xA] != 0x1E { 0x292 } else { 0x290 };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_290<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x290: 6902
+        m.v_registers[0x9] = 0x02;
+        m.program_counter = 0x292;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_292<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x292: 4B3F
+        m.program_counter = if m.v_registers[0xB] != 0x3F { 0x296 } else { 0x294 };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_294<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x294: 6900
+        m.v_registers[0x9] = 0x00;
+        m.program_counter = 0x296;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_296<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x296: 4900
+        m.program_counter = if m.v_registers[0x9] != 0x00 { 0x29A } else { 0x298 };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_298<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x298: 00EE
+        m.ret(0x298)?;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_29a<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x29A: DBA1
+        let (x, y) = (m.v_registers[0xB], m.v_registers[0xA]);
+        m.display.begin_draw();
+        for row in 0..1u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x29C: 4F00
+        m.program_counter = if m.v_registers[0xF] != 0x00 { 0x2A0 } else { 0x29E };
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_29e<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x29E: 00EE
+        m.ret(0x29E)?;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2a0<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2A0: 6900
+        m.v_registers[0x9] = 0x00;
+        // 0x2A2: 00EE
+        m.ret(0x2A2)?;
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_2c2<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2C2: D541
+        let (x, y) = (m.v_registers[0x5], m.v_registers[0x4]);
+        m.display.begin_draw();
+        for row in 0..1u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x2C4: 75FF
+        m.v_registers[0x5] = m.v_registers[0x5].wrapping_add(0xFF);
+        // 0x2C6: 4302
+        m.program_counter = if m.v_registers[0x3] != 0x02 { 0x2CA } else { 0x2C8 };
+        Ok(())
+    })?;
+    Ok(3)
+}
+
+fn block_2c8<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2C8: 74FF
+        m.v_registers[0x4] = m.v_registers[0x4].wrapping_add(0xFF);
+        m.program_counter = 0x2CA;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2ca<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2CA: 4303
+        m.program_counter = if m.v_registers[0x3] != 0x03 { 0x2CE } else { 0x2CC };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2cc<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2CC: 7401
+        m.v_registers[0x4] = m.v_registers[0x4].wrapping_add(0x01);
+        m.program_counter = 0x2CE;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2ce<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2CE: 4401
+        m.program_counter = if m.v_registers[0x4] != 0x01 { 0x2D2 } else { 0x2D0 };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2d0<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2D0: 6303
+        m.v_registers[0x3] = 0x03;
+        m.program_counter = 0x2D2;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2d2<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2D2: 441E
+        m.program_counter = if m.v_registers[0x4] != 0x1E { 0x2D6 } else { 0x2D4 };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2d4<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2D4: 6302
+        m.v_registers[0x3] = 0x02;
+        m.program_counter = 0x2D6;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2d6<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2D6: 4500
+        m.program_counter = if m.v_registers[0x5] != 0x00 { 0x2DA } else { 0x2D8 };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2d8<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2D8: 6300
+        m.v_registers[0x3] = 0x00;
+        m.program_counter = 0x2DA;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2da<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2DA: 4300
+        m.program_counter = if m.v_registers[0x3] != 0x00 { 0x2DE } else { 0x2DC };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2dc<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2DC: 00EE
+        m.ret(0x2DC)?;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2de<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2DE: D541
+        let (x, y) = (m.v_registers[0x5], m.v_registers[0x4]);
+        m.display.begin_draw();
+        for row in 0..1u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x2E0: 4F00
+        m.program_counter = if m.v_registers[0xF] != 0x00 { 0x2E4 } else { 0x2E2 };
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_2e2<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2E2: 00EE
+        m.ret(0x2E2)?;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2e4<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2E4: 6300
+        m.v_registers[0x3] = 0x00;
+        // 0x2E6: 00EE
+        m.ret(0x2E6)?;
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_2e8<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2E8: 81E0
+        m.v_registers[0x1] = m.v_registers[0xE];
+        // 0x2EA: 82D0
+        m.v_registers[0x2] = m.v_registers[0xD];
+        // 0x2EC: 6007
+        m.v_registers[0x0] = 0x07;
+        // 0x2EE: E0A1
+        m.program_counter = if !m.keyboard.is_key_pressed(m.v_registers[0x0]) { 0x2F2 } else { 0x2F0 };
+        Ok(())
+    })?;
+    Ok(4)
+}
+
+fn block_2f0<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2F0: 71FF
+        m.v_registers[0x1] = m.v_registers[0x1].wrapping_add(0xFF);
+        m.program_counter = 0x2F2;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2f2<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2F2: 6009
+        m.v_registers[0x0] = 0x09;
+        // 0x2F4: E0A1
+        m.program_counter = if !m.keyboard.is_key_pressed(m.v_registers[0x0]) { 0x2F8 } else { 0x2F6 };
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_2f6<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2F6: 7101
+        m.v_registers[0x1] = m.v_registers[0x1].wrapping_add(0x01);
+        m.program_counter = 0x2F8;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2f8<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2F8: 6005
+        m.v_registers[0x0] = 0x05;
+        // 0x2FA: E0A1
+        m.program_counter = if !m.keyboard.is_key_pressed(m.v_registers[0x0]) { 0x2FE } else { 0x2FC };
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_2fc<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2FC: 72FF
+        m.v_registers[0x2] = m.v_registers[0x2].wrapping_add(0xFF);
+        m.program_counter = 0x2FE;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2fe<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2FE: 6008
+        m.v_registers[0x0] = 0x08;
+        // 0x300: E0A1
+        m.program_counter = if !m.keyboard.is_key_pressed(m.v_registers[0x0]) { 0x304 } else { 0x302 };
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_302<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x302: 7201
+        m.v_registers[0x2] = m.v_registers[0x2].wrapping_add(0x01);
+        m.program_counter = 0x304;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_304<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x304: 6000
+        m.v_registers[0x0] = 0x00;
+        // 0x306: 51E0
+        m.program_counter = if m.v_registers[0x1] == m.v_registers[0xE] { 0x30A } else { 0x308 };
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_308<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x308: 6018
+        m.v_registers[0x0] = 0x18;
+        m.program_counter = 0x30A;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_30a<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x30A: 52D0
+        m.program_counter = if m.v_registers[0x2] == m.v_registers[0xD] { 0x30E } else { 0x30C };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_30c<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x30C: 6018
+        m.v_registers[0x0] = 0x18;
+        m.program_counter = 0x30E;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_30e<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x30E: 4C18
+        m.program_counter = if m.v_registers[0xC] != 0x18 { 0x312 } else { 0x310 };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_310<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x310: 600C
+        m.v_registers[0x0] = 0x0C;
+        m.program_counter = 0x312;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_312<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x312: 6F06
+        m.v_registers[0xF] = 0x06;
+        // 0x314: EFA1
+        m.program_counter = if !m.keyboard.is_key_pressed(m.v_registers[0xF]) { 0x318 } else { 0x316 };
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_316<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x316: 6000
+        m.v_registers[0x0] = 0x00;
+        m.program_counter = 0x318;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_318<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x318: 4100
+        m.program_counter = if m.v_registers[0x1] != 0x00 { 0x31C } else { 0x31A };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_31a<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x31A: 6101
+        m.v_registers[0x1] = 0x01;
+        m.program_counter = 0x31C;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_31c<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x31C: 4115
+        m.program_counter = if m.v_registers[0x1] != 0x15 { 0x320 } else { 0x31E };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_31e<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x31E: 6114
+        m.v_registers[0x1] = 0x14;
+        m.program_counter = 0x320;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_320<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x320: 4200
+        m.program_counter = if m.v_registers[0x2] != 0x00 { 0x324 } else { 0x322 };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_322<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x322: 6201
+        m.v_registers[0x2] = 0x01;
+        m.program_counter = 0x324;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_324<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x324: 4212
+        m.program_counter = if m.v_registers[0x2] != 0x12 { 0x328 } else { 0x326 };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_326<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x326: 6211
+        m.v_registers[0x2] = 0x11;
+        m.program_counter = 0x328;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_328<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x328: A211
+        m.i_register = 0x211;
+        // 0x32A: FC1E
+        let index = m.i_register + m.v_registers[0xC] as u16;
+        m.i_register = index & 0xFFF;
+        m.v_registers[0xF] = (index > 0xFFF) as u8;
+        // 0x32C: DEDC
+        let (x, y) = (m.v_registers[0xE], m.v_registers[0xD]);
+        m.display.begin_draw();
+        for row in 0..12u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x32E: 8E10
+        m.v_registers[0xE] = m.v_registers[0x1];
+        // 0x330: 8D20
+        m.v_registers[0xD] = m.v_registers[0x2];
+        // 0x332: 8C00
+        m.v_registers[0xC] = m.v_registers[0x0];
+        // 0x334: A211
+        m.i_register = 0x211;
+        // 0x336: FC1E
+        let index = m.i_register + m.v_registers[0xC] as u16;
+        m.i_register = index & 0xFFF;
+        m.v_registers[0xF] = (index > 0xFFF) as u8;
+        // 0x338: DEDC
+        let (x, y) = (m.v_registers[0xE], m.v_registers[0xD]);
+        m.display.begin_draw();
+        for row in 0..12u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x33A: 00EE
+        m.ret(0x33A)?;
+        Ok(())
+    })?;
+    Ok(10)
+}
+
+fn block_35c<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x35C: C01C
+        m.v_registers[0x0] = m.random() & 0x1C;
+        m.program_counter = 0x35E;
+        Ok(())
+    })?;
+    chip8.execute_at(0x35E, Instruction::JumpWithOffset(828))?; // B33C
+    Ok(2)
+}
+
+fn block_360<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x360: 8180
+        m.v_registers[0x1] = m.v_registers[0x8];
+        // 0x362: 8270
+        m.v_registers[0x2] = m.v_registers[0x7];
+        // 0x364: 235C
+        m.call(0x364, 0x35C)?;
+        Ok(())
+    })?;
+    Ok(3)
+}
+
+fn block_366<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x366: 6000
+        m.v_registers[0x0] = 0x00;
+        // 0x368: 5180
+        m.program_counter = if m.v_registers[0x1] == m.v_registers[0x8] { 0x36C } else { 0x36A };
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_36a<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x36A: 6018
+        m.v_registers[0x0] = 0x18;
+        m.program_counter = 0x36C;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_36c<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x36C: 5270
+        m.program_counter = if m.v_registers[0x2] == m.v_registers[0x7] { 0x370 } else { 0x36E };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_36e<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x36E: 6018
+        m.v_registers[0x0] = 0x18;
+        m.program_counter = 0x370;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_370<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x370: 4618
+        m.program_counter = if m.v_registers[0x6] != 0x18 { 0x374 } else { 0x372 };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_372<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x372: 600C
+        m.v_registers[0x0] = 0x0C;
+        m.program_counter = 0x374;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_374<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x374: 4123
+        m.program_counter = if m.v_registers[0x1] != 0x23 { 0x378 } else { 0x376 };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_376<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x376: 6124
+        m.v_registers[0x1] = 0x24;
+        m.program_counter = 0x378;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_378<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x378: 4138
+        m.program_counter = if m.v_registers[0x1] != 0x38 { 0x37C } else { 0x37A };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_37a<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x37A: 6137
+        m.v_registers[0x1] = 0x37;
+        m.program_counter = 0x37C;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_37c<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x37C: 4200
+        m.program_counter = if m.v_registers[0x2] != 0x00 { 0x380 } else { 0x37E };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_37e<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x37E: 6201
+        m.v_registers[0x2] = 0x01;
+        m.program_counter = 0x380;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_380<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x380: 4212
+        m.program_counter = if m.v_registers[0x2] != 0x12 { 0x384 } else { 0x382 };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_382<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x382: 6211
+        m.v_registers[0x2] = 0x11;
+        m.program_counter = 0x384;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_384<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x384: A235
+        m.i_register = 0x235;
+        // 0x386: F61E
+        let index = m.i_register + m.v_registers[0x6] as u16;
+        m.i_register = index & 0xFFF;
+        m.v_registers[0xF] = (index > 0xFFF) as u8;
+        // 0x388: D87C
+        let (x, y) = (m.v_registers[0x8], m.v_registers[0x7]);
+        m.display.begin_draw();
+        for row in 0..12u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x38A: 8810
+        m.v_registers[0x8] = m.v_registers[0x1];
+        // 0x38C: 8720
+        m.v_registers[0x7] = m.v_registers[0x2];
+        // 0x38E: 8600
+        m.v_registers[0x6] = m.v_registers[0x0];
+        // 0x390: A235
+        m.i_register = 0x235;
+        // 0x392: F61E
+        let index = m.i_register + m.v_registers[0x6] as u16;
+        m.i_register = index & 0xFFF;
+        m.v_registers[0xF] = (index > 0xFFF) as u8;
+        // 0x394: D87C
+        let (x, y) = (m.v_registers[0x8], m.v_registers[0x7]);
+        m.display.begin_draw();
+        for row in 0..12u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x396: 00EE
+        m.ret(0x396)?;
+        Ok(())
+    })?;
+    Ok(10)
+}
+
+fn block_398<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x398: D87C
+        let (x, y) = (m.v_registers[0x8], m.v_registers[0x7]);
+        m.display.begin_draw();
+        for row in 0..12u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x39A: A25B
+        m.i_register = 0x25B;
+        // 0x39C: DBA1
+        let (x, y) = (m.v_registers[0xB], m.v_registers[0xA]);
+        m.display.begin_draw();
+        for row in 0..1u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        m.program_counter = 0x39E;
+        Ok(())
+    })?;
+    Ok(3)
+}
+
+fn block_39e<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x39E: 6020
+        m.v_registers[0x0] = 0x20;
+        // 0x3A0: F018
+        m.sound_timer = m.v_registers[0x0];
+        // 0x3A2: F015
+        m.delay_timer = m.v_registers[0x0];
+        m.program_counter = 0x3A4;
+        Ok(())
+    })?;
+    Ok(3)
+}
+
+fn block_3a4<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3A4: F007
+        m.v_registers[0x0] = m.delay_timer;
+        // 0x3A6: 3000
+        m.program_counter = if m.v_registers[0x0] == 0x00 { 0x3AA } else { 0x3A8 };
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_3a8<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3A8: 13A4
+        m.program_counter = 0x3A4;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_3aa<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3AA: 13B4
+        m.program_counter = 0x3B4;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_3ac<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3AC: DEDC
+        let (x, y) = (m.v_registers[0xE], m.v_registers[0xD]);
+        m.display.begin_draw();
+        for row in 0..12u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x3AE: A25B
+        m.i_register = 0x25B;
+        // 0x3B0: D541
+        let (x, y) = (m.v_registers[0x5], m.v_registers[0x4]);
+        m.display.begin_draw();
+        for row in 0..1u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x3B2: 139E
+        m.program_counter = 0x39E;
+        Ok(())
+    })?;
+    Ok(4)
+}
+
+fn block_3b4<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3B4: 6E05
+        m.v_registers[0xE] = 0x05;
+        // 0x3B6: 6D0A
+        m.v_registers[0xD] = 0x0A;
+        // 0x3B8: 6C00
+        m.v_registers[0xC] = 0x00;
+        // 0x3BA: 6833
+        m.v_registers[0x8] = 0x33;
+        // 0x3BC: 670A
+        m.v_registers[0x7] = 0x0A;
+        // 0x3BE: 6600
+        m.v_registers[0x6] = 0x00;
+        // 0x3C0: 6900
+        m.v_registers[0x9] = 0x00;
+        // 0x3C2: 6300
+        m.v_registers[0x3] = 0x00;
+        // 0x3C4: 00E0
+        m.display.clear();
+        m.display.draw_if_dirty();
+        // 0x3C6: A202
+        m.i_register = 0x202;
+        // 0x3C8: 601C
+        m.v_registers[0x0] = 0x1C;
+        // 0x3CA: 6109
+        m.v_registers[0x1] = 0x09;
+        // 0x3CC: D01F
+        let (x, y) = (m.v_registers[0x0], m.v_registers[0x1]);
+        m.display.begin_draw();
+        for row in 0..15u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x3CE: A259
+        m.i_register = 0x259;
+        // 0x3D0: 6000
+        m.v_registers[0x0] = 0x00;
+        // 0x3D2: 611F
+        m.v_registers[0x1] = 0x1F;
+        m.program_counter = 0x3D4;
+        Ok(())
+    })?;
+    Ok(16)
+}
+
+fn block_3d4<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3D4: D012
+        let (x, y) = (m.v_registers[0x0], m.v_registers[0x1]);
+        m.display.begin_draw();
+        for row in 0..2u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x3D6: 7008
+        m.v_registers[0x0] = m.v_registers[0x0].wrapping_add(0x08);
+        // 0x3D8: 3040
+        m.program_counter = if m.v_registers[0x0] == 0x40 { 0x3DC } else { 0x3DA };
+        Ok(())
+    })?;
+    Ok(3)
+}
+
+fn block_3da<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3DA: 13D4
+        m.program_counter = 0x3D4;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_3dc<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3DC: A211
+        m.i_register = 0x211;
+        // 0x3DE: DEDC
+        let (x, y) = (m.v_registers[0xE], m.v_registers[0xD]);
+        m.display.begin_draw();
+        for row in 0..12u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x3E0: A235
+        m.i_register = 0x235;
+        // 0x3E2: D87C
+        let (x, y) = (m.v_registers[0x8], m.v_registers[0x7]);
+        m.display.begin_draw();
+        for row in 0..12u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        m.program_counter = 0x3E4;
+        Ok(())
+    })?;
+    Ok(4)
+}
+
+fn block_3e4<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3E4: 22E8
+        m.call(0x3E4, 0x2E8)?;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_3e6<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3E6: 3F00
+        m.program_counter = if m.v_registers[0xF] == 0x00 { 0x3EA } else { 0x3E8 };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_3e8<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3E8: 13AC
+        m.program_counter = 0x3AC;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_3ea<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3EA: 2360
+        m.call(0x3EA, 0x360)?;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_3ec<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3EC: 3F00
+        m.program_counter = if m.v_registers[0xF] == 0x00 { 0x3F0 } else { 0x3EE };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_3ee<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3EE: 1398
+        m.program_counter = 0x398;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_3f0<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3F0: A25B
+        m.i_register = 0x25B;
+        // 0x3F2: 3900
+        m.program_counter = if m.v_registers[0x9] == 0x00 { 0x3F6 } else { 0x3F4 };
+        Ok(())
+    })?;
+    Ok(2)
+}
+
+fn block_3f4<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3F4: 227E
+        m.call(0x3F4, 0x27E)?;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_3f6<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3F6: 4900
+        m.program_counter = if m.v_registers[0x9] != 0x00 { 0x3FA } else { 0x3F8 };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_3f8<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3F8: 225C
+        m.call(0x3F8, 0x25C)?;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_3fa<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3FA: 3300
+        m.program_counter = if m.v_registers[0x3] == 0x00 { 0x3FE } else { 0x3FC };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_3fc<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3FC: 22C2
+        m.call(0x3FC, 0x2C2)?;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_3fe<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3FE: 13E4
+        m.program_counter = 0x3E4;
+        Ok(())
+    })?;
+    Ok(1)
+}
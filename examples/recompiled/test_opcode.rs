@@ -0,0 +1,1170 @@
+// Recompiled from test_opcode.ch8 by rust-chip8 --recompile. Each basic block reachable from 0x200
+// through jumps, calls and skips is a native function, with its instructions written out in
+// Rust for the quirks below. BNNN and anything else the analysis couldn't follow are
+// interpreted, as are blocks the ROM has overwritten, blocks that wouldn't fit in what's left
+// of the frame and every instruction when the machine's quirks have been changed.
+
+use rust_chip8::chip8::{self, Chip8};
+use rust_chip8::cpu::Fault;
+use rust_chip8::display::DisplayDriver;
+use rust_chip8::quirks::Quirks;
+
+pub const QUIRKS: Quirks = Quirks { shift_uses_vy: false, load_store_increments_index: false, logic_resets_vf: false, jump_uses_vx: false, index_overflow_sets_vf: true };
+
+pub const ROM: [u8; 478] = [
+    0x12, 0x4E, 0xEA, 0xAC, 0xAA, 0xEA, 0xCE, 0xAA, 0xAA, 0xAE, 0xE0, 0xA0, 0xA0, 0xE0, 0xC0, 0x40,
+    0x40, 0xE0, 0xE0, 0x20, 0xC0, 0xE0, 0xE0, 0x60, 0x20, 0xE0, 0xA0, 0xE0, 0x20, 0x20, 0x60, 0x40,
+    0x20, 0x40, 0xE0, 0x80, 0xE0, 0xE0, 0xE0, 0x20, 0x20, 0x20, 0xE0, 0xE0, 0xA0, 0xE0, 0xE0, 0xE0,
+    0x20, 0xE0, 0x40, 0xA0, 0xE0, 0xA0, 0xE0, 0xC0, 0x80, 0xE0, 0xE0, 0x80, 0xC0, 0x80, 0xA0, 0x40,
+    0xA0, 0xA0, 0xA2, 0x02, 0xDA, 0xB4, 0x00, 0xEE, 0xA2, 0x02, 0xDA, 0xB4, 0x13, 0xDC, 0x68, 0x01,
+    0x69, 0x05, 0x6A, 0x0A, 0x6B, 0x01, 0x65, 0x2A, 0x66, 0x2B, 0xA2, 0x16, 0xD8, 0xB4, 0xA2, 0x3E,
+    0xD9, 0xB4, 0xA2, 0x02, 0x36, 0x2B, 0xA2, 0x06, 0xDA, 0xB4, 0x6B, 0x06, 0xA2, 0x1A, 0xD8, 0xB4,
+    0xA2, 0x3E, 0xD9, 0xB4, 0xA2, 0x06, 0x45, 0x2A, 0xA2, 0x02, 0xDA, 0xB4, 0x6B, 0x0B, 0xA2, 0x1E,
+    0xD8, 0xB4, 0xA2, 0x3E, 0xD9, 0xB4, 0xA2, 0x06, 0x55, 0x60, 0xA2, 0x02, 0xDA, 0xB4, 0x6B, 0x10,
+    0xA2, 0x26, 0xD8, 0xB4, 0xA2, 0x3E, 0xD9, 0xB4, 0xA2, 0x06, 0x76, 0xFF, 0x46, 0x2A, 0xA2, 0x02,
+    0xDA, 0xB4, 0x6B, 0x15, 0xA2, 0x2E, 0xD8, 0xB4, 0xA2, 0x3E, 0xD9, 0xB4, 0xA2, 0x06, 0x95, 0x60,
+    0xA2, 0x02, 0xDA, 0xB4, 0x6B, 0x1A, 0xA2, 0x32, 0xD8, 0xB4, 0xA2, 0x3E, 0xD9, 0xB4, 0x22, 0x42,
+    0x68, 0x17, 0x69, 0x1B, 0x6A, 0x20, 0x6B, 0x01, 0xA2, 0x0A, 0xD8, 0xB4, 0xA2, 0x36, 0xD9, 0xB4,
+    0xA2, 0x02, 0xDA, 0xB4, 0x6B, 0x06, 0xA2, 0x2A, 0xD8, 0xB4, 0xA2, 0x0A, 0xD9, 0xB4, 0xA2, 0x06,
+    0x87, 0x50, 0x47, 0x2A, 0xA2, 0x02, 0xDA, 0xB4, 0x6B, 0x0B, 0xA2, 0x2A, 0xD8, 0xB4, 0xA2, 0x0E,
+    0xD9, 0xB4, 0xA2, 0x06, 0x67, 0x2A, 0x87, 0xB1, 0x47, 0x2B, 0xA2, 0x02, 0xDA, 0xB4, 0x6B, 0x10,
+    0xA2, 0x2A, 0xD8, 0xB4, 0xA2, 0x12, 0xD9, 0xB4, 0xA2, 0x06, 0x66, 0x78, 0x67, 0x1F, 0x87, 0x62,
+    0x47, 0x18, 0xA2, 0x02, 0xDA, 0xB4, 0x6B, 0x15, 0xA2, 0x2A, 0xD8, 0xB4, 0xA2, 0x16, 0xD9, 0xB4,
+    0xA2, 0x06, 0x66, 0x78, 0x67, 0x1F, 0x87, 0x63, 0x47, 0x67, 0xA2, 0x02, 0xDA, 0xB4, 0x6B, 0x1A,
+    0xA2, 0x2A, 0xD8, 0xB4, 0xA2, 0x1A, 0xD9, 0xB4, 0xA2, 0x06, 0x66, 0x8C, 0x67, 0x8C, 0x87, 0x64,
+    0x47, 0x18, 0xA2, 0x02, 0xDA, 0xB4, 0x68, 0x2C, 0x69, 0x30, 0x6A, 0x34, 0x6B, 0x01, 0xA2, 0x2A,
+    0xD8, 0xB4, 0xA2, 0x1E, 0xD9, 0xB4, 0xA2, 0x06, 0x66, 0x8C, 0x67, 0x78, 0x87, 0x65, 0x47, 0xEC,
+    0xA2, 0x02, 0xDA, 0xB4, 0x6B, 0x06, 0xA2, 0x2A, 0xD8, 0xB4, 0xA2, 0x22, 0xD9, 0xB4, 0xA2, 0x06,
+    0x66, 0xE0, 0x86, 0x6E, 0x46, 0xC0, 0xA2, 0x02, 0xDA, 0xB4, 0x6B, 0x0B, 0xA2, 0x2A, 0xD8, 0xB4,
+    0xA2, 0x36, 0xD9, 0xB4, 0xA2, 0x06, 0x66, 0x0F, 0x86, 0x66, 0x46, 0x07, 0xA2, 0x02, 0xDA, 0xB4,
+    0x6B, 0x10, 0xA2, 0x3A, 0xD8, 0xB4, 0xA2, 0x1E, 0xD9, 0xB4, 0xA3, 0xE8, 0x60, 0x00, 0x61, 0x30,
+    0xF1, 0x55, 0xA3, 0xE9, 0xF0, 0x65, 0xA2, 0x06, 0x40, 0x30, 0xA2, 0x02, 0xDA, 0xB4, 0x6B, 0x15,
+    0xA2, 0x3A, 0xD8, 0xB4, 0xA2, 0x16, 0xD9, 0xB4, 0xA3, 0xE8, 0x66, 0x89, 0xF6, 0x33, 0xF2, 0x65,
+    0xA2, 0x02, 0x30, 0x01, 0xA2, 0x06, 0x31, 0x03, 0xA2, 0x06, 0x32, 0x07, 0xA2, 0x06, 0xDA, 0xB4,
+    0x6B, 0x1A, 0xA2, 0x0E, 0xD8, 0xB4, 0xA2, 0x3E, 0xD9, 0xB4, 0x12, 0x48, 0x13, 0xDC,
+];
+
+pub fn load<D: DisplayDriver>(chip8: &mut Chip8<D>) {
+    chip8.set_quirks(QUIRKS);
+    chip8.load_default_font();
+    chip8.load_rom(&ROM).expect("ROM fits in memory");
+}
+
+fn unchanged<D: DisplayDriver>(chip8: &Chip8<D>, address: u16, length: usize) -> bool {
+    let offset = (address - chip8::PROGRAM_START) as usize;
+    chip8.memory().read_bytes(address, length) == &ROM[offset..offset + length]
+}
+
+// Like Chip8::run_frame, running recompiled blocks wherever they can be
+pub fn run_frame<D: DisplayDriver>(chip8: &mut Chip8<D>, instructions: u32) -> Result<(), Fault> {
+    if chip8.cpu().quirks() != QUIRKS {
+        return chip8.run_frame(instructions);
+    }
+
+    let mut remaining = instructions;
+
+    while remaining > 0 {
+        chip8.precycle();
+
+        remaining -= match chip8.cpu().program_counter() {
+            0x200 if unchanged(chip8, 0x200, 2) => block_200(chip8)?,
+            0x242 if remaining >= 3 && unchanged(chip8, 0x242, 6) => block_242(chip8)?,
+            0x248 if remaining >= 3 && unchanged(chip8, 0x248, 6) => block_248(chip8)?,
+            0x24E if remaining >= 12 && unchanged(chip8, 0x24E, 24) => block_24e(chip8)?,
+            0x266 if unchanged(chip8, 0x266, 2) => block_266(chip8)?,
+            0x268 if remaining >= 8 && unchanged(chip8, 0x268, 16) => block_268(chip8)?,
+            0x278 if unchanged(chip8, 0x278, 2) => block_278(chip8)?,
+            0x27A if remaining >= 8 && unchanged(chip8, 0x27A, 16) => block_27a(chip8)?,
+            0x28A if unchanged(chip8, 0x28A, 2) => block_28a(chip8)?,
+            0x28C if remaining >= 9 && unchanged(chip8, 0x28C, 18) => block_28c(chip8)?,
+            0x29E if unchanged(chip8, 0x29E, 2) => block_29e(chip8)?,
+            0x2A0 if remaining >= 8 && unchanged(chip8, 0x2A0, 16) => block_2a0(chip8)?,
+            0x2B0 if unchanged(chip8, 0x2B0, 2) => block_2b0(chip8)?,
+            0x2B2 if remaining >= 7 && unchanged(chip8, 0x2B2, 14) => block_2b2(chip8)?,
+            0x2C0 if remaining >= 18 && unchanged(chip8, 0x2C0, 36) => block_2c0(chip8)?,
+            0x2E4 if unchanged(chip8, 0x2E4, 2) => block_2e4(chip8)?,
+            0x2E6 if remaining >= 10 && unchanged(chip8, 0x2E6, 20) => block_2e6(chip8)?,
+            0x2FA if unchanged(chip8, 0x2FA, 2) => block_2fa(chip8)?,
+            0x2FC if remaining >= 11 && unchanged(chip8, 0x2FC, 22) => block_2fc(chip8)?,
+            0x312 if unchanged(chip8, 0x312, 2) => block_312(chip8)?,
+            0x314 if remaining >= 11 && unchanged(chip8, 0x314, 22) => block_314(chip8)?,
+            0x32A if unchanged(chip8, 0x32A, 2) => block_32a(chip8)?,
+            0x32C if remaining >= 11 && unchanged(chip8, 0x32C, 22) => block_32c(chip8)?,
+            0x342 if unchanged(chip8, 0x342, 2) => block_342(chip8)?,
+            0x344 if remaining >= 14 && unchanged(chip8, 0x344, 28) => block_344(chip8)?,
+            0x360 if unchanged(chip8, 0x360, 2) => block_360(chip8)?,
+            0x362 if remaining >= 10 && unchanged(chip8, 0x362, 20) => block_362(chip8)?,
+            0x376 if unchanged(chip8, 0x376, 2) => block_376(chip8)?,
+            0x378 if remaining >= 10 && unchanged(chip8, 0x378, 20) => block_378(chip8)?,
+            0x38C if unchanged(chip8, 0x38C, 2) => block_38c(chip8)?,
+            0x38E if remaining >= 14 && unchanged(chip8, 0x38E, 28) => block_38e(chip8)?,
+            0x3AA if unchanged(chip8, 0x3AA, 2) => block_3aa(chip8)?,
+            0x3AC if remaining >= 12 && unchanged(chip8, 0x3AC, 24) => block_3ac(chip8)?,
+            0x3C4 if unchanged(chip8, 0x3C4, 2) => block_3c4(chip8)?,
+            0x3C6 if unchanged(chip8, 0x3C6, 2) => block_3c6(chip8)?,
+            0x3C8 if unchanged(chip8, 0x3C8, 2) => block_3c8(chip8)?,
+            0x3CA if unchanged(chip8, 0x3CA, 2) => block_3ca(chip8)?,
+            0x3CC if unchanged(chip8, 0x3CC, 2) => block_3cc(chip8)?,
+            0x3CE if remaining >= 7 && unchanged(chip8, 0x3CE, 14) => block_3ce(chip8)?,
+            0x3DC if unchanged(chip8, 0x3DC, 2) => block_3dc(chip8)?,
+            _ => {
+                chip8.cycle()?;
+                1
+            },
+        };
+    }
+    chip8.timer_cycle();
+    Ok(())
+}
+
+fn block_200<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x200: 124E
+        m.program_counter = 0x24E;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_242<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x242: A202
+        m.i_register = 0x202;
+        // 0x244: DAB4
+        let (x, y) = (m.v_registers[0xA], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x246: 00EE
+        m.ret(0x246)?;
+        Ok(())
+    })?;
+    Ok(3)
+}
+
+fn block_248<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x248: A202
+        m.i_register = 0x202;
+        // 0x24A: DAB4
+        let (x, y) = (m.v_registers[0xA], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x24C: 13DC
+        m.program_counter = 0x3DC;
+        Ok(())
+    })?;
+    Ok(3)
+}
+
+fn block_24e<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x24E: 6801
+        m.v_registers[0x8] = 0x01;
+        // 0x250: 6905
+        m.v_registers[0x9] = 0x05;
+        // 0x252: 6A0A
+        m.v_registers[0xA] = 0x0A;
+        // 0x254: 6B01
+        m.v_registers[0xB] = 0x01;
+        // 0x256: 652A
+        m.v_registers[0x5] = 0x2A;
+        // 0x258: 662B
+        m.v_registers[0x6] = 0x2B;
+        // 0x25A: A216
+        m.i_register = 0x216;
+        // 0x25C: D8B4
+        let (x, y) = (m.v_registers[0x8], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x25E: A23E
+        m.i_register = 0x23E;
+        // 0x260: D9B4
+        let (x, y) = (m.v_registers[0x9], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x262: A202
+        m.i_register = 0x202;
+        // 0x264: 362B
+        m.program_counter = if m.v_registers[0x6] == 0x2B { 0x268 } else { 0x266 };
+        Ok(())
+    })?;
+    Ok(12)
+}
+
+fn block_266<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x266: A206
+        m.i_register = 0x206;
+        m.program_counter = 0x268;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_268<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x268: DAB4
+        let (x, y) = (m.v_registers[0xA], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x26A: 6B06
+        m.v_registers[0xB] = 0x06;
+        // 0x26C: A21A
+        m.i_register = 0x21A;
+        // 0x26E: D8B4
+        let (x, y) = (m.v_registers[0x8], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x270: A23E
+        m.i_register = 0x23E;
+        // 0x272: D9B4
+        let (x, y) = (m.v_registers[0x9], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x274: A206
+        m.i_register = 0x206;
+        // 0x276: 452A
+        m.program_counter = if m.v_registers[0x5] != 0x2A { 0x27A } else { 0x278 };
+        Ok(())
+    })?;
+    Ok(8)
+}
+
+fn block_278<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x278: A202
+        m.i_register = 0x202;
+        m.program_counter = 0x27A;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_27a<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x27A: DAB4
+        let (x, y) = (m.v_registers[0xA], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x27C: 6B0B
+        m.v_registers[0xB] = 0x0B;
+        // 0x27E: A21E
+        m.i_register = 0x21E;
+        // 0x280: D8B4
+        let (x, y) = (m.v_registers[0x8], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x282: A23E
+        m.i_register = 0x23E;
+        // 0x284: D9B4
+        let (x, y) = (m.v_registers[0x9], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x286: A206
+        m.i_register = 0x206;
+        // 0x288: 5560
+        m.program_counter = if m.v_registers[0x5] == m.v_registers[0x6] { 0x28C } else { 0x28A };
+        Ok(())
+    })?;
+    Ok(8)
+}
+
+fn block_28a<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x28A: A202
+        m.i_register = 0x202;
+        m.program_counter = 0x28C;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_28c<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x28C: DAB4
+        let (x, y) = (m.v_registers[0xA], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x28E: 6B10
+        m.v_registers[0xB] = 0x10;
+        // 0x290: A226
+        m.i_register = 0x226;
+        // 0x292: D8B4
+        let (x, y) = (m.v_registers[0x8], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x294: A23E
+        m.i_register = 0x23E;
+        // 0x296: D9B4
+        let (x, y) = (m.v_registers[0x9], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x298: A206
+        m.i_register = 0x206;
+        // 0x29A: 76FF
+        m.v_registers[0x6] = m.v_registers[0x6].wrapping_add(0xFF);
+        // 0x29C: 462A
+        m.program_counter = if m.v_registers[0x6] != 0x2A { 0x2A0 } else { 0x29E };
+        Ok(())
+    })?;
+    Ok(9)
+}
+
+fn block_29e<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x29E: A202
+        m.i_register = 0x202;
+        m.program_counter = 0x2A0;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2a0<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2A0: DAB4
+        let (x, y) = (m.v_registers[0xA], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x2A2: 6B15
+        m.v_registers[0xB] = 0x15;
+        // 0x2A4: A22E
+        m.i_register = 0x22E;
+        // 0x2A6: D8B4
+        let (x, y) = (m.v_registers[0x8], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x2A8: A23E
+        m.i_register = 0x23E;
+        // 0x2AA: D9B4
+        let (x, y) = (m.v_registers[0x9], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x2AC: A206
+        m.i_register = 0x206;
+        // 0x2AE: 9560
+        m.program_counter = if m.v_registers[0x5] != m.v_registers[0x6] { 0x2B2 } else { 0x2B0 };
+        Ok(())
+    })?;
+    Ok(8)
+}
+
+fn block_2b0<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2B0: A202
+        m.i_register = 0x202;
+        m.program_counter = 0x2B2;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2b2<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2B2: DAB4
+        let (x, y) = (m.v_registers[0xA], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x2B4: 6B1A
+        m.v_registers[0xB] = 0x1A;
+        // 0x2B6: A232
+        m.i_register = 0x232;
+        // 0x2B8: D8B4
+        let (x, y) = (m.v_registers[0x8], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x2BA: A23E
+        m.i_register = 0x23E;
+        // 0x2BC: D9B4
+        let (x, y) = (m.v_registers[0x9], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x2BE: 2242
+        m.call(0x2BE, 0x242)?;
+        Ok(())
+    })?;
+    Ok(7)
+}
+
+fn block_2c0<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2C0: 6817
+        m.v_registers[0x8] = 0x17;
+        // 0x2C2: 691B
+        m.v_registers[0x9] = 0x1B;
+        // 0x2C4: 6A20
+        m.v_registers[0xA] = 0x20;
+        // 0x2C6: 6B01
+        m.v_registers[0xB] = 0x01;
+        // 0x2C8: A20A
+        m.i_register = 0x20A;
+        // 0x2CA: D8B4
+        let (x, y) = (m.v_registers[0x8], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x2CC: A236
+        m.i_register = 0x236;
+        // 0x2CE: D9B4
+        let (x, y) = (m.v_registers[0x9], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x2D0: A202
+        m.i_register = 0x202;
+        // 0x2D2: DAB4
+        let (x, y) = (m.v_registers[0xA], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x2D4: 6B06
+        m.v_registers[0xB] = 0x06;
+        // 0x2D6: A22A
+        m.i_register = 0x22A;
+        // 0x2D8: D8B4
+        let (x, y) = (m.v_registers[0x8], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x2DA: A20A
+        m.i_register = 0x20A;
+        // 0x2DC: D9B4
+        let (x, y) = (m.v_registers[0x9], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x2DE: A206
+        m.i_register = 0x206;
+        // 0x2E0: 8750
+        m.v_registers[0x7] = m.v_registers[0x5];
+        // 0x2E2: 472A
+        m.program_counter = if m.v_registers[0x7] != 0x2A { 0x2E6 } else { 0x2E4 };
+        Ok(())
+    })?;
+    Ok(18)
+}
+
+fn block_2e4<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2E4: A202
+        m.i_register = 0x202;
+        m.program_counter = 0x2E6;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2e6<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2E6: DAB4
+        let (x, y) = (m.v_registers[0xA], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x2E8: 6B0B
+        m.v_registers[0xB] = 0x0B;
+        // 0x2EA: A22A
+        m.i_register = 0x22A;
+        // 0x2EC: D8B4
+        let (x, y) = (m.v_registers[0x8], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x2EE: A20E
+        m.i_register = 0x20E;
+        // 0x2F0: D9B4
+        let (x, y) = (m.v_registers[0x9], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x2F2: A206
+        m.i_register = 0x206;
+        // 0x2F4: 672A
+        m.v_registers[0x7] = 0x2A;
+        // 0x2F6: 87B1
+        m.v_registers[0x7] |= m.v_registers[0xB];
+        // 0x2F8: 472B
+        m.program_counter = if m.v_registers[0x7] != 0x2B { 0x2FC } else { 0x2FA };
+        Ok(())
+    })?;
+    Ok(10)
+}
+
+fn block_2fa<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2FA: A202
+        m.i_register = 0x202;
+        m.program_counter = 0x2FC;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_2fc<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x2FC: DAB4
+        let (x, y) = (m.v_registers[0xA], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x2FE: 6B10
+        m.v_registers[0xB] = 0x10;
+        // 0x300: A22A
+        m.i_register = 0x22A;
+        // 0x302: D8B4
+        let (x, y) = (m.v_registers[0x8], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x304: A212
+        m.i_register = 0x212;
+        // 0x306: D9B4
+        let (x, y) = (m.v_registers[0x9], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x308: A206
+        m.i_register = 0x206;
+        // 0x30A: 6678
+        m.v_registers[0x6] = 0x78;
+        // 0x30C: 671F
+        m.v_registers[0x7] = 0x1F;
+        // 0x30E: 8762
+        m.v_registers[0x7] &= m.v_registers[0x6];
+        // 0x310: 4718
+        m.program_counter = if m.v_registers[0x7] != 0x18 { 0x314 } else { 0x312 };
+        Ok(())
+    })?;
+    Ok(11)
+}
+
+fn block_312<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x312: A202
+        m.i_register = 0x202;
+        m.program_counter = 0x314;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_314<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x314: DAB4
+        let (x, y) = (m.v_registers[0xA], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x316: 6B15
+        m.v_registers[0xB] = 0x15;
+        // 0x318: A22A
+        m.i_register = 0x22A;
+        // 0x31A: D8B4
+        let (x, y) = (m.v_registers[0x8], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x31C: A216
+        m.i_register = 0x216;
+        // 0x31E: D9B4
+        let (x, y) = (m.v_registers[0x9], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x320: A206
+        m.i_register = 0x206;
+        // 0x322: 6678
+        m.v_registers[0x6] = 0x78;
+        // 0x324: 671F
+        m.v_registers[0x7] = 0x1F;
+        // 0x326: 8763
+        m.v_registers[0x7] ^= m.v_registers[0x6];
+        // 0x328: 4767
+        m.program_counter = if m.v_registers[0x7] != 0x67 { 0x32C } else { 0x32A };
+        Ok(())
+    })?;
+    Ok(11)
+}
+
+fn block_32a<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x32A: A202
+        m.i_register = 0x202;
+        m.program_counter = 0x32C;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_32c<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x32C: DAB4
+        let (x, y) = (m.v_registers[0xA], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x32E: 6B1A
+        m.v_registers[0xB] = 0x1A;
+        // 0x330: A22A
+        m.i_register = 0x22A;
+        // 0x332: D8B4
+        let (x, y) = (m.v_registers[0x8], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x334: A21A
+        m.i_register = 0x21A;
+        // 0x336: D9B4
+        let (x, y) = (m.v_registers[0x9], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x338: A206
+        m.i_register = 0x206;
+        // 0x33A: 668C
+        m.v_registers[0x6] = 0x8C;
+        // 0x33C: 678C
+        m.v_registers[0x7] = 0x8C;
+        // 0x33E: 8764
+        let (value, carry) = m.v_registers[0x7].overflowing_add(m.v_registers[0x6]);
+        m.v_registers[0x7] = value;
+        m.v_registers[0xF] = carry as u8;
+        // 0x340: 4718
+        m.program_counter = if m.v_registers[0x7] != 0x18 { 0x344 } else { 0x342 };
+        Ok(())
+    })?;
+    Ok(11)
+}
+
+fn block_342<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x342: A202
+        m.i_register = 0x202;
+        m.program_counter = 0x344;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_344<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x344: DAB4
+        let (x, y) = (m.v_registers[0xA], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x346: 682C
+        m.v_registers[0x8] = 0x2C;
+        // 0x348: 6930
+        m.v_registers[0x9] = 0x30;
+        // 0x34A: 6A34
+        m.v_registers[0xA] = 0x34;
+        // 0x34C: 6B01
+        m.v_registers[0xB] = 0x01;
+        // 0x34E: A22A
+        m.i_register = 0x22A;
+        // 0x350: D8B4
+        let (x, y) = (m.v_registers[0x8], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x352: A21E
+        m.i_register = 0x21E;
+        // 0x354: D9B4
+        let (x, y) = (m.v_registers[0x9], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x356: A206
+        m.i_register = 0x206;
+        // 0x358: 668C
+        m.v_registers[0x6] = 0x8C;
+        // 0x35A: 6778
+        m.v_registers[0x7] = 0x78;
+        // 0x35C: 8765
+        let (value, borrow) = m.v_registers[0x7].overflowing_sub(m.v_registers[0x6]);
+        m.v_registers[0x7] = value;
+        m.v_registers[0xF] = !borrow as u8;
+        // 0x35E: 47EC
+        m.program_counter = if m.v_registers[0x7] != 0xEC { 0x362 } else { 0x360 };
+        Ok(())
+    })?;
+    Ok(14)
+}
+
+fn block_360<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x360: A202
+        m.i_register = 0x202;
+        m.program_counter = 0x362;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_362<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x362: DAB4
+        let (x, y) = (m.v_registers[0xA], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x364: 6B06
+        m.v_registers[0xB] = 0x06;
+        // 0x366: A22A
+        m.i_register = 0x22A;
+        // 0x368: D8B4
+        let (x, y) = (m.v_registers[0x8], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x36A: A222
+        m.i_register = 0x222;
+        // 0x36C: D9B4
+        let (x, y) = (m.v_registers[0x9], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x36E: A206
+        m.i_register = 0x206;
+        // 0x370: 66E0
+        m.v_registers[0x6] = 0xE0;
+        // 0x372: 866E
+        let value = m.v_registers[0x6];
+        m.v_registers[0x6] = value << 1;
+        m.v_registers[0xF] = value >> 7;
+        // 0x374: 46C0
+        m.program_counter = if m.v_registers[0x6] != 0xC0 { 0x378 } else { 0x376 };
+        Ok(())
+    })?;
+    Ok(10)
+}
+
+fn block_376<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x376: A202
+        m.i_register = 0x202;
+        m.program_counter = 0x378;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_378<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x378: DAB4
+        let (x, y) = (m.v_registers[0xA], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x37A: 6B0B
+        m.v_registers[0xB] = 0x0B;
+        // 0x37C: A22A
+        m.i_register = 0x22A;
+        // 0x37E: D8B4
+        let (x, y) = (m.v_registers[0x8], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x380: A236
+        m.i_register = 0x236;
+        // 0x382: D9B4
+        let (x, y) = (m.v_registers[0x9], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x384: A206
+        m.i_register = 0x206;
+        // 0x386: 660F
+        m.v_registers[0x6] = 0x0F;
+        // 0x388: 8666
+        let value = m.v_registers[0x6];
+        m.v_registers[0x6] = value >> 1;
+        m.v_registers[0xF] = value & 0x01;
+        // 0x38A: 4607
+        m.program_counter = if m.v_registers[0x6] != 0x07 { 0x38E } else { 0x38C };
+        Ok(())
+    })?;
+    Ok(10)
+}
+
+fn block_38c<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x38C: A202
+        m.i_register = 0x202;
+        m.program_counter = 0x38E;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_38e<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x38E: DAB4
+        let (x, y) = (m.v_registers[0xA], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x390: 6B10
+        m.v_registers[0xB] = 0x10;
+        // 0x392: A23A
+        m.i_register = 0x23A;
+        // 0x394: D8B4
+        let (x, y) = (m.v_registers[0x8], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x396: A21E
+        m.i_register = 0x21E;
+        // 0x398: D9B4
+        let (x, y) = (m.v_registers[0x9], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x39A: A3E8
+        m.i_register = 0x3E8;
+        // 0x39C: 6000
+        m.v_registers[0x0] = 0x00;
+        // 0x39E: 6130
+        m.v_registers[0x1] = 0x30;
+        // 0x3A0: F155
+        for register in 0..=0x1 {
+            m.memory.write_byte(m.i_register + register as u16, m.v_registers[register]);
+        }
+        // 0x3A2: A3E9
+        m.i_register = 0x3E9;
+        // 0x3A4: F065
+        for register in 0..=0x0 {
+            m.v_registers[register] = m.memory.read_byte(m.i_register + register as u16);
+        }
+        // 0x3A6: A206
+        m.i_register = 0x206;
+        // 0x3A8: 4030
+        m.program_counter = if m.v_registers[0x0] != 0x30 { 0x3AC } else { 0x3AA };
+        Ok(())
+    })?;
+    Ok(14)
+}
+
+fn block_3aa<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3AA: A202
+        m.i_register = 0x202;
+        m.program_counter = 0x3AC;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_3ac<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3AC: DAB4
+        let (x, y) = (m.v_registers[0xA], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x3AE: 6B15
+        m.v_registers[0xB] = 0x15;
+        // 0x3B0: A23A
+        m.i_register = 0x23A;
+        // 0x3B2: D8B4
+        let (x, y) = (m.v_registers[0x8], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x3B4: A216
+        m.i_register = 0x216;
+        // 0x3B6: D9B4
+        let (x, y) = (m.v_registers[0x9], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x3B8: A3E8
+        m.i_register = 0x3E8;
+        // 0x3BA: 6689
+        m.v_registers[0x6] = 0x89;
+        // 0x3BC: F633
+        let value = m.v_registers[0x6];
+        m.memory.write_byte(m.i_register, value / 100);
+        m.memory.write_byte(m.i_register + 1, value / 10 % 10);
+        m.memory.write_byte(m.i_register + 2, value % 10);
+        // 0x3BE: F265
+        for register in 0..=0x2 {
+            m.v_registers[register] = m.memory.read_byte(m.i_register + register as u16);
+        }
+        // 0x3C0: A202
+        m.i_register = 0x202;
+        // 0x3C2: 3001
+        m.program_counter = if m.v_registers[0x0] == 0x01 { 0x3C6 } else { 0x3C4 };
+        Ok(())
+    })?;
+    Ok(12)
+}
+
+fn block_3c4<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3C4: A206
+        m.i_register = 0x206;
+        m.program_counter = 0x3C6;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_3c6<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3C6: 3103
+        m.program_counter = if m.v_registers[0x1] == 0x03 { 0x3CA } else { 0x3C8 };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_3c8<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3C8: A206
+        m.i_register = 0x206;
+        m.program_counter = 0x3CA;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_3ca<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3CA: 3207
+        m.program_counter = if m.v_registers[0x2] == 0x07 { 0x3CE } else { 0x3CC };
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_3cc<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3CC: A206
+        m.i_register = 0x206;
+        m.program_counter = 0x3CE;
+        Ok(())
+    })?;
+    Ok(1)
+}
+
+fn block_3ce<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3CE: DAB4
+        let (x, y) = (m.v_registers[0xA], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x3D0: 6B1A
+        m.v_registers[0xB] = 0x1A;
+        // 0x3D2: A20E
+        m.i_register = 0x20E;
+        // 0x3D4: D8B4
+        let (x, y) = (m.v_registers[0x8], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x3D6: A23E
+        m.i_register = 0x23E;
+        // 0x3D8: D9B4
+        let (x, y) = (m.v_registers[0x9], m.v_registers[0xB]);
+        m.display.begin_draw();
+        for row in 0..4u16 {
+            m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);
+        }
+        m.v_registers[0xF] = m.display.did_collide() as u8;
+        m.display.draw_if_dirty();
+        // 0x3DA: 1248
+        m.program_counter = 0x248;
+        Ok(())
+    })?;
+    Ok(7)
+}
+
+fn block_3dc<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {
+    chip8.run_recompiled(|m| {
+        // 0x3DC: 13DC
+        m.program_counter = 0x3DC;
+        Ok(())
+    })?;
+    Ok(1)
+}
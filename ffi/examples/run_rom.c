@@ -0,0 +1,98 @@
+// Runs a ROM through the C API and prints the final screen, optionally holding a key
+// throughout. Halfway through it saves a state, and checks that loading it and running the
+// second half again ends on the same screen. A ROM that halts exits with status 2.
+//
+//   cc -I ffi/include ffi/examples/run_rom.c -L target/debug -lchip8 -o run_rom
+//   LD_LIBRARY_PATH=target/debug ./run_rom roms/BRIX 600 4
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "chip8.h"
+
+static int run_frames(chip8_t *chip8, int frames) {
+    for (int frame = 0; frame < frames; frame++) {
+        int status = chip8_run_frame(chip8);
+        if (status != CHIP8_OK) {
+            return status;
+        }
+    }
+    return CHIP8_OK;
+}
+
+static int halted(chip8_t *chip8, int status) {
+    fprintf(stderr, "Halted with status %d\n", status);
+    chip8_destroy(chip8);
+    return 2;
+}
+
+int main(int argc, char **argv) {
+    if (argc < 3 || argc > 4) {
+        fprintf(stderr, "Usage: %s <rom> <frames> [key]\n", argv[0]);
+        return 1;
+    }
+
+    FILE *file = fopen(argv[1], "rb");
+    if (file == NULL) {
+        perror(argv[1]);
+        return 1;
+    }
+    uint8_t rom[4096];
+    size_t length = fread(rom, 1, sizeof rom, file);
+    fclose(file);
+
+    int frames = atoi(argv[2]);
+    int key = argc == 4 ? atoi(argv[3]) : CHIP8_NO_KEY;
+
+    chip8_t *chip8 = chip8_create(1);
+    if (chip8 == NULL) {
+        fprintf(stderr, "Couldn't create the machine\n");
+        return 1;
+    }
+    if (!chip8_load_rom(chip8, rom, length)) {
+        fprintf(stderr, "%s is too large\n", argv[1]);
+        chip8_destroy(chip8);
+        return 1;
+    }
+    if (!chip8_set_key(chip8, key)) {
+        fprintf(stderr, "Invalid key: %d\n", key);
+        chip8_destroy(chip8);
+        return 1;
+    }
+
+    int status = run_frames(chip8, frames / 2);
+    if (status != CHIP8_OK) {
+        return halted(chip8, status);
+    }
+    uint8_t state[CHIP8_STATE_SIZE];
+    chip8_save_state(chip8, state, sizeof state);
+
+    status = run_frames(chip8, frames - frames / 2);
+    if (status != CHIP8_OK) {
+        return halted(chip8, status);
+    }
+    bool screen[CHIP8_WIDTH * CHIP8_HEIGHT];
+    memcpy(screen, chip8_framebuffer(chip8), sizeof screen);
+
+    chip8_load_state(chip8, state, sizeof state);
+    status = run_frames(chip8, frames - frames / 2);
+    if (status != CHIP8_OK) {
+        return halted(chip8, status);
+    }
+    if (memcmp(screen, chip8_framebuffer(chip8), sizeof screen) != 0) {
+        fprintf(stderr, "Screen differs after loading the saved state\n");
+        chip8_destroy(chip8);
+        return 1;
+    }
+
+    for (int y = 0; y < CHIP8_HEIGHT; y++) {
+        for (int x = 0; x < CHIP8_WIDTH; x++) {
+            putchar(screen[y * CHIP8_WIDTH + x] ? '#' : '.');
+        }
+        putchar('\n');
+    }
+
+    chip8_destroy(chip8);
+    return 0;
+}
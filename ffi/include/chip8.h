@@ -0,0 +1,127 @@
+// Generated from ffi/src/lib.rs by cbindgen. Run UPDATE_GOLDENS=1 cargo test -p rust-chip8-ffi to regenerate.
+
+#ifndef CHIP8_H
+#define CHIP8_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Width of the screen in pixels.
+ */
+#define CHIP8_WIDTH 64
+
+/**
+ * Height of the screen in pixels.
+ */
+#define CHIP8_HEIGHT 32
+
+/**
+ * Size of a save state in bytes.
+ */
+#define CHIP8_STATE_SIZE 4461
+
+/**
+ * Releases the held key in chip8_set_key.
+ */
+#define CHIP8_NO_KEY -1
+
+/**
+ * chip8_run_frame ran the whole frame.
+ */
+#define CHIP8_OK 0
+
+/**
+ * The ROM ran an opcode that isn't an instruction, and the machine has halted on it.
+ */
+#define CHIP8_UNKNOWN_OPCODE 1
+
+/**
+ * The ROM called a subroutine with 16 calls already on the stack, and the machine has halted.
+ */
+#define CHIP8_STACK_OVERFLOW 2
+
+/**
+ * The ROM returned with no call to return to, and the machine has halted.
+ */
+#define CHIP8_STACK_UNDERFLOW 3
+
+/**
+ * The interpreter failed in a way it should never have, leaving the machine unusable. Only
+ * chip8_destroy can still be called on it.
+ */
+#define CHIP8_INTERNAL_ERROR 4
+
+typedef struct chip8_t chip8_t;
+
+/**
+ * Creates a machine with the font loaded and its random number generator seeded with
+ * `seed`. Free it with chip8_destroy. Returns null if it couldn't be created.
+ */
+struct chip8_t *chip8_create(uint64_t seed);
+
+/**
+ * # Safety
+ * `chip8` must be null or come from chip8_create, and isn't valid afterwards.
+ */
+void chip8_destroy(struct chip8_t *chip8);
+
+/**
+ * Copies a ROM into memory at 0x200. Returns false if it doesn't fit.
+ *
+ * # Safety
+ * `chip8` must come from chip8_create, and `rom` point to `length` readable bytes.
+ */
+bool chip8_load_rom(struct chip8_t *chip8, const uint8_t *rom, size_t length);
+
+/**
+ * Runs one 60Hz frame's worth of instructions, then ticks the timers. Returns CHIP8_OK, or
+ * the reason the machine halted partway through. A halted machine stays halted, returning the
+ * same status, until a state is loaded.
+ *
+ * # Safety
+ * `chip8` must come from chip8_create.
+ */
+int32_t chip8_run_frame(struct chip8_t *chip8);
+
+/**
+ * Holds a key from 0 to 15, or releases it with CHIP8_NO_KEY, from the next frame on. Only
+ * one key can be held at once. Returns false for any other value.
+ *
+ * # Safety
+ * `chip8` must come from chip8_create.
+ */
+bool chip8_set_key(struct chip8_t *chip8, int32_t key);
+
+/**
+ * The screen as CHIP8_WIDTH * CHIP8_HEIGHT pixels, a row at a time from the top left, true
+ * where lit. It changes as the machine runs, and is freed with the machine. Null once the
+ * machine is unusable.
+ *
+ * # Safety
+ * `chip8` must come from chip8_create.
+ */
+const bool *chip8_framebuffer(const struct chip8_t *chip8);
+
+/**
+ * Saves the machine into `buffer`, which needs CHIP8_STATE_SIZE bytes. Quirks and other
+ * settings aren't included. Returns false if the buffer is too small.
+ *
+ * # Safety
+ * `chip8` must come from chip8_create, and `buffer` point to `length` writable bytes.
+ */
+bool chip8_save_state(const struct chip8_t *chip8, uint8_t *buffer, size_t length);
+
+/**
+ * Restores a state saved by chip8_save_state, including the held key. Returns false, leaving
+ * the machine as it was, if the buffer is too small or isn't a save state.
+ *
+ * # Safety
+ * `chip8` must come from chip8_create, and `buffer` point to `length` readable bytes.
+ */
+bool chip8_load_state(struct chip8_t *chip8, const uint8_t *buffer, size_t length);
+
+#endif  /* CHIP8_H */
@@ -0,0 +1,192 @@
+// The C API, for embedding the interpreter in C and C++ tools. include/chip8.h is generated
+// from this file, so the doc comments here are the header's documentation.
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+
+use rust_chip8::chip8::{self, Chip8};
+use rust_chip8::cpu::Fault;
+use rust_chip8::display;
+use rust_chip8::null_display_driver::NullDisplayDriver;
+use rust_chip8::state;
+
+/// Width of the screen in pixels.
+pub const CHIP8_WIDTH: usize = 64;
+
+/// Height of the screen in pixels.
+pub const CHIP8_HEIGHT: usize = 32;
+
+/// Size of a save state in bytes.
+pub const CHIP8_STATE_SIZE: usize = 4461;
+
+/// Releases the held key in chip8_set_key.
+pub const CHIP8_NO_KEY: i32 = -1;
+
+/// chip8_run_frame ran the whole frame.
+pub const CHIP8_OK: i32 = 0;
+
+/// The ROM ran an opcode that isn't an instruction, and the machine has halted on it.
+pub const CHIP8_UNKNOWN_OPCODE: i32 = 1;
+
+/// The ROM called a subroutine with 16 calls already on the stack, and the machine has halted.
+pub const CHIP8_STACK_OVERFLOW: i32 = 2;
+
+/// The ROM returned with no call to return to, and the machine has halted.
+pub const CHIP8_STACK_UNDERFLOW: i32 = 3;
+
+/// The interpreter failed in a way it should never have, leaving the machine unusable. Only
+/// chip8_destroy can still be called on it.
+pub const CHIP8_INTERNAL_ERROR: i32 = 4;
+
+// cbindgen only exports literals, so these are checked against the values they copy
+const _: () = assert!(CHIP8_WIDTH == display::WIDTH && CHIP8_HEIGHT == display::HEIGHT);
+const _: () = assert!(CHIP8_STATE_SIZE == state::SIZE);
+
+// Exported to C as the opaque chip8_t
+pub struct Machine {
+    chip8: Chip8<NullDisplayDriver>,
+    key: Option<u8>,
+    // Set once a panic has been caught, after which the machine can't be trusted
+    broken: bool,
+}
+
+// A panic can't unwind into C, so each export catches it and returns `failed` instead
+fn guard<T>(failed: T, f: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(failed)
+}
+
+// Like guard, for exports using a machine, which is marked broken if they panic
+unsafe fn guard_machine<T>(chip8: *mut Machine, failed: T, f: impl FnOnce(&mut Machine) -> T) -> T {
+    let machine = &mut *chip8;
+    if machine.broken {
+        return failed;
+    }
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| f(machine)));
+    result.unwrap_or_else(|_| {
+        machine.broken = true;
+        failed
+    })
+}
+
+/// Creates a machine with the font loaded and its random number generator seeded with
+/// `seed`. Free it with chip8_destroy. Returns null if it couldn't be created.
+#[no_mangle]
+pub extern "C" fn chip8_create(seed: u64) -> *mut Machine {
+    guard(ptr::null_mut(), || {
+        let mut chip8 = Chip8::new(NullDisplayDriver::new());
+        chip8.seed_rng(seed);
+        chip8.load_default_font();
+
+        Box::into_raw(Box::new(Machine { chip8, key: None, broken: false }))
+    })
+}
+
+/// # Safety
+/// `chip8` must be null or come from chip8_create, and isn't valid afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_destroy(chip8: *mut Machine) {
+    if !chip8.is_null() {
+        guard((), || drop(Box::from_raw(chip8)));
+    }
+}
+
+/// Copies a ROM into memory at 0x200. Returns false if it doesn't fit.
+///
+/// # Safety
+/// `chip8` must come from chip8_create, and `rom` point to `length` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_load_rom(chip8: *mut Machine, rom: *const u8, length: usize) -> bool {
+    guard_machine(chip8, false, |machine| machine.chip8.load_rom(slice::from_raw_parts(rom, length)).is_ok())
+}
+
+/// Runs one 60Hz frame's worth of instructions, then ticks the timers. Returns CHIP8_OK, or
+/// the reason the machine halted partway through. A halted machine stays halted, returning the
+/// same status, until a state is loaded.
+///
+/// # Safety
+/// `chip8` must come from chip8_create.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_run_frame(chip8: *mut Machine) -> i32 {
+    guard_machine(chip8, CHIP8_INTERNAL_ERROR, |machine| {
+        match machine.chip8.run_frame_holding(machine.key, chip8::INSTRUCTIONS_PER_FRAME) {
+            Ok(()) => CHIP8_OK,
+            Err(Fault::UnknownOpcode { .. }) => CHIP8_UNKNOWN_OPCODE,
+            Err(Fault::StackOverflow { .. }) => CHIP8_STACK_OVERFLOW,
+            Err(Fault::StackUnderflow { .. }) => CHIP8_STACK_UNDERFLOW,
+        }
+    })
+}
+
+/// Holds a key from 0 to 15, or releases it with CHIP8_NO_KEY, from the next frame on. Only
+/// one key can be held at once. Returns false for any other value.
+///
+/// # Safety
+/// `chip8` must come from chip8_create.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_set_key(chip8: *mut Machine, key: i32) -> bool {
+    guard_machine(chip8, false, |machine| {
+        machine.key = match key {
+            CHIP8_NO_KEY => None,
+            0..=15 => Some(key as u8),
+            _ => return false,
+        };
+        true
+    })
+}
+
+/// The screen as CHIP8_WIDTH * CHIP8_HEIGHT pixels, a row at a time from the top left, true
+/// where lit. It changes as the machine runs, and is freed with the machine. Null once the
+/// machine is unusable.
+///
+/// # Safety
+/// `chip8` must come from chip8_create.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_framebuffer(chip8: *const Machine) -> *const bool {
+    let machine = &*chip8;
+    if machine.broken {
+        return ptr::null();
+    }
+    guard(ptr::null(), || machine.chip8.framebuffer().as_ptr())
+}
+
+/// Saves the machine into `buffer`, which needs CHIP8_STATE_SIZE bytes. Quirks and other
+/// settings aren't included. Returns false if the buffer is too small.
+///
+/// # Safety
+/// `chip8` must come from chip8_create, and `buffer` point to `length` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_save_state(chip8: *const Machine, buffer: *mut u8, length: usize) -> bool {
+    if length < CHIP8_STATE_SIZE {
+        return false;
+    }
+
+    let machine = &*chip8;
+    if machine.broken {
+        return false;
+    }
+    guard(false, || {
+        machine.chip8.save_state(&mut *(buffer as *mut [u8; CHIP8_STATE_SIZE]));
+        true
+    })
+}
+
+/// Restores a state saved by chip8_save_state, including the held key. Returns false, leaving
+/// the machine as it was, if the buffer is too small or isn't a save state.
+///
+/// # Safety
+/// `chip8` must come from chip8_create, and `buffer` point to `length` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_load_state(chip8: *mut Machine, buffer: *const u8, length: usize) -> bool {
+    if length < CHIP8_STATE_SIZE {
+        return false;
+    }
+
+    guard_machine(chip8, false, |machine| {
+        if machine.chip8.load_state(&*(buffer as *const [u8; CHIP8_STATE_SIZE])).is_err() {
+            return false;
+        }
+        machine.key = machine.chip8.key_pressed();
+        true
+    })
+}
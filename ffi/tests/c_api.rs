@@ -0,0 +1,103 @@
+// Checks the generated header is current, and that the C example builds against the library
+// and runs a ROM exactly like the interpreter. Run with UPDATE_GOLDENS=1 to regenerate the header.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use rust_chip8::chip8::{self, Chip8};
+use rust_chip8::display::WIDTH;
+use rust_chip8::null_display_driver::NullDisplayDriver;
+
+fn crate_path(path: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(path)
+}
+
+#[test]
+fn test_header_is_current() {
+    let crate_dir = crate_path("");
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).unwrap();
+    let mut header = Vec::new();
+    cbindgen::generate_with_config(&crate_dir, config).unwrap().write(&mut header);
+    let header = String::from_utf8(header).unwrap();
+
+    let path = crate_path("include/chip8.h");
+    if env::var_os("UPDATE_GOLDENS").is_some() {
+        fs::write(&path, &header).unwrap();
+    }
+
+    assert!(
+        fs::read_to_string(&path).unwrap() == header,
+        "include/chip8.h is out of date, regenerate it with UPDATE_GOLDENS=1 cargo test -p rust-chip8-ffi"
+    );
+}
+
+// Builds examples/run_rom.c against the library as `name`, returning its path. Tests run in
+// parallel, so each needs its own.
+fn compile_example(name: &str) -> PathBuf {
+    // The cdylib is built next to the test binaries
+    let libraries = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    let example = libraries.join(name);
+
+    let compiled = Command::new("cc")
+        .args(["-Wall", "-Werror", "-I"])
+        .arg(crate_path("include"))
+        .arg(crate_path("examples/run_rom.c"))
+        .arg("-L")
+        .arg(&libraries)
+        // An old-style rpath, which wins over the LD_LIBRARY_PATH cargo sets, as that also
+        // holds the copy of the library from the last cargo build
+        .arg(format!("-Wl,--disable-new-dtags,-rpath,{}", libraries.display()))
+        .args(["-lchip8", "-o"])
+        .arg(&example)
+        .status()
+        .unwrap();
+    assert!(compiled.success(), "couldn't compile the C example");
+    example
+}
+
+#[test]
+fn test_c_example_matches_interpreter() {
+    let example = compile_example("run_rom");
+
+    let rom_path = crate_path("../roms/BRIX");
+    let output = Command::new(&example).arg(&rom_path).args(["300", "4"]).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let mut chip8 = Chip8::new(NullDisplayDriver::new());
+    chip8.seed_rng(1);
+    chip8.load_default_font();
+    chip8.load_rom(&fs::read(Path::new(&rom_path)).unwrap()).unwrap();
+    for _ in 0..300 {
+        chip8.run_frame_holding(Some(4), chip8::INSTRUCTIONS_PER_FRAME).unwrap();
+    }
+
+    let expected: String = chip8
+        .framebuffer()
+        .chunks(WIDTH)
+        .map(|row| row.iter().map(|&pixel_on| if pixel_on { '#' } else { '.' }).chain(['\n']).collect::<String>())
+        .collect();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+}
+
+#[test]
+fn test_c_host_survives_faulting_roms() {
+    let example = compile_example("run_faulting_rom");
+    let roms: [(&str, &[u8], i32); 3] = [
+        ("unknown_opcode", &[0xFF, 0xFF], 1),
+        // Calls itself until the stack runs out
+        ("stack_overflow", &[0x22, 0x00], 2),
+        ("stack_underflow", &[0x00, 0xEE], 3),
+    ];
+
+    for (name, rom, status) in roms {
+        let rom_path = env::temp_dir().join(format!("chip8_ffi_{}.ch8", name));
+        fs::write(&rom_path, rom).unwrap();
+        let output = Command::new(&example).arg(&rom_path).arg("10").output().unwrap();
+
+        // A panic unwinding into C would abort instead
+        assert_eq!(output.status.code(), Some(2), "{}: {}", name, String::from_utf8_lossy(&output.stderr));
+        assert_eq!(String::from_utf8(output.stderr).unwrap(), format!("Halted with status {}\n", status), "{}", name);
+    }
+}
@@ -0,0 +1,44 @@
+// Loads a built core the way RetroArch would, runs a ROM headlessly and prints the last frame
+// with its hash, for checking the core outside a real frontend:
+//   retro-frontend target/debug/librust_chip8_libretro.so roms/BRIX 600
+use std::env;
+use std::fs;
+use std::process;
+
+use rust_chip8::display::{self, WIDTH, HEIGHT};
+use rust_chip8_libretro::frontend::{Core, Frontend};
+
+fn run(args: &[String]) -> Result<(), String> {
+    let [core, rom, frames] = args else {
+        return Err("Usage: retro-frontend <core> <rom> <frames>".to_string());
+    };
+    let frames: u32 = frames.parse().map_err(|_| format!("Invalid frame count: {}", frames))?;
+    let rom = fs::read(rom).map_err(|error| format!("Couldn't read {}: {}", rom, error))?;
+
+    let mut frontend = Frontend::new(Core::load(core)?)?;
+    frontend.load_game(&rom)?;
+    frontend.run(frames);
+
+    let frame = frontend.frame().ok_or("Core sent no video")?;
+    if (frame.width, frame.height) != (WIDTH, HEIGHT) {
+        return Err(format!("Core sent a {}x{} frame", frame.width, frame.height));
+    }
+    let pixels: Vec<bool> = frame.pixels.iter().map(|&pixel| pixel & 0xFFFFFF != 0).collect();
+    let pixels = pixels.try_into().unwrap();
+
+    println!("{} ran {} frames, {} audio frames", frontend.library_name(), frames, frontend.audio_frames());
+    for row in frame.pixels.chunks(WIDTH) {
+        println!("{}", row.iter().map(|&pixel| if pixel & 0xFFFFFF != 0 { '#' } else { '.' }).collect::<String>());
+    }
+    println!("Screen hash: {:016x}", display::screen_hash(&pixels));
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if let Err(error) = run(&args) {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
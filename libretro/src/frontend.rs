@@ -0,0 +1,234 @@
+// Just enough of a libretro frontend to check a core works: it loads a ROM, holds buttons,
+// runs frames and keeps what the core sent back. It talks to the core only through the C
+// API, either linked in directly or loaded from a shared library like RetroArch would.
+use std::cell::{Cell, RefCell};
+use std::ffi::{c_uint, c_void, CStr};
+use std::ptr;
+use std::slice;
+
+use libloading::Library;
+
+use crate::*;
+
+pub struct Core {
+    api_version: extern "C" fn() -> c_uint,
+    set_environment: extern "C" fn(EnvironmentFn),
+    set_video_refresh: extern "C" fn(VideoRefreshFn),
+    set_audio_sample_batch: extern "C" fn(AudioSampleBatchFn),
+    set_input_poll: extern "C" fn(InputPollFn),
+    set_input_state: extern "C" fn(InputStateFn),
+    init: extern "C" fn(),
+    deinit: extern "C" fn(),
+    get_system_info: unsafe extern "C" fn(*mut SystemInfo),
+    load_game: unsafe extern "C" fn(*const GameInfo) -> bool,
+    unload_game: extern "C" fn(),
+    run: extern "C" fn(),
+    serialize_size: extern "C" fn() -> usize,
+    serialize: unsafe extern "C" fn(*mut c_void, usize) -> bool,
+    unserialize: unsafe extern "C" fn(*const c_void, usize) -> bool,
+    // Keeps the functions above loaded
+    _library: Option<Library>,
+}
+
+impl Core {
+    // The core in this crate, called directly
+    pub fn linked() -> Core {
+        Core {
+            api_version: retro_api_version,
+            set_environment: retro_set_environment,
+            set_video_refresh: retro_set_video_refresh,
+            set_audio_sample_batch: retro_set_audio_sample_batch,
+            set_input_poll: retro_set_input_poll,
+            set_input_state: retro_set_input_state,
+            init: retro_init,
+            deinit: retro_deinit,
+            get_system_info: retro_get_system_info,
+            load_game: retro_load_game,
+            unload_game: retro_unload_game,
+            run: retro_run,
+            serialize_size: retro_serialize_size,
+            serialize: retro_serialize,
+            unserialize: retro_unserialize,
+            _library: None,
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Core, String> {
+        // Safety: a libretro core's symbols have the signatures in libretro.h
+        unsafe {
+            let library = Library::new(path).map_err(|error| format!("Couldn't load {}: {}", path, error))?;
+
+            Ok(Core {
+                api_version: symbol(&library, path, "retro_api_version")?,
+                set_environment: symbol(&library, path, "retro_set_environment")?,
+                set_video_refresh: symbol(&library, path, "retro_set_video_refresh")?,
+                set_audio_sample_batch: symbol(&library, path, "retro_set_audio_sample_batch")?,
+                set_input_poll: symbol(&library, path, "retro_set_input_poll")?,
+                set_input_state: symbol(&library, path, "retro_set_input_state")?,
+                init: symbol(&library, path, "retro_init")?,
+                deinit: symbol(&library, path, "retro_deinit")?,
+                get_system_info: symbol(&library, path, "retro_get_system_info")?,
+                load_game: symbol(&library, path, "retro_load_game")?,
+                unload_game: symbol(&library, path, "retro_unload_game")?,
+                run: symbol(&library, path, "retro_run")?,
+                serialize_size: symbol(&library, path, "retro_serialize_size")?,
+                serialize: symbol(&library, path, "retro_serialize")?,
+                unserialize: symbol(&library, path, "retro_unserialize")?,
+                _library: Some(library),
+            })
+        }
+    }
+}
+
+// Safety: T has to be the symbol's type
+unsafe fn symbol<T: Copy>(library: &Library, path: &str, name: &str) -> Result<T, String> {
+    library
+        .get::<T>(name.as_bytes())
+        .map(|symbol| *symbol)
+        .map_err(|error| format!("Missing {} in {}: {}", name, path, error))
+}
+
+#[derive(Clone)]
+pub struct Frame {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u32>,
+}
+
+// What the callbacks have been given, per thread as the callbacks can't carry any context
+thread_local! {
+    static FRAME: RefCell<Option<Frame>> = const { RefCell::new(None) };
+    static AUDIO_FRAMES: Cell<usize> = const { Cell::new(0) };
+    static BUTTONS: Cell<u16> = const { Cell::new(0) };
+}
+
+unsafe extern "C" fn environment(cmd: c_uint, data: *mut c_void) -> bool {
+    cmd == RETRO_ENVIRONMENT_SET_PIXEL_FORMAT && *(data as *const c_uint) == RETRO_PIXEL_FORMAT_XRGB8888
+}
+
+unsafe extern "C" fn video_refresh(data: *const c_void, width: c_uint, height: c_uint, pitch: usize) {
+    // Null means the frame hasn't changed
+    if data.is_null() {
+        return;
+    }
+
+    let (width, height) = (width as usize, height as usize);
+    let mut pixels = Vec::with_capacity(width * height);
+    for y in 0..height {
+        let row = (data as *const u8).add(y * pitch) as *const u32;
+        pixels.extend_from_slice(slice::from_raw_parts(row, width));
+    }
+
+    FRAME.with(|frame| *frame.borrow_mut() = Some(Frame { width, height, pixels }));
+}
+
+unsafe extern "C" fn audio_sample_batch(_data: *const i16, frames: usize) -> usize {
+    AUDIO_FRAMES.with(|count| count.set(count.get() + frames));
+    frames
+}
+
+unsafe extern "C" fn input_poll() {}
+
+unsafe extern "C" fn input_state(port: c_uint, device: c_uint, _index: c_uint, id: c_uint) -> i16 {
+    let held = port == 0 && device == RETRO_DEVICE_JOYPAD && id < 16 && BUTTONS.with(Cell::get) & 1 << id != 0;
+    held as i16
+}
+
+pub struct Frontend {
+    core: Core,
+    game_loaded: bool,
+}
+
+impl Frontend {
+    pub fn new(core: Core) -> Result<Frontend, String> {
+        let version = (core.api_version)();
+        if version != RETRO_API_VERSION {
+            return Err(format!("Core uses libretro API version {}, expected {}", version, RETRO_API_VERSION));
+        }
+
+        (core.set_environment)(environment);
+        (core.set_video_refresh)(video_refresh);
+        (core.set_audio_sample_batch)(audio_sample_batch);
+        (core.set_input_poll)(input_poll);
+        (core.set_input_state)(input_state);
+        (core.init)();
+
+        Ok(Frontend { core, game_loaded: false })
+    }
+
+    pub fn library_name(&self) -> String {
+        let mut info = SystemInfo {
+            library_name: ptr::null(),
+            library_version: ptr::null(),
+            valid_extensions: ptr::null(),
+            need_fullpath: false,
+            block_extract: false,
+        };
+        // Safety: the core fills in static strings
+        unsafe {
+            (self.core.get_system_info)(&mut info);
+            CStr::from_ptr(info.library_name).to_string_lossy().into_owned()
+        }
+    }
+
+    pub fn load_game(&mut self, rom: &[u8]) -> Result<(), String> {
+        let game = GameInfo {
+            path: ptr::null(),
+            data: rom.as_ptr() as *const c_void,
+            size: rom.len(),
+            meta: ptr::null(),
+        };
+
+        // Safety: game points to the ROM for the duration of the call, and the core copies it
+        if !unsafe { (self.core.load_game)(&game) } {
+            return Err("Core refused to load the ROM".to_string());
+        }
+        self.game_loaded = true;
+        Ok(())
+    }
+
+    // A bitmask of RetroPad buttons by RETRO_DEVICE_ID_JOYPAD, held until changed
+    pub fn hold(&mut self, buttons: u16) {
+        BUTTONS.with(|held| held.set(buttons));
+    }
+
+    pub fn run(&mut self, frames: u32) {
+        for _ in 0..frames {
+            (self.core.run)();
+        }
+    }
+
+    pub fn frame(&self) -> Option<Frame> {
+        FRAME.with(|frame| frame.borrow().clone())
+    }
+
+    pub fn audio_frames(&self) -> usize {
+        AUDIO_FRAMES.with(Cell::get)
+    }
+
+    pub fn serialize(&self) -> Result<Vec<u8>, String> {
+        let mut state = vec![0; (self.core.serialize_size)()];
+        // Safety: state is as large as the core asked for
+        if !unsafe { (self.core.serialize)(state.as_mut_ptr() as *mut c_void, state.len()) } {
+            return Err("Core failed to save its state".to_string());
+        }
+        Ok(state)
+    }
+
+    pub fn unserialize(&self, state: &[u8]) -> Result<(), String> {
+        // Safety: the core reads at most state.len() bytes
+        if !unsafe { (self.core.unserialize)(state.as_ptr() as *const c_void, state.len()) } {
+            return Err("Core failed to load the state".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Frontend {
+    fn drop(&mut self) {
+        if self.game_loaded {
+            (self.core.unload_game)();
+        }
+        (self.core.deinit)();
+    }
+}
@@ -0,0 +1,389 @@
+// A libretro core, so the emulator runs inside RetroArch and other libretro frontends. Only
+// the parts of the API a CHIP-8 needs are implemented: video, RetroPad input, a beep while the
+// sound timer runs, and save states.
+use std::ffi::{c_char, c_uint, c_void};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rust_chip8::chip8::{self, Chip8};
+use rust_chip8::display::{WIDTH, HEIGHT};
+use rust_chip8::null_display_driver::NullDisplayDriver;
+use rust_chip8::state;
+
+pub mod frontend;
+
+pub const RETRO_API_VERSION: c_uint = 1;
+pub const RETRO_ENVIRONMENT_SET_PIXEL_FORMAT: c_uint = 10;
+pub const RETRO_PIXEL_FORMAT_XRGB8888: c_uint = 1;
+pub const RETRO_DEVICE_JOYPAD: c_uint = 1;
+pub const RETRO_REGION_NTSC: c_uint = 0;
+
+pub const SAMPLE_RATE: u32 = 44100;
+pub const SAMPLES_PER_FRAME: usize = (SAMPLE_RATE / chip8::TIMER_FREQUENCY) as usize;
+
+const TONE_FREQUENCY: u32 = 440;
+const TONE_VOLUME: i16 = 0x1000;
+
+const PIXEL_ON: u32 = 0xFFFFFF;
+const PIXEL_OFF: u32 = 0x000000;
+
+// The CHIP-8 key for each RetroPad button, in RETRO_DEVICE_ID_JOYPAD order. The d-pad and A
+// are the 2/4/6/8 directions and 5 for fire that most games use.
+pub const KEYS: [u8; 16] = [
+    // B, Y, Select, Start, Up, Down, Left, Right
+    0x0, 0x7, 0xC, 0xD, 0x2, 0x8, 0x4, 0x6,
+    // A, X, L, R, L2, R2, L3, R3
+    0x5, 0x9, 0x1, 0x3, 0xA, 0xB, 0xE, 0xF,
+];
+
+pub type EnvironmentFn = unsafe extern "C" fn(cmd: c_uint, data: *mut c_void) -> bool;
+pub type VideoRefreshFn = unsafe extern "C" fn(data: *const c_void, width: c_uint, height: c_uint, pitch: usize);
+pub type AudioSampleFn = unsafe extern "C" fn(left: i16, right: i16);
+pub type AudioSampleBatchFn = unsafe extern "C" fn(data: *const i16, frames: usize) -> usize;
+pub type InputPollFn = unsafe extern "C" fn();
+pub type InputStateFn = unsafe extern "C" fn(port: c_uint, device: c_uint, index: c_uint, id: c_uint) -> i16;
+
+#[repr(C)]
+pub struct SystemInfo {
+    pub library_name: *const c_char,
+    pub library_version: *const c_char,
+    pub valid_extensions: *const c_char,
+    pub need_fullpath: bool,
+    pub block_extract: bool,
+}
+
+#[repr(C)]
+pub struct GameGeometry {
+    pub base_width: c_uint,
+    pub base_height: c_uint,
+    pub max_width: c_uint,
+    pub max_height: c_uint,
+    pub aspect_ratio: f32,
+}
+
+#[repr(C)]
+pub struct SystemTiming {
+    pub fps: f64,
+    pub sample_rate: f64,
+}
+
+#[repr(C)]
+pub struct SystemAvInfo {
+    pub geometry: GameGeometry,
+    pub timing: SystemTiming,
+}
+
+#[repr(C)]
+pub struct GameInfo {
+    pub path: *const c_char,
+    pub data: *const c_void,
+    pub size: usize,
+    pub meta: *const c_char,
+}
+
+#[derive(Clone, Copy)]
+struct Callbacks {
+    environment: Option<EnvironmentFn>,
+    video_refresh: Option<VideoRefreshFn>,
+    audio_sample_batch: Option<AudioSampleBatchFn>,
+    input_poll: Option<InputPollFn>,
+    input_state: Option<InputStateFn>,
+}
+
+struct Core {
+    chip8: Chip8<NullDisplayDriver>,
+    rom: Vec<u8>,
+    video: [u32; WIDTH * HEIGHT],
+    audio: [i16; SAMPLES_PER_FRAME * 2],
+    tone_phase: u32,
+}
+
+impl Core {
+    fn new(rom: Vec<u8>) -> Core {
+        let mut chip8 = Chip8::new(NullDisplayDriver::new());
+        // The core has no entropy source without std, so seed it here
+        let seed = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_nanos() as u64);
+        chip8.seed_rng(seed);
+        chip8.load_default_font();
+        chip8.load_rom(&rom).expect("ROM checked to fit when the game was loaded");
+
+        Core {
+            chip8,
+            rom,
+            video: [PIXEL_OFF; WIDTH * HEIGHT],
+            audio: [0; SAMPLES_PER_FRAME * 2],
+            tone_phase: 0,
+        }
+    }
+
+    fn render_video(&mut self) {
+        for (pixel, &pixel_on) in self.video.iter_mut().zip(self.chip8.framebuffer().iter()) {
+            *pixel = if pixel_on { PIXEL_ON } else { PIXEL_OFF };
+        }
+    }
+
+    // A square wave while the sound timer is running, silence otherwise
+    fn render_audio(&mut self) {
+        let beeping = self.chip8.cpu().sound_timer() > 0;
+        let half_period = SAMPLE_RATE / TONE_FREQUENCY / 2;
+
+        for frame in self.audio.chunks_mut(2) {
+            let sample = if !beeping {
+                0
+            } else if self.tone_phase < half_period {
+                TONE_VOLUME
+            } else {
+                -TONE_VOLUME
+            };
+            frame.fill(sample);
+            self.tone_phase = (self.tone_phase + 1) % (half_period * 2);
+        }
+    }
+}
+
+static CALLBACKS: Mutex<Callbacks> = Mutex::new(Callbacks {
+    environment: None,
+    video_refresh: None,
+    audio_sample_batch: None,
+    input_poll: None,
+    input_state: None,
+});
+
+static CORE: Mutex<Option<Core>> = Mutex::new(None);
+
+fn callbacks() -> Callbacks {
+    *lock(&CALLBACKS)
+}
+
+// A panic caught while the lock was held leaves nothing half-changed that matters: callbacks
+// are replaced whole, and retro_run unloads a core it panicked in
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+// A panic can't unwind into the frontend, so each export catches it and returns `failed` instead
+fn guard<T>(failed: T, f: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(failed)
+}
+
+#[no_mangle]
+pub extern "C" fn retro_api_version() -> c_uint {
+    RETRO_API_VERSION
+}
+
+#[no_mangle]
+pub extern "C" fn retro_set_environment(callback: EnvironmentFn) {
+    guard((), || lock(&CALLBACKS).environment = Some(callback));
+}
+
+#[no_mangle]
+pub extern "C" fn retro_set_video_refresh(callback: VideoRefreshFn) {
+    guard((), || lock(&CALLBACKS).video_refresh = Some(callback));
+}
+
+// Audio goes out a frame at a time through the batch callback
+#[no_mangle]
+pub extern "C" fn retro_set_audio_sample(_callback: AudioSampleFn) {}
+
+#[no_mangle]
+pub extern "C" fn retro_set_audio_sample_batch(callback: AudioSampleBatchFn) {
+    guard((), || lock(&CALLBACKS).audio_sample_batch = Some(callback));
+}
+
+#[no_mangle]
+pub extern "C" fn retro_set_input_poll(callback: InputPollFn) {
+    guard((), || lock(&CALLBACKS).input_poll = Some(callback));
+}
+
+#[no_mangle]
+pub extern "C" fn retro_set_input_state(callback: InputStateFn) {
+    guard((), || lock(&CALLBACKS).input_state = Some(callback));
+}
+
+#[no_mangle]
+pub extern "C" fn retro_set_controller_port_device(_port: c_uint, _device: c_uint) {}
+
+#[no_mangle]
+pub extern "C" fn retro_init() {}
+
+#[no_mangle]
+pub extern "C" fn retro_deinit() {
+    guard((), || *lock(&CORE) = None);
+}
+
+/// # Safety
+/// `info` must point to a writable `retro_system_info`.
+#[no_mangle]
+pub unsafe extern "C" fn retro_get_system_info(info: *mut SystemInfo) {
+    *info = SystemInfo {
+        library_name: c"rust-chip8".as_ptr(),
+        library_version: c"0.1.0".as_ptr(),
+        valid_extensions: c"ch8|c8".as_ptr(),
+        need_fullpath: false,
+        block_extract: false,
+    };
+}
+
+/// # Safety
+/// `info` must point to a writable `retro_system_av_info`.
+#[no_mangle]
+pub unsafe extern "C" fn retro_get_system_av_info(info: *mut SystemAvInfo) {
+    *info = SystemAvInfo {
+        geometry: GameGeometry {
+            base_width: WIDTH as c_uint,
+            base_height: HEIGHT as c_uint,
+            max_width: WIDTH as c_uint,
+            max_height: HEIGHT as c_uint,
+            aspect_ratio: WIDTH as f32 / HEIGHT as f32,
+        },
+        timing: SystemTiming {
+            fps: chip8::TIMER_FREQUENCY as f64,
+            sample_rate: SAMPLE_RATE as f64,
+        },
+    };
+}
+
+/// # Safety
+/// `game` must be null or point to a `retro_game_info` whose data is `size` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn retro_load_game(game: *const GameInfo) -> bool {
+    let Some(game) = game.as_ref() else {
+        return false;
+    };
+    if game.data.is_null() || game.size > chip8::MAX_ROM_SIZE {
+        return false;
+    }
+
+    let Some(environment) = callbacks().environment else {
+        return false;
+    };
+    let mut format = RETRO_PIXEL_FORMAT_XRGB8888;
+    if !environment(RETRO_ENVIRONMENT_SET_PIXEL_FORMAT, &mut format as *mut c_uint as *mut c_void) {
+        return false;
+    }
+
+    guard(false, || {
+        let rom = slice::from_raw_parts(game.data as *const u8, game.size).to_vec();
+        *lock(&CORE) = Some(Core::new(rom));
+        true
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn retro_load_game_special(_game_type: c_uint, _info: *const GameInfo, _num_info: usize) -> bool {
+    false
+}
+
+#[no_mangle]
+pub extern "C" fn retro_unload_game() {
+    guard((), || *lock(&CORE) = None);
+}
+
+#[no_mangle]
+pub extern "C" fn retro_reset() {
+    guard((), || {
+        if let Some(core) = lock(&CORE).as_mut() {
+            *core = Core::new(std::mem::take(&mut core.rom));
+        }
+    });
+}
+
+// A core that panicked mid-frame can't be trusted, so it's unloaded and later frames do nothing
+#[no_mangle]
+pub extern "C" fn retro_run() {
+    if panic::catch_unwind(run_frame).is_err() {
+        *lock(&CORE) = None;
+    }
+}
+
+fn run_frame() {
+    let callbacks = callbacks();
+    let mut core = lock(&CORE);
+    let Some(core) = core.as_mut() else {
+        return;
+    };
+
+    // Safety: the frontend passed these in to be called from retro_run
+    unsafe {
+        if let Some(input_poll) = callbacks.input_poll {
+            input_poll();
+        }
+        // Only one key can be held at once, so the first button down wins
+        let pressed = callbacks.input_state.and_then(|input_state| {
+            (0..KEYS.len()).find(|&id| input_state(0, RETRO_DEVICE_JOYPAD, 0, id as c_uint) != 0).map(|id| KEYS[id])
+        });
+
+        // A halted ROM keeps showing the screen it stopped on
+        let _ = core.chip8.run_frame_holding(pressed, chip8::INSTRUCTIONS_PER_FRAME);
+
+        if let Some(video_refresh) = callbacks.video_refresh {
+            core.render_video();
+            video_refresh(core.video.as_ptr() as *const c_void, WIDTH as c_uint, HEIGHT as c_uint, WIDTH * 4);
+        }
+        if let Some(audio_sample_batch) = callbacks.audio_sample_batch {
+            core.render_audio();
+            audio_sample_batch(core.audio.as_ptr(), SAMPLES_PER_FRAME);
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn retro_serialize_size() -> usize {
+    state::SIZE
+}
+
+/// # Safety
+/// `data` must point to `size` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn retro_serialize(data: *mut c_void, size: usize) -> bool {
+    guard(false, || {
+        let core = lock(&CORE);
+        let (Some(core), true) = (core.as_ref(), size >= state::SIZE) else {
+            return false;
+        };
+
+        let buffer = &mut *(data as *mut [u8; state::SIZE]);
+        core.chip8.save_state(buffer);
+        true
+    })
+}
+
+/// # Safety
+/// `data` must point to `size` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn retro_unserialize(data: *const c_void, size: usize) -> bool {
+    guard(false, || {
+        let mut core = lock(&CORE);
+        let (Some(core), true) = (core.as_mut(), size >= state::SIZE) else {
+            return false;
+        };
+
+        let buffer = &*(data as *const [u8; state::SIZE]);
+        core.chip8.load_state(buffer).is_ok()
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn retro_cheat_reset() {}
+
+#[no_mangle]
+pub extern "C" fn retro_cheat_set(_index: c_uint, _enabled: bool, _code: *const c_char) {}
+
+#[no_mangle]
+pub extern "C" fn retro_get_region() -> c_uint {
+    RETRO_REGION_NTSC
+}
+
+// Memory is only reachable through the interpreter, so none is exposed for cheats or achievements
+#[no_mangle]
+pub extern "C" fn retro_get_memory_data(_id: c_uint) -> *mut c_void {
+    ptr::null_mut()
+}
+
+#[no_mangle]
+pub extern "C" fn retro_get_memory_size(_id: c_uint) -> usize {
+    0
+}
@@ -0,0 +1,93 @@
+// Drives the core through the libretro API with the in-crate test frontend, checking it runs
+// ROMs like the interpreter does and that save states round trip.
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use rust_chip8::chip8::{self, Chip8};
+use rust_chip8::display::{WIDTH, HEIGHT};
+use rust_chip8::null_display_driver::NullDisplayDriver;
+use rust_chip8_libretro::frontend::{Core, Frontend};
+use rust_chip8_libretro::SAMPLES_PER_FRAME;
+
+// The core is a process-wide singleton, as libretro cores are
+static CORE: Mutex<()> = Mutex::new(());
+
+const UP: u16 = 1 << 4;
+const A: u16 = 1 << 8;
+
+fn rom(name: &str) -> Vec<u8> {
+    fs::read(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../roms").join(name)).unwrap()
+}
+
+fn screen(frontend: &Frontend) -> Vec<bool> {
+    let frame = frontend.frame().unwrap();
+    assert_eq!((frame.width, frame.height), (WIDTH, HEIGHT));
+    frame.pixels.iter().map(|&pixel| pixel != 0).collect()
+}
+
+#[test]
+fn test_runs_rom_like_interpreter() {
+    let _core = CORE.lock().unwrap();
+    let rom = rom("test_opcode.ch8");
+
+    let mut frontend = Frontend::new(Core::linked()).unwrap();
+    assert_eq!(frontend.library_name(), "rust-chip8");
+    frontend.load_game(&rom).unwrap();
+    frontend.run(60);
+
+    let mut chip8 = Chip8::new(NullDisplayDriver::new());
+    chip8.load_default_font();
+    chip8.load_rom(&rom).unwrap();
+    for _ in 0..60 {
+        chip8.run_frame(chip8::INSTRUCTIONS_PER_FRAME).unwrap();
+    }
+
+    assert_eq!(screen(&frontend), chip8.framebuffer().to_vec());
+    assert_eq!(frontend.audio_frames(), 60 * SAMPLES_PER_FRAME);
+}
+
+#[test]
+fn test_unserialize_restores_game() {
+    let _core = CORE.lock().unwrap();
+
+    let mut frontend = Frontend::new(Core::linked()).unwrap();
+    frontend.load_game(&rom("BRIX")).unwrap();
+    frontend.run(120);
+
+    let state = frontend.serialize().unwrap();
+    let play = |frontend: &mut Frontend| {
+        for buttons in [A, UP, 0, A] {
+            frontend.hold(buttons);
+            frontend.run(30);
+        }
+        screen(frontend)
+    };
+    let expected = play(&mut frontend);
+
+    frontend.unserialize(&state).unwrap();
+    assert_eq!(play(&mut frontend), expected);
+    assert!(frontend.unserialize(&state[1..]).is_err());
+}
+
+#[test]
+fn test_survives_faulting_rom_and_bad_state() {
+    let _core = CORE.lock().unwrap();
+
+    // Calls itself until the stack runs out, after which the core keeps showing the last frame
+    let mut frontend = Frontend::new(Core::linked()).unwrap();
+    frontend.load_game(&[0x00, 0xE0, 0x22, 0x02]).unwrap();
+    frontend.run(10);
+    assert!(screen(&frontend).iter().all(|&pixel_on| !pixel_on));
+
+    frontend.load_game(&rom("BRIX")).unwrap();
+    frontend.run(60);
+    let state = frontend.serialize().unwrap();
+
+    // The program counter, past the end of memory
+    let mut corrupted = state.clone();
+    corrupted[24] = 0xFF;
+    assert!(frontend.unserialize(&corrupted).is_err());
+    assert_eq!(frontend.serialize().unwrap(), state);
+    frontend.run(60);
+}
@@ -0,0 +1,183 @@
+// A Gym-style environment for driving ROMs from Python, built with maturin. Observations are
+// the screen as a read-only 32x64 buffer of 0s and 1s, which numpy.asarray wraps without copying.
+use std::ffi::{c_char, c_int, c_void};
+use std::ptr;
+
+use pyo3::exceptions::{PyBufferError, PyValueError};
+use pyo3::ffi;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+
+use rust_chip8::chip8::{self, Chip8, RomTooLarge};
+use rust_chip8::display::{WIDTH, HEIGHT};
+use rust_chip8::null_display_driver::NullDisplayDriver;
+use rust_chip8::quirks::Quirks;
+use rust_chip8::state;
+
+static SHAPE: [ffi::Py_ssize_t; 2] = [HEIGHT as ffi::Py_ssize_t, WIDTH as ffi::Py_ssize_t];
+static STRIDES: [ffi::Py_ssize_t; 2] = [WIDTH as ffi::Py_ssize_t, 1];
+
+// A copy of the screen when it was observed, one byte per pixel
+#[pyclass(frozen, module = "chip8_gym")]
+struct Screen {
+    pixels: [u8; WIDTH * HEIGHT],
+}
+
+#[pymethods]
+impl Screen {
+    // Safety: Python passes a view to fill in, which keeps the screen alive through `obj`
+    unsafe fn __getbuffer__(slf: Bound<'_, Self>, view: *mut ffi::Py_buffer, flags: c_int) -> PyResult<()> {
+        if flags & ffi::PyBUF_WRITABLE == ffi::PyBUF_WRITABLE {
+            return Err(PyBufferError::new_err("Screens are read-only"));
+        }
+
+        let view = &mut *view;
+        view.buf = slf.get().pixels.as_ptr() as *mut c_void;
+        view.len = (WIDTH * HEIGHT) as ffi::Py_ssize_t;
+        view.readonly = 1;
+        view.itemsize = 1;
+        view.format = if flags & ffi::PyBUF_FORMAT == ffi::PyBUF_FORMAT { c"B".as_ptr() as *mut c_char } else { ptr::null_mut() };
+        // Without PyBUF_ND the consumer wants plain bytes
+        (view.ndim, view.shape) = if flags & ffi::PyBUF_ND == ffi::PyBUF_ND {
+            (2, SHAPE.as_ptr() as *mut _)
+        } else {
+            (1, ptr::null_mut())
+        };
+        view.strides = if flags & ffi::PyBUF_STRIDES == ffi::PyBUF_STRIDES { STRIDES.as_ptr() as *mut _ } else { ptr::null_mut() };
+        view.suboffsets = ptr::null_mut();
+        view.internal = ptr::null_mut();
+        view.obj = slf.into_any().into_ptr();
+        Ok(())
+    }
+}
+
+// Reward is always 0 and episodes never terminate, as nothing is known about the game being
+// played. Episodes are truncated after `max_steps` steps, if given. Environments stay on the
+// thread that made them, as the machine isn't Sync when built with the JIT.
+#[pyclass(unsendable, module = "chip8_gym")]
+struct Chip8Env {
+    chip8: Chip8<NullDisplayDriver>,
+    rom: Vec<u8>,
+    quirks: Quirks,
+    frame_skip: u32,
+    max_steps: Option<u32>,
+    steps: u32,
+}
+
+impl Chip8Env {
+    fn machine(rom: &[u8], quirks: Quirks, seed: u64) -> Chip8<NullDisplayDriver> {
+        let mut chip8 = Chip8::new(NullDisplayDriver::new());
+        chip8.set_quirks(quirks);
+        chip8.seed_rng(seed);
+        chip8.load_default_font();
+        chip8.load_rom(rom).expect("ROM checked to fit when the environment was made");
+        chip8
+    }
+
+    fn observe(&self) -> Screen {
+        let mut pixels = [0; WIDTH * HEIGHT];
+        for (pixel, &pixel_on) in pixels.iter_mut().zip(self.chip8.framebuffer()) {
+            *pixel = pixel_on as u8;
+        }
+        Screen { pixels }
+    }
+}
+
+#[pymethods]
+impl Chip8Env {
+    #[new]
+    #[pyo3(signature = (rom, frame_skip = 1, quirks = "default", max_steps = None))]
+    fn new(rom: &[u8], frame_skip: u32, quirks: &str, max_steps: Option<u32>) -> PyResult<Chip8Env> {
+        if rom.len() > chip8::MAX_ROM_SIZE {
+            return Err(PyValueError::new_err(RomTooLarge { size: rom.len() }.to_string()));
+        }
+        if frame_skip == 0 {
+            return Err(PyValueError::new_err("frame_skip must be at least 1"));
+        }
+        let quirks = Quirks::named(quirks)
+            .ok_or_else(|| PyValueError::new_err(format!("Unknown quirks profile: {}", quirks)))?;
+
+        Ok(Chip8Env {
+            chip8: Chip8Env::machine(rom, quirks, 0),
+            rom: rom.to_vec(),
+            quirks,
+            frame_skip,
+            max_steps,
+            steps: 0,
+        })
+    }
+
+    // Starts a new episode, seeding the random number generator randomly unless given a seed
+    #[pyo3(signature = (seed = None))]
+    fn reset<'py>(&mut self, py: Python<'py>, seed: Option<u64>) -> (Screen, Bound<'py, PyDict>) {
+        self.chip8 = Chip8Env::machine(&self.rom, self.quirks, seed.unwrap_or_else(rand::random));
+        self.steps = 0;
+        (self.observe(), PyDict::new(py))
+    }
+
+    // Holds `keys` for `frame_skip` frames. The interpreter only models one held key at a time,
+    // so more than one is an error. Returns (observation, reward, terminated, truncated, info).
+    fn step<'py>(&mut self, py: Python<'py>, keys: Vec<u8>) -> PyResult<(Screen, f64, bool, bool, Bound<'py, PyDict>)> {
+        let key = match keys[..] {
+            [] => None,
+            [key] if key < 16 => Some(key),
+            [key] => return Err(PyValueError::new_err(format!("Invalid key: {}", key))),
+            _ => return Err(PyValueError::new_err("Only one key can be held at a time")),
+        };
+
+        let mut fault = None;
+        for _ in 0..self.frame_skip {
+            if let Err(err) = self.chip8.run_frame_holding(key, chip8::INSTRUCTIONS_PER_FRAME) {
+                fault = Some(err);
+                break;
+            }
+        }
+        self.steps += 1;
+
+        // A ROM that has faulted can't go on, which ends the episode
+        let info = PyDict::new(py);
+        if let Some(fault) = fault {
+            info.set_item("fault", fault.to_string())?;
+        }
+        let truncated = self.max_steps.is_some_and(|max_steps| self.steps >= max_steps);
+        Ok((self.observe(), 0.0, fault.is_some(), truncated, info))
+    }
+
+    // The machine and step count, for restore_state to go back to
+    fn clone_state<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        let mut buffer = [0; state::SIZE + 4];
+        let (machine, steps) = buffer.split_at_mut(state::SIZE);
+        self.chip8.save_state(machine.try_into().unwrap());
+        steps.copy_from_slice(&self.steps.to_be_bytes());
+        PyBytes::new(py, &buffer)
+    }
+
+    fn restore_state(&mut self, state: &[u8]) -> PyResult<Screen> {
+        if state.len() != state::SIZE + 4 {
+            return Err(PyValueError::new_err("Not a state from clone_state"));
+        }
+
+        let (machine, steps) = state.split_at(state::SIZE);
+        self.chip8.load_state(machine.try_into().unwrap()).map_err(PyValueError::new_err)?;
+        self.steps = u32::from_be_bytes(steps.try_into().unwrap());
+        Ok(self.observe())
+    }
+
+    fn observation(&self) -> Screen {
+        self.observe()
+    }
+
+    #[getter]
+    fn frame_skip(&self) -> u32 {
+        self.frame_skip
+    }
+}
+
+#[pymodule]
+pub fn chip8_gym(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<Chip8Env>()?;
+    module.add_class::<Screen>()?;
+    module.add("WIDTH", WIDTH)?;
+    module.add("HEIGHT", HEIGHT)?;
+    Ok(())
+}
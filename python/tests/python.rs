@@ -0,0 +1,40 @@
+// Runs the test_ functions in test_env.py against the module, in an embedded interpreter.
+
+use std::ffi::CString;
+use std::fs;
+use std::path::PathBuf;
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use chip8_gym::chip8_gym;
+
+#[test]
+fn test_python_tests_pass() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/test_env.py");
+    let source = CString::new(fs::read(&path).unwrap()).unwrap();
+
+    pyo3::append_to_inittab!(chip8_gym);
+    pyo3::prepare_freethreaded_python();
+
+    Python::with_gil(|py| {
+        let globals = PyDict::new(py);
+        globals.set_item("__file__", path.to_str().unwrap()).unwrap();
+        py.run(&source, Some(&globals), None).unwrap();
+
+        let mut tests: Vec<(String, Bound<PyAny>)> = globals
+            .iter()
+            .map(|(name, value)| (name.extract::<String>().unwrap(), value))
+            .filter(|(name, _)| name.starts_with("test_"))
+            .collect();
+        tests.sort_by(|(a, _), (b, _)| a.cmp(b));
+        assert!(!tests.is_empty());
+
+        for (name, test) in tests {
+            if let Err(error) = test.call0() {
+                error.print(py);
+                panic!("{} failed", name);
+            }
+        }
+    });
+}
@@ -0,0 +1,89 @@
+# Tests for the chip8_gym module. They run under pytest once the module is installed with
+# maturin, and are also run by `cargo test` through tests/python.rs.
+from pathlib import Path
+
+import chip8_gym
+
+ROMS = Path(__file__).resolve().parents[2] / "roms"
+
+
+def load(name):
+    return (ROMS / name).read_bytes()
+
+
+def test_observation_is_a_screen_buffer():
+    env = chip8_gym.Chip8Env(load("IBM Logo.ch8"))
+    observation, info = env.reset(seed=1)
+    assert info == {}
+
+    for _ in range(5):
+        observation, reward, terminated, truncated, info = env.step([])
+
+    screen = memoryview(observation)
+    assert screen.shape == (chip8_gym.HEIGHT, chip8_gym.WIDTH)
+    assert screen.format == "B" and screen.readonly
+    # The top left corner of the I in the logo
+    assert screen[8, 11] == 0 and screen[8, 12] == 1
+    assert set(screen.tobytes()) == {0, 1}
+    assert (reward, terminated, truncated) == (0.0, False, False)
+
+
+def test_same_seed_and_keys_give_same_screens():
+    def play(seed):
+        env = chip8_gym.Chip8Env(load("BRIX"), frame_skip=4)
+        env.reset(seed=seed)
+        screens = []
+        for step in range(100):
+            observation, *_ = env.step([4] if step % 10 < 5 else [6])
+            screens.append(bytes(observation))
+        return screens
+
+    assert play(7) == play(7)
+    assert play(7) != play(8)
+
+
+def test_frame_skip_runs_that_many_frames():
+    single = chip8_gym.Chip8Env(load("BRIX"))
+    skipping = chip8_gym.Chip8Env(load("BRIX"), frame_skip=3)
+    single.reset(seed=2)
+    skipping.reset(seed=2)
+
+    for _ in range(3):
+        expected, *_ = single.step([5])
+    observation, *_ = skipping.step([5])
+
+    assert bytes(observation) == bytes(expected)
+    assert skipping.frame_skip == 3
+
+
+def test_restore_state_rewinds():
+    env = chip8_gym.Chip8Env(load("BRIX"), max_steps=40)
+    env.reset(seed=3)
+    for _ in range(20):
+        env.step([4])
+
+    state = env.clone_state()
+    first = [env.step([6]) for _ in range(20)]
+    assert first[-1][3], "should be truncated at max_steps"
+
+    env.restore_state(state)
+    second = [env.step([6]) for _ in range(20)]
+    assert [bytes(step[0]) for step in first] == [bytes(step[0]) for step in second]
+    assert second[-1][3]
+
+
+def test_invalid_input_is_rejected():
+    def raises(function):
+        try:
+            function()
+        except ValueError:
+            return True
+        return False
+
+    env = chip8_gym.Chip8Env(load("BRIX"))
+    assert raises(lambda: env.step([16]))
+    assert raises(lambda: env.step([1, 2]))
+    assert raises(lambda: env.restore_state(b"not a state"))
+    assert raises(lambda: chip8_gym.Chip8Env(bytes(4096)))
+    assert raises(lambda: chip8_gym.Chip8Env(load("BRIX"), quirks="unknown"))
+    assert raises(lambda: chip8_gym.Chip8Env(load("BRIX"), frame_skip=0))
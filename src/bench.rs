@@ -0,0 +1,173 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::chip8::{self, Chip8};
+use crate::cpu::Fault;
+use crate::display::DisplayDriver;
+
+// Only one instruction in this many is timed, as reading the clock costs about as much as a cycle
+const SAMPLE_INTERVAL: u64 = 16;
+
+// How often a time limited run checks the clock
+const INSTRUCTIONS_PER_CHECK: u64 = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BenchLimit {
+    Instructions(u64),
+    Duration(Duration),
+}
+
+impl BenchLimit {
+    // An instruction count like 1000000, or seconds like 5s
+    pub fn parse(limit: &str) -> Result<BenchLimit, String> {
+        let invalid = || format!("Invalid bench limit: {} (expected an instruction count or seconds, e.g. 5s)", limit);
+
+        match limit.strip_suffix('s') {
+            Some(seconds) => {
+                let seconds: f64 = seconds.parse().map_err(|_| invalid())?;
+                Duration::try_from_secs_f64(seconds).map(BenchLimit::Duration).map_err(|_| invalid())
+            },
+            None => limit.parse().map(BenchLimit::Instructions).map_err(|_| invalid()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BenchReport {
+    pub instructions: u64,
+    pub elapsed: Duration,
+    // Averages over the sampled instructions, with the cost of reading the clock taken off
+    pub time_per_cycle: Duration,
+    pub display_time_per_instruction: Duration,
+    // What stopped the run early, if anything did
+    pub fault: Option<Fault>,
+}
+
+impl BenchReport {
+    pub fn instructions_per_second(&self) -> f64 {
+        self.instructions as f64 / self.elapsed.as_secs_f64()
+    }
+
+    pub fn display_share(&self) -> f64 {
+        let display_time = self.display_time_per_instruction.as_secs_f64() * self.instructions as f64;
+        display_time / self.elapsed.as_secs_f64()
+    }
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Instructions:        {}", self.instructions)?;
+        writeln!(f, "Elapsed:             {:.3}s", self.elapsed.as_secs_f64())?;
+        writeln!(f, "Instructions/second: {:.2}M", self.instructions_per_second() / 1_000_000.0)?;
+        writeln!(f, "Time per cycle:      {:.1}ns", self.time_per_cycle.as_secs_f64() * 1e9)?;
+        writeln!(
+            f,
+            "Time in display:     {:.1}ns per instruction ({:.1}% of the run)",
+            self.display_time_per_instruction.as_secs_f64() * 1e9,
+            self.display_share() * 100.0
+        )?;
+        if let Some(fault) = self.fault {
+            writeln!(f, "Stopped early:       {}", fault)?;
+        }
+        Ok(())
+    }
+}
+
+// Runs instructions as fast as possible, ticking the timers once per frame's worth so
+// ROMs behave as they would at normal speed
+pub fn run<DisplayDriverT: DisplayDriver>(chip8: &mut Chip8<DisplayDriverT>, limit: BenchLimit) -> BenchReport {
+    let clock_overhead = clock_overhead();
+
+    let mut instructions = 0;
+    let mut samples = 0;
+    let mut cpu_time = Duration::ZERO;
+    let mut display_time = Duration::ZERO;
+    let mut fault = None;
+
+    let start = Instant::now();
+
+    loop {
+        match limit {
+            BenchLimit::Instructions(count) if instructions >= count => break,
+            BenchLimit::Duration(duration)
+                if instructions % INSTRUCTIONS_PER_CHECK == 0 && start.elapsed() >= duration => break,
+            _ => {},
+        }
+
+        chip8.precycle();
+
+        if instructions % SAMPLE_INTERVAL == 0 {
+            let cycle_start = Instant::now();
+            let result = chip8.cpu_cycle();
+            let draw_start = Instant::now();
+            chip8.draw_if_dirty();
+            let draw_end = Instant::now();
+
+            cpu_time += (draw_start - cycle_start).saturating_sub(clock_overhead);
+            display_time += (draw_end - draw_start).saturating_sub(clock_overhead);
+            samples += 1;
+
+            if let Err(err) = result {
+                fault = Some(err);
+                break;
+            }
+        } else if let Err(err) = chip8.cycle() {
+            fault = Some(err);
+            break;
+        }
+
+        instructions += 1;
+        if instructions % chip8::INSTRUCTIONS_PER_FRAME as u64 == 0 {
+            chip8.timer_cycle();
+        }
+    }
+
+    let elapsed = start.elapsed();
+    let samples = samples.max(1);
+
+    BenchReport {
+        instructions,
+        elapsed,
+        time_per_cycle: cpu_time / samples,
+        display_time_per_instruction: display_time / samples,
+        fault,
+    }
+}
+
+// The smallest gap seen between two consecutive clock reads
+fn clock_overhead() -> Duration {
+    (0..1000)
+        .map(|_| {
+            let start = Instant::now();
+            Instant::now() - start
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::null_display_driver::NullDisplayDriver;
+
+    #[test]
+    fn test_parse_limit() {
+        assert_eq!(BenchLimit::parse("1000"), Ok(BenchLimit::Instructions(1000)));
+        assert_eq!(BenchLimit::parse("1.5s"), Ok(BenchLimit::Duration(Duration::from_millis(1500))));
+        assert!(BenchLimit::parse("fast").is_err());
+        assert!(BenchLimit::parse("-1s").is_err());
+    }
+
+    #[test]
+    fn test_runs_requested_instructions() {
+        let mut chip8 = Chip8::new(NullDisplayDriver::new());
+        // Draw the 0 glyph and jump back, so both halves of the cycle do some work
+        chip8.load_default_font();
+        chip8.load_rom(&[0xA0, 0x50, 0xD0, 0x05, 0x12, 0x02]).unwrap();
+
+        let report = run(&mut chip8, BenchLimit::Instructions(1000));
+
+        assert_eq!(report.instructions, 1000);
+        assert!(report.elapsed > Duration::ZERO);
+    }
+}
@@ -0,0 +1,361 @@
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use gif::{ColorOutput, DecodeOptions, Encoder, Frame, Repeat};
+use serde_json::{json, Map, Value};
+
+use crate::chip8::PROGRAM_START;
+use crate::display::{WIDTH, HEIGHT};
+use crate::memory;
+use crate::palette::{Colour, Palette};
+use crate::quirks::Quirks;
+use crate::rom_database::RomInfo;
+
+// The label is the screen at double size
+const LABEL_SCALE: usize = 2;
+const LABEL_WIDTH: usize = WIDTH * LABEL_SCALE;
+const LABEL_HEIGHT: usize = HEIGHT * LABEL_SCALE;
+
+// Two pixels hold a byte, a nybble in the low bits of each one's colour index
+const BYTES_PER_FRAME: usize = LABEL_WIDTH * LABEL_HEIGHT / 2;
+
+const BYTES_PER_LINE: usize = 16;
+
+// Octo's colour options, in palette order
+const COLOUR_OPTIONS: [&str; 4] = ["backgroundColor", "fillColor", "fillColor2", "blendColor"];
+
+// A program and its settings hidden in a GIF, in the style of Octo's cartridges. The payload is
+// Octo's JSON, {"program": source, "options": {...}}, after its length as 4 big-endian bytes.
+// Each frame shows the label while carrying the next part of the payload: colour index
+// `colour << 4 | nybble` is drawn in palette colour `colour`, so the data doesn't show.
+//
+// Cartridges saved by Octo hold Octo source. Only programs written as byte literals, like the
+// ones exported here, can be run without assembling them in Octo first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cartridge {
+    pub program: Program,
+    // Only the quirks, speed and palette are stored
+    pub settings: RomInfo,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Program {
+    Bytes(Vec<u8>),
+    // Octo source that's more than byte literals, kept as it is
+    Source(String),
+}
+
+impl Cartridge {
+    pub fn is_gif(data: &[u8]) -> bool {
+        data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a")
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Cartridge, String> {
+        let error = |err: gif::DecodingError| format!("Invalid cartridge: {}", err);
+
+        let mut options = DecodeOptions::new();
+        options.set_color_output(ColorOutput::Indexed);
+        let mut decoder = options.read_info(data).map_err(error)?;
+
+        let mut nybbles = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().map_err(error)? {
+            nybbles.extend(frame.buffer.iter().map(|index| index & 0x0F));
+        }
+        let bytes: Vec<u8> = nybbles.chunks_exact(2).map(|pair| pair[0] << 4 | pair[1]).collect();
+
+        let length = bytes.get(..4).ok_or("Cartridge has no payload")?;
+        let length = u32::from_be_bytes(length.try_into().unwrap()) as usize;
+        let payload = bytes.get(4..4 + length).ok_or("Cartridge payload is truncated")?;
+
+        let payload: Value = serde_json::from_slice(payload).map_err(|err| format!("Invalid cartridge payload: {}", err))?;
+        let source = payload["program"].as_str().ok_or("Cartridge has no program")?;
+
+        let program = match assemble(source) {
+            Some(program) if program.len() > memory::SIZE - PROGRAM_START as usize => {
+                return Err(format!("Cartridge program is too large: {} bytes", program.len()));
+            },
+            Some(program) => Program::Bytes(program),
+            None => Program::Source(source.to_string()),
+        };
+
+        Ok(Cartridge {
+            program,
+            settings: settings_from_options(&payload["options"])?,
+        })
+    }
+
+    // A GIF with `label`, normally a screen from the program, on the front
+    pub fn encode(&self, label: &[bool; WIDTH * HEIGHT]) -> Result<Vec<u8>, String> {
+        let error = |err: gif::EncodingError| format!("Failed to write cartridge: {}", err);
+
+        let source = match &self.program {
+            Program::Bytes(program) => disassemble(program),
+            Program::Source(source) => source.clone(),
+        };
+        let payload = json!({ "program": source, "options": options_from_settings(&self.settings) }).to_string();
+        let mut bytes = (payload.len() as u32).to_be_bytes().to_vec();
+        bytes.extend(payload.as_bytes());
+
+        let palette = self.settings.palette.unwrap_or_default();
+        let colour_table: Vec<u8> = (0..256)
+            .flat_map(|index| {
+                let colour = palette.colour_for(index >> 4);
+                [colour.r, colour.g, colour.b]
+            })
+            .collect();
+
+        let mut gif = Vec::new();
+        {
+            let mut encoder = Encoder::new(&mut gif, LABEL_WIDTH as u16, LABEL_HEIGHT as u16, &colour_table).map_err(error)?;
+            encoder.set_repeat(Repeat::Infinite).map_err(error)?;
+
+            for chunk in bytes.chunks(BYTES_PER_FRAME) {
+                let mut data = chunk.iter().flat_map(|byte| [byte >> 4, byte & 0x0F]);
+                let buffer: Vec<u8> = (0..LABEL_WIDTH * LABEL_HEIGHT)
+                    .map(|i| {
+                        let (x, y) = (i % LABEL_WIDTH / LABEL_SCALE, i / LABEL_WIDTH / LABEL_SCALE);
+                        (label[y * WIDTH + x] as u8) << 4 | data.next().unwrap_or(0)
+                    })
+                    .collect();
+
+                let frame = Frame {
+                    width: LABEL_WIDTH as u16,
+                    height: LABEL_HEIGHT as u16,
+                    buffer: Cow::Owned(buffer),
+                    ..Frame::default()
+                };
+                encoder.write_frame(&frame).map_err(error)?;
+            }
+        }
+
+        Ok(gif)
+    }
+}
+
+// Octo source made of nothing but byte literals, which Octo assembles to the same program
+fn disassemble(program: &[u8]) -> String {
+    let mut source = String::from(": main\n");
+    for line in program.chunks(BYTES_PER_LINE) {
+        let bytes: Vec<String> = line.iter().map(|byte| format!("0x{:02X}", byte)).collect();
+        writeln!(source, "{}", bytes.join(" ")).unwrap();
+    }
+    source
+}
+
+// The subset of Octo written by disassemble: comments, the main label and byte literals. None
+// for anything else, which only Octo can assemble.
+fn assemble(source: &str) -> Option<Vec<u8>> {
+    let mut program = Vec::new();
+    let mut tokens = source.lines().flat_map(|line| line.split('#').next().unwrap_or_default().split_whitespace());
+
+    if tokens.clone().take(2).eq([":", "main"]) {
+        tokens.nth(1);
+    }
+
+    for token in tokens {
+        let value = if let Some(hex) = token.strip_prefix("0x") {
+            i64::from_str_radix(hex, 16)
+        } else if let Some(binary) = token.strip_prefix("0b") {
+            i64::from_str_radix(binary, 2)
+        } else {
+            token.parse()
+        };
+
+        match value {
+            Ok(value @ -128..=255) => program.push(value as u8),
+            _ => return None,
+        }
+    }
+
+    Some(program)
+}
+
+fn settings_from_options(options: &Value) -> Result<RomInfo, String> {
+    let flag = |name: &str| options[name].as_bool().unwrap_or(false);
+    let mut settings = RomInfo::default();
+
+    if let Some(tickrate) = options.get("tickrate") {
+        let tickrate = tickrate.as_u64().filter(|&tickrate| tickrate > 0 && tickrate <= u32::MAX as u64);
+        settings.instructions_per_frame = Some(tickrate.ok_or("Invalid cartridge tickrate")? as u32);
+    }
+
+    if ["shiftQuirks", "loadStoreQuirks", "logicQuirks", "jumpQuirks"].iter().any(|name| options.get(name).is_some()) {
+        settings.quirks = Some(Quirks {
+            shift_uses_vy: !flag("shiftQuirks"),
+            load_store_increments_index: !flag("loadStoreQuirks"),
+            logic_resets_vf: flag("logicQuirks"),
+            jump_uses_vx: flag("jumpQuirks"),
+            index_overflow_sets_vf: flag("indexOverflowQuirks"),
+        });
+    }
+
+    if COLOUR_OPTIONS.iter().any(|name| options.get(name).is_some()) {
+        let mut colours = Palette::default().colours()[..COLOUR_OPTIONS.len()].to_vec();
+        for (colour, name) in colours.iter_mut().zip(COLOUR_OPTIONS) {
+            if let Some(hex) = options.get(name) {
+                *colour = Colour::from_hex(hex.as_str().unwrap_or_default())?;
+            }
+        }
+        settings.palette = Some(Palette::from_colours(&colours));
+    }
+
+    Ok(settings)
+}
+
+fn options_from_settings(settings: &RomInfo) -> Value {
+    let mut options = Map::new();
+
+    if let Some(speed) = settings.instructions_per_frame {
+        options.insert("tickrate".to_string(), speed.into());
+    }
+
+    if let Some(quirks) = settings.quirks {
+        options.insert("shiftQuirks".to_string(), (!quirks.shift_uses_vy).into());
+        options.insert("loadStoreQuirks".to_string(), (!quirks.load_store_increments_index).into());
+        options.insert("logicQuirks".to_string(), quirks.logic_resets_vf.into());
+        options.insert("jumpQuirks".to_string(), quirks.jump_uses_vx.into());
+        // Not an Octo option, so Octo ignores it
+        options.insert("indexOverflowQuirks".to_string(), quirks.index_overflow_sets_vf.into());
+    }
+
+    if let Some(palette) = settings.palette {
+        for (colour, name) in palette.colours().iter().zip(COLOUR_OPTIONS) {
+            options.insert(name.to_string(), format!("#{:02X}{:02X}{:02X}", colour.r, colour.g, colour.b).into());
+        }
+    }
+
+    Value::Object(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn settings() -> RomInfo {
+        RomInfo {
+            quirks: Some(Quirks::COSMAC_VIP),
+            instructions_per_frame: Some(30),
+            palette: Palette::named("amber"),
+            ..RomInfo::default()
+        }
+    }
+
+    fn label() -> [bool; WIDTH * HEIGHT] {
+        let mut label = [false; WIDTH * HEIGHT];
+        label[WIDTH + 1] = true;
+        label
+    }
+
+    #[test]
+    fn test_encode_and_decode_round_trip() {
+        // Large enough to need several frames
+        let program: Vec<u8> = (0..3000).map(|i| (i * 7) as u8).collect();
+        let cartridge = Cartridge { program: Program::Bytes(program), settings: settings() };
+
+        let gif = cartridge.encode(&label()).unwrap();
+
+        assert!(Cartridge::is_gif(&gif));
+        assert_eq!(Cartridge::decode(&gif).unwrap(), cartridge);
+    }
+
+    #[test]
+    fn test_label_shows_through_the_data() {
+        let cartridge = Cartridge { program: Program::Bytes(vec![0xFF; 64]), settings: settings() };
+        let gif = cartridge.encode(&label()).unwrap();
+
+        let mut options = DecodeOptions::new();
+        options.set_color_output(ColorOutput::RGBA);
+        let mut decoder = options.read_info(gif.as_slice()).unwrap();
+        let frame = decoder.read_next_frame().unwrap().unwrap();
+
+        let amber = Palette::named("amber").unwrap();
+        let pixel = |x: usize, y: usize| {
+            let rgba = &frame.buffer[(y * LABEL_WIDTH + x) * 4..];
+            Colour::rgb(rgba[0], rgba[1], rgba[2])
+        };
+        assert_eq!(pixel(0, 0), amber.background());
+        assert_eq!(pixel(3, 3), amber.foreground());
+        assert_eq!(pixel(4, 3), amber.background());
+    }
+
+    #[test]
+    fn test_reads_octo_options() {
+        let settings = settings_from_options(&json!({
+            "tickrate": 20,
+            "shiftQuirks": true,
+            "loadStoreQuirks": true,
+            "backgroundColor": "#000000",
+            "fillColor": "#FFFFFF",
+        })).unwrap();
+
+        assert_eq!(settings.instructions_per_frame, Some(20));
+        assert_eq!(settings.quirks, Some(Quirks {
+            shift_uses_vy: false,
+            load_store_increments_index: false,
+            logic_resets_vf: false,
+            jump_uses_vx: false,
+            index_overflow_sets_vf: false,
+        }));
+        assert_eq!(settings.palette.unwrap().foreground(), Colour::rgb(0xFF, 0xFF, 0xFF));
+        assert_eq!(settings_from_options(&json!({})).unwrap(), RomInfo::default());
+    }
+
+    #[test]
+    fn test_assembles_byte_literals_only() {
+        assert_eq!(assemble(": main\n0x12 0b101 # a comment\n7 -1\n").unwrap(), [0x12, 0x05, 0x07, 0xFF]);
+        assert_eq!(assemble(&disassemble(&[0xA2, 0x2A, 0x60])).unwrap(), [0xA2, 0x2A, 0x60]);
+        assert!(assemble(": main\nv0 := 1\n").is_none());
+    }
+
+    #[test]
+    fn test_decodes_octo_source_cartridge() {
+        let gif = fs::read(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("cartridges").join("bounce.gif")).unwrap();
+        let cartridge = Cartridge::decode(&gif).unwrap();
+
+        let Program::Source(source) = &cartridge.program else {
+            panic!("Expected Octo source, got {:?}", cartridge.program);
+        };
+        assert!(source.contains("sprite v0 v1 4"));
+        assert_eq!(cartridge.settings.instructions_per_frame, Some(7));
+        assert_eq!(cartridge.settings.quirks.map(|quirks| quirks.load_store_increments_index), Some(false));
+        assert_eq!(cartridge.settings.palette.unwrap().foreground(), Colour::rgb(0xFF, 0xCC, 0x00));
+
+        // Saved again as it was
+        assert_eq!(Cartridge::decode(&cartridge.encode(&label()).unwrap()).unwrap(), cartridge);
+    }
+
+    // Laid out as Octo saves them, a 160x128 cartridge with its art drawn in the high bits,
+    // rather than the way encode does
+    #[test]
+    fn test_decodes_octo_layout_cartridge() {
+        let gif = fs::read(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("cartridges").join("maze.gif")).unwrap();
+        let maze = fs::read(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("roms").join("MAZE")).unwrap();
+        let cartridge = Cartridge::decode(&gif).unwrap();
+
+        assert_eq!(cartridge.program, Program::Bytes(maze));
+        assert_eq!(cartridge.settings.instructions_per_frame, Some(15));
+        assert_eq!(cartridge.settings.quirks, Some(Quirks {
+            shift_uses_vy: false,
+            load_store_increments_index: false,
+            logic_resets_vf: false,
+            jump_uses_vx: false,
+            index_overflow_sets_vf: false,
+        }));
+        let palette = cartridge.settings.palette.unwrap();
+        assert_eq!(palette.background(), Colour::rgb(0x0F, 0x38, 0x0F));
+        assert_eq!(palette.foreground(), Colour::rgb(0x8B, 0xAC, 0x0F));
+        assert_eq!(palette.colours()[2..4], [Colour::rgb(0x30, 0x62, 0x30), Colour::rgb(0x9B, 0xBC, 0x0F)]);
+    }
+
+    #[test]
+    fn test_rejects_programs_too_large_for_memory() {
+        let fits = Cartridge { program: Program::Bytes(vec![0; memory::SIZE - PROGRAM_START as usize]), settings: RomInfo::default() };
+        assert!(Cartridge::decode(&fits.encode(&label()).unwrap()).is_ok());
+
+        let too_large = Cartridge { program: Program::Bytes(vec![0; memory::SIZE - PROGRAM_START as usize + 1]), settings: RomInfo::default() };
+        assert!(Cartridge::decode(&too_large.encode(&label()).unwrap()).is_err());
+    }
+}
@@ -1,33 +1,76 @@
-use std::fmt;
+use core::fmt;
 
-use sdl2::Sdl;
-
-use crate::memory::Memory;
-use crate::display::Display;
-use crate::sdl2_display_driver::Sdl2DisplayDriver;
-use crate::cpu::Cpu;
+use crate::memory::{self, Memory};
+use crate::display::{self, Display, DisplayDriver, WIDTH, HEIGHT};
+use crate::cpu::{Cpu, Fault, RecompiledState};
 use crate::keyboard::Keyboard;
+use crate::instructions::Instruction;
+use crate::quirks::Quirks;
+use crate::state;
+#[cfg(feature = "jit")]
+use crate::jit::Jit;
 
 pub const PROGRAM_START: u16 = 0x200;
 pub const FONT_START: u16 = 0x050;
 
-pub struct Chip8 {
+pub const INSTRUCTIONS_PER_SECOND: u32 = 700;
+pub const TIMER_FREQUENCY: u32 = 60;
+pub const INSTRUCTIONS_PER_FRAME: u32 = (INSTRUCTIONS_PER_SECOND + TIMER_FREQUENCY / 2) / TIMER_FREQUENCY;
+
+// A ROM fills memory from PROGRAM_START to the end, at most
+pub const MAX_ROM_SIZE: usize = memory::SIZE - PROGRAM_START as usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RomTooLarge {
+    pub size: usize,
+}
+
+impl fmt::Display for RomTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ROM is {} bytes, too large to fit in memory", self.size)
+    }
+}
+
+pub struct Chip8<DisplayDriverT: DisplayDriver> {
     memory: Memory,
     cpu: Cpu,
-    display: Display<Sdl2DisplayDriver>,
+    display: Display<DisplayDriverT>,
     keyboard: Keyboard,
+    #[cfg(feature = "jit")]
+    jit: Option<Jit>,
 }
 
-impl Chip8 {
-    pub fn new (sdl_context: &Sdl) -> Chip8 {
+impl<DisplayDriverT: DisplayDriver> Chip8<DisplayDriverT> {
+    pub fn new(display_driver: DisplayDriverT) -> Chip8<DisplayDriverT> {
         Chip8 {
             memory: Memory::new(),
             cpu: Cpu::new(PROGRAM_START),
-            display: Display::new(Sdl2DisplayDriver::new(sdl_context)),
+            display: Display::new(display_driver),
             keyboard: Keyboard::new(),
+            #[cfg(feature = "jit")]
+            jit: None,
         }
     }
 
+    pub fn set_quirks(&mut self, quirks: Quirks) {
+        self.cpu.set_quirks(quirks);
+    }
+
+    pub fn set_decode_cache(&mut self, enabled: bool) {
+        self.cpu.set_decode_cache(enabled);
+    }
+
+    // Only affects run_frame and run_frame_holding, which frontends stepping one cycle at a
+    // time don't use
+    #[cfg(feature = "jit")]
+    pub fn set_jit(&mut self, enabled: bool) {
+        self.jit = enabled.then(Jit::new);
+    }
+
+    pub fn seed_rng(&mut self, seed: u64) {
+        self.cpu.seed_rng(seed);
+    }
+
     pub fn load_default_font(&mut self) {
         self.memory.write_bytes(FONT_START, &[
             0xF0, 0x90, 0x90, 0x90, 0xF0, // 0
@@ -49,19 +92,112 @@ impl Chip8 {
         ]);
     }
 
-    pub fn load_rom(&mut self, data: &[u8]) {
+    // Memory is left as it was if the ROM doesn't fit
+    pub fn load_rom(&mut self, data: &[u8]) -> Result<(), RomTooLarge> {
+        if data.len() > MAX_ROM_SIZE {
+            return Err(RomTooLarge { size: data.len() });
+        }
+
         self.memory.write_bytes(PROGRAM_START, data);
+        Ok(())
     }
 
     pub fn precycle(&mut self) {
         self.keyboard.clear_signals();
     }
 
-    pub fn cycle(&mut self) {
-        self.cpu.cycle(&mut self.memory, &mut self.display, &self.keyboard);
+    // Once the CPU faults, every cycle returns the fault until a state is loaded
+    pub fn cycle(&mut self) -> Result<(), Fault> {
+        let result = self.cpu_cycle();
+        self.draw_if_dirty();
+        result
+    }
+
+    // The two halves of a cycle, separated so they can be timed individually
+    pub fn cpu_cycle(&mut self) -> Result<(), Fault> {
+        self.cpu.cycle(&mut self.memory, &mut self.display, &self.keyboard)
+    }
+
+    pub fn draw_if_dirty(&mut self) {
+        self.display.draw_if_dirty();
+    }
+
+    // A whole cycle for an instruction already decoded from `address`, for the BNNN recompiled
+    // code leaves to the interpreter
+    #[inline]
+    pub fn execute_at(&mut self, address: u16, instruction: Instruction) -> Result<(), Fault> {
+        let result = self.cpu.execute_at(address, instruction, &mut self.memory, &mut self.display, &self.keyboard);
         self.display.draw_if_dirty();
+        result
+    }
+
+    // Runs a block of recompiled code, which draws for itself where a cycle would
+    #[inline]
+    pub fn run_recompiled<F>(&mut self, block: F) -> Result<(), Fault>
+    where
+        F: FnOnce(&mut RecompiledState<DisplayDriverT>) -> Result<(), Fault>,
+    {
+        self.cpu.run_recompiled(&mut self.memory, &mut self.display, &self.keyboard, block)
+    }
+
+    // Runs one 60Hz frame's worth of instructions as fast as possible, then ticks the timers.
+    // A fault ends the frame early, without ticking them.
+    pub fn run_frame(&mut self, instructions: u32) -> Result<(), Fault> {
+        self.run_frame_changing_key(None, instructions)
     }
 
+    // Like run_frame, but changing the held key the way the frontends do: after the first
+    // precycle, so a release is seen by FX0A
+    pub fn run_frame_holding(&mut self, key: Option<u8>, instructions: u32) -> Result<(), Fault> {
+        self.run_frame_changing_key(Some(key), instructions)
+    }
+
+    // Holds `key`, if given, after the first precycle
+    fn run_frame_changing_key(&mut self, key: Option<Option<u8>>, instructions: u32) -> Result<(), Fault> {
+        #[cfg(feature = "jit")]
+        if let Some(mut jit) = self.jit.take() {
+            let result = self.run_frame_compiled(&mut jit, key, instructions);
+            self.jit = Some(jit);
+            return result;
+        }
+
+        let mut key = key;
+        for _ in 0..instructions {
+            self.precycle();
+            if let Some(key) = key.take() {
+                self.hold_key(key);
+            }
+            self.cycle()?;
+        }
+        self.timer_cycle();
+        Ok(())
+    }
+
+    // Compiled blocks never draw or read keys, so they only need the precycle before them
+    #[cfg(feature = "jit")]
+    fn run_frame_compiled(&mut self, jit: &mut Jit, key: Option<Option<u8>>, instructions: u32) -> Result<(), Fault> {
+        let mut remaining = instructions;
+        let mut key = key;
+
+        while remaining > 0 {
+            self.precycle();
+            if let Some(key) = key.take() {
+                self.hold_key(key);
+            }
+
+            match self.cpu.run_compiled(jit, &self.memory, remaining) {
+                0 => {
+                    self.cycle()?;
+                    remaining -= 1;
+                },
+                compiled => remaining -= compiled,
+            }
+        }
+        self.timer_cycle();
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
     pub fn show_internals(&self) {
         println!("Registers");
 
@@ -77,6 +213,35 @@ impl Chip8 {
         self.cpu.timer_cycle();
     }
 
+    // Quirks and other settings aren't saved, only the machine they apply to
+    pub fn save_state(&self, buffer: &mut [u8; state::SIZE]) {
+        let mut writer = state::Writer::new(buffer);
+        self.cpu.save_state(&mut writer);
+        self.memory.save_state(&mut writer);
+        self.display.save_state(&mut writer);
+        self.keyboard.save_state(&mut writer);
+    }
+
+    // A state that doesn't load leaves the machine as it was
+    pub fn load_state(&mut self, buffer: &[u8; state::SIZE]) -> Result<(), &'static str> {
+        let mut reader = state::Reader::new(buffer)?;
+        let mut cpu = self.cpu.clone();
+        cpu.load_state(&mut reader)?;
+
+        // Memory and the screen can hold anything, so only the keyboard after them is checked
+        let mut machine_reader = reader.clone();
+        reader.bytes(memory::SIZE + display::STATE_SIZE);
+        let mut keyboard = self.keyboard.clone();
+        keyboard.load_state(&mut reader)?;
+
+        self.cpu = cpu;
+        self.memory.load_state(&mut machine_reader);
+        self.display.load_state(&mut machine_reader);
+        self.keyboard = keyboard;
+        self.display.draw_if_dirty();
+        Ok(())
+    }
+
     pub fn key_down(&mut self, key: u8) {
         self.keyboard.key_down(key);
     }
@@ -85,11 +250,87 @@ impl Chip8 {
         self.keyboard.key_up();
     }
 
-    
+    // Only one key can be held at once, so holding another releases the current one
+    pub fn hold_key(&mut self, key: Option<u8>) {
+        if key == self.key_pressed() {
+            return;
+        }
+        if self.key_pressed().is_some() {
+            self.key_up();
+        }
+        if let Some(key) = key {
+            self.key_down(key);
+        }
+    }
+
+    pub fn key_pressed(&self) -> Option<u8> {
+        self.keyboard.key_pressed()
+    }
+
+    pub fn read_byte(&self, address: u16) -> u8 {
+        self.memory.read_byte(address)
+    }
+
+    pub fn fault(&self) -> Option<Fault> {
+        self.cpu.fault()
+    }
+
+    pub fn cpu(&self) -> &Cpu {
+        &self.cpu
+    }
+
+    pub fn memory(&self) -> &Memory {
+        &self.memory
+    }
+
+    pub fn framebuffer(&self) -> &[bool; WIDTH * HEIGHT] {
+        self.display.framebuffer()
+    }
+
+    pub fn display_driver(&self) -> &DisplayDriverT {
+        self.display.display_driver()
+    }
+
+    // Changes made through the driver show up on the next redraw
+    pub fn display_driver_mut(&mut self) -> &mut DisplayDriverT {
+        self.display.redraw();
+        self.display.display_driver_mut()
+    }
 }
 
-impl fmt::Debug for Chip8 {
+// A copy of the whole machine, e.g. for exploring from the same point repeatedly. The JIT's
+// compiled blocks aren't copied, so a clone compiles its own as it runs.
+impl<DisplayDriverT: DisplayDriver + Clone> Clone for Chip8<DisplayDriverT> {
+    fn clone(&self) -> Self {
+        Chip8 {
+            memory: self.memory.clone(),
+            cpu: self.cpu.clone(),
+            display: self.display.clone(),
+            keyboard: self.keyboard.clone(),
+            #[cfg(feature = "jit")]
+            jit: self.jit.as_ref().map(|_| Jit::new()),
+        }
+    }
+}
+
+impl<DisplayDriverT: DisplayDriver> fmt::Debug for Chip8<DisplayDriverT> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{{ memory: {:?} }}", self.memory)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::null_display_driver::NullDisplayDriver;
+
+    #[test]
+    fn test_load_rom_rejects_roms_too_large_for_memory() {
+        let mut chip8 = Chip8::new(NullDisplayDriver::new());
+        assert_eq!(chip8.load_rom(&[0xAA; MAX_ROM_SIZE]), Ok(()));
+        assert_eq!(chip8.memory().read_byte(0xFFF), 0xAA);
+
+        assert_eq!(chip8.load_rom(&[0xBB; MAX_ROM_SIZE + 1]), Err(RomTooLarge { size: MAX_ROM_SIZE + 1 }));
+        assert_eq!(chip8.memory().read_byte(PROGRAM_START), 0xAA);
+    }
+}
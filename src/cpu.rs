@@ -1,19 +1,107 @@
-use rand::Rng;
+use core::fmt;
 
-use crate::{memory::Memory, display::Display, display::DisplayDriver, chip8, keyboard::Keyboard, instructions::Instruction};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+use crate::{memory::{Memory, ADDRESS_MASK}, display::Display, display::DisplayDriver, chip8, keyboard::Keyboard, instructions::Instruction, quirks::Quirks, state};
+#[cfg(feature = "jit")]
+use crate::jit::{Jit, JitState};
 
 pub type Address = u16;
 pub type Register = u8;
 pub type Value = u8;
 
+// Nesting deeper than this is a stack overflow, as on most interpreters since the SCHIP
+pub const STACK_SIZE: usize = 16;
+
+// Registers, timers, the stack and the RNG's seed and position
+pub const STATE_SIZE: usize = 16 + 2 + 1 + 1 + 2 + STACK_SIZE * 2 + 1 + 32 + 16;
+
+// Something a ROM did that there's no carrying on from. The CPU halts with the program counter
+// on the instruction at fault, and stays halted until a state is loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    UnknownOpcode { address: Address, opcode: u16 },
+    StackOverflow { address: Address },
+    // A return with nothing to return to
+    StackUnderflow { address: Address },
+}
+
+impl Fault {
+    pub fn address(&self) -> Address {
+        match *self {
+            Fault::UnknownOpcode { address, .. } | Fault::StackOverflow { address } | Fault::StackUnderflow { address } => address,
+        }
+    }
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Fault::UnknownOpcode { address, opcode } => write!(f, "Unknown opcode {:04X} at 0x{:03X}", opcode, address),
+            Fault::StackOverflow { address } => write!(f, "Stack overflow calling from 0x{:03X}", address),
+            Fault::StackUnderflow { address } => write!(f, "Return with an empty stack at 0x{:03X}", address),
+        }
+    }
+}
+
+// The machine as recompiled code runs on it. As with the JIT's state, the registers are copied in
+// and out around each block, and the block sets the program counter to wherever it ends up.
+pub struct RecompiledState<'a, T: DisplayDriver> {
+    pub v_registers: [Register; 16],
+    pub i_register: Address,
+    pub delay_timer: Value,
+    pub sound_timer: Value,
+    pub program_counter: Address,
+    pub memory: &'a mut Memory,
+    pub display: &'a mut Display<T>,
+    pub keyboard: &'a Keyboard,
+    stack: &'a mut [Address; STACK_SIZE],
+    stack_pointer: &'a mut usize,
+    rng: &'a mut ChaCha12Rng,
+}
+
+impl<T: DisplayDriver> RecompiledState<'_, T> {
+    // 2NNN at `address`
+    pub fn call(&mut self, address: Address, target: Address) -> Result<(), Fault> {
+        if *self.stack_pointer == STACK_SIZE {
+            return Err(Fault::StackOverflow { address });
+        }
+        self.stack[*self.stack_pointer] = (address + 2) & ADDRESS_MASK;
+        *self.stack_pointer += 1;
+        self.program_counter = target;
+        Ok(())
+    }
+
+    // 00EE at `address`
+    pub fn ret(&mut self, address: Address) -> Result<(), Fault> {
+        if *self.stack_pointer == 0 {
+            return Err(Fault::StackUnderflow { address });
+        }
+        *self.stack_pointer -= 1;
+        self.program_counter = self.stack[*self.stack_pointer];
+        Ok(())
+    }
+
+    pub fn random(&mut self) -> Value {
+        self.rng.gen::<u8>()
+    }
+}
+
+#[derive(Clone)]
 pub struct Cpu {
     v_registers: [Register; 16],
     i_register: Address,
     delay_timer: Value,
     sound_timer: Value,
     program_counter: Address,
-    stack: Vec<Address>,
-    rng: rand::rngs::ThreadRng,
+    stack: [Address; STACK_SIZE],
+    stack_pointer: usize,
+    // The same generator as rand's StdRng, which doesn't expose its position for save states
+    rng: ChaCha12Rng,
+    quirks: Quirks,
+    decode_cache: bool,
+    fault: Option<Fault>,
 }
 
 impl Cpu {
@@ -24,11 +112,83 @@ impl Cpu {
             delay_timer: 0,
             sound_timer: 0,
             program_counter: memory_start,
-            stack: Vec::new(),
-            rng: rand::thread_rng(),
+            stack: [0; STACK_SIZE],
+            stack_pointer: 0,
+            #[cfg(feature = "std")]
+            rng: ChaCha12Rng::from_entropy(),
+            // There's no entropy source without std, so CXNN is the same every run unless seeded
+            #[cfg(not(feature = "std"))]
+            rng: ChaCha12Rng::seed_from_u64(0),
+            quirks: Quirks::default(),
+            decode_cache: true,
+            fault: None,
         }
     }
 
+    pub fn set_quirks(&mut self, quirks: Quirks) {
+        self.quirks = quirks;
+    }
+
+    // Decoding every fetch is kept for comparison in benchmarks
+    pub fn set_decode_cache(&mut self, enabled: bool) {
+        self.decode_cache = enabled;
+    }
+
+    // Makes CXNN repeatable, for replaying recorded input
+    pub fn seed_rng(&mut self, seed: u64) {
+        self.rng = ChaCha12Rng::seed_from_u64(seed);
+    }
+
+    pub fn save_state(&self, writer: &mut state::Writer) {
+        writer.bytes(&self.v_registers);
+        writer.word(self.i_register);
+        writer.byte(self.delay_timer);
+        writer.byte(self.sound_timer);
+        writer.word(self.program_counter);
+        for address in self.stack {
+            writer.word(address);
+        }
+        writer.byte(self.stack_pointer as u8);
+        writer.bytes(&self.rng.get_seed());
+        writer.bytes(&self.rng.get_word_pos().to_be_bytes());
+    }
+
+    pub fn load_state(&mut self, reader: &mut state::Reader) -> Result<(), &'static str> {
+        let mut v_registers = [0; 16];
+        v_registers.copy_from_slice(reader.bytes(16));
+        let i_register = reader.word();
+        let delay_timer = reader.byte();
+        let sound_timer = reader.byte();
+        let program_counter = reader.word();
+        let mut stack = [0; STACK_SIZE];
+        for address in &mut stack {
+            *address = reader.word();
+        }
+        let stack_pointer = reader.byte() as usize;
+        if stack_pointer > STACK_SIZE {
+            return Err("Invalid stack pointer in save state");
+        }
+        if program_counter > ADDRESS_MASK || i_register > ADDRESS_MASK || stack.iter().any(|&address| address > ADDRESS_MASK) {
+            return Err("Address out of range in save state");
+        }
+        let mut seed = [0; 32];
+        seed.copy_from_slice(reader.bytes(32));
+        let mut word_pos = [0; 16];
+        word_pos.copy_from_slice(reader.bytes(16));
+
+        self.v_registers = v_registers;
+        self.i_register = i_register;
+        self.delay_timer = delay_timer;
+        self.sound_timer = sound_timer;
+        self.program_counter = program_counter;
+        self.stack = stack;
+        self.stack_pointer = stack_pointer;
+        self.rng = ChaCha12Rng::from_seed(seed);
+        self.rng.set_word_pos(u128::from_be_bytes(word_pos));
+        self.fault = None;
+        Ok(())
+    }
+
     pub fn i_register(&self) -> Address {
         self.i_register
     }
@@ -41,16 +201,56 @@ impl Cpu {
         self.program_counter
     }
 
-    pub fn cycle<T: DisplayDriver>(&mut self, memory: &mut Memory, display: &mut Display<T>, keyboard: &Keyboard) {
-        // fetch
-        let opcode = memory.read_word(self.program_counter);
-        self.program_counter += 2;
+    pub fn delay_timer(&self) -> Value {
+        self.delay_timer
+    }
+
+    pub fn sound_timer(&self) -> Value {
+        self.sound_timer
+    }
+
+    pub fn fault(&self) -> Option<Fault> {
+        self.fault
+    }
+
+    pub fn quirks(&self) -> Quirks {
+        self.quirks
+    }
+
+    pub fn cycle<T: DisplayDriver>(&mut self, memory: &mut Memory, display: &mut Display<T>, keyboard: &Keyboard) -> Result<(), Fault> {
+        if let Some(fault) = self.fault {
+            return Err(fault);
+        }
+
+        // fetch and decode
+        let address = self.program_counter;
+        let instruction = if self.decode_cache {
+            memory.fetch(address)
+        } else {
+            Instruction::try_decode(memory.read_word(address))
+        };
+        let Some(instruction) = instruction else {
+            return self.halt(Fault::UnknownOpcode { address, opcode: memory.read_word(address) });
+        };
+        self.skip();
+
+        self.execute(address, instruction, memory, display, keyboard)
+    }
+
+    // Runs an instruction as if it had just been fetched from `address`, for the BNNN recompiled
+    // code leaves to the interpreter
+    #[inline]
+    pub fn execute_at<T: DisplayDriver>(&mut self, address: Address, instruction: Instruction, memory: &mut Memory, display: &mut Display<T>, keyboard: &Keyboard) -> Result<(), Fault> {
+        if let Some(fault) = self.fault {
+            return Err(fault);
+        }
 
-        // decode
-        // Option here to decode entire program upfront? (although some programs may self modify)
-        let instruction = Instruction::decode(opcode);
+        self.program_counter = (address + 2) & ADDRESS_MASK;
+        self.execute(address, instruction, memory, display, keyboard)
+    }
 
-        // execute
+    #[inline]
+    fn execute<T: DisplayDriver>(&mut self, address: Address, instruction: Instruction, memory: &mut Memory, display: &mut Display<T>, keyboard: &Keyboard) -> Result<(), Fault> {
         match instruction {
             Instruction::ClearScreen => {
                 display.clear();
@@ -59,30 +259,38 @@ impl Cpu {
                 self.program_counter = address;
             },
             Instruction::Return => {
-                self.program_counter = self.stack.pop().unwrap();
+                if self.stack_pointer == 0 {
+                    return self.halt(Fault::StackUnderflow { address });
+                }
+                self.stack_pointer -= 1;
+                self.program_counter = self.stack[self.stack_pointer];
             },
-            Instruction::Call(address) => {
-                self.stack.push(self.program_counter);
-                self.program_counter = address;
+            Instruction::Call(target) => {
+                if self.stack_pointer == STACK_SIZE {
+                    return self.halt(Fault::StackOverflow { address });
+                }
+                self.stack[self.stack_pointer] = self.program_counter;
+                self.stack_pointer += 1;
+                self.program_counter = target;
             },
             Instruction::CheckEqualValue(vx, value) => {
                 if self.v_registers[vx as usize] == value {
-                    self.program_counter += 2;
+                    self.skip();
                 }
             },
             Instruction::CheckNotEqualValue(vx, value) => {
                 if self.v_registers[vx as usize] != value {
-                    self.program_counter += 2;
+                    self.skip();
                 }
             },
             Instruction::CheckEqual(vx, vy) => {
                 if self.v_registers[vx as usize] == self.v_registers[vy as usize] {
-                    self.program_counter += 2;
+                    self.skip();
                 }
             },
             Instruction::CheckNotEqual(vx, vy) => {
                 if self.v_registers[vx as usize] != self.v_registers[vy as usize] {
-                    self.program_counter += 2;
+                    self.skip();
                 }
             },
             Instruction::SetRegisterToValue(register, value) => {
@@ -96,12 +304,15 @@ impl Cpu {
             },
             Instruction::Or(vx, vy) => {
                 self.v_registers[vx as usize] |= self.v_registers[vy as usize];
+                self.reset_flag_after_logic();
             },
             Instruction::And(vx, vy) => {
                 self.v_registers[vx as usize] &= self.v_registers[vy as usize];
+                self.reset_flag_after_logic();
             },
             Instruction::Xor(vx, vy) => {
                 self.v_registers[vx as usize] ^= self.v_registers[vy as usize];
+                self.reset_flag_after_logic();
             },
             Instruction::Add(vx, vy) => {
                 let (val, overflow) = self.v_registers[vx as usize].overflowing_add(self.v_registers[vy as usize]);
@@ -115,21 +326,32 @@ impl Cpu {
                 self.v_registers[destination as usize] = val;
                 self.v_registers[0xF] = !overflow as u8;
             },
-            Instruction::ShiftLeft(vx) => {
-                let lost_bit = (self.v_registers[vx as usize] >> 7) & 0x1;
+            Instruction::ShiftLeft(vx, vy) => {
+                let value = self.shift_source(vx, vy);
+                let lost_bit = (value >> 7) & 0x1;
 
-                self.v_registers[vx as usize] <<= 1;
+                self.v_registers[vx as usize] = value << 1;
                 self.v_registers[0xF] = lost_bit;
             },
-            Instruction::ShiftRight(vx) => {
-                let lost_bit = self.v_registers[vx as usize] & 0x01;
+            Instruction::ShiftRight(vx, vy) => {
+                let value = self.shift_source(vx, vy);
+                let lost_bit = value & 0x01;
         
-                self.v_registers[vx as usize] >>= 1;
+                self.v_registers[vx as usize] = value >> 1;
                 self.v_registers[0xF] = lost_bit;
             },
             Instruction::SetIndex(index) => {
                 self.i_register = index;
             },
+            Instruction::JumpWithOffset(address) => {
+                let offset_register = if self.quirks.jump_uses_vx {
+                    (address >> 8) as usize
+                } else {
+                    0
+                };
+
+                self.program_counter = (address + self.v_registers[offset_register] as u16) & ADDRESS_MASK;
+            },
             Instruction::Display { vx, vy, pixel_height } => {
                 let x = self.v_registers[vx as usize];
                 let y = self.v_registers[vy as usize];
@@ -140,8 +362,6 @@ impl Cpu {
                 for i in 0..pixel_height as u16 {
                     let pixel_location = self.i_register + i;
                     let pixels = memory.read_byte(pixel_location);
-                    println!("Address: {:02x}", pixel_location);
-                    println!("Pixel: {:02x}", pixels);
 
                     display.set_pixels(x, y.saturating_add(i as u8), pixels, y);
                 }
@@ -156,12 +376,12 @@ impl Cpu {
             },
             Instruction::SkipIfKeyPressed(x) => {
                 if keyboard.is_key_pressed(self.v_registers[x as usize]) {
-                    self.program_counter += 2;
+                    self.skip();
                 }
             },
             Instruction::SkipIfKeyNotPressed(x) => {
                 if !keyboard.is_key_pressed(self.v_registers[x as usize]) {
-                    self.program_counter += 2;
+                    self.skip();
                 }
             },
             Instruction::ReadDelayTimer(x) => {
@@ -174,19 +394,19 @@ impl Cpu {
                 self.sound_timer = self.v_registers[vx as usize]; 
             },
             Instruction::AddRegisterToIndex(vx) => {
-                let value = self.v_registers[vx as usize];
-                self.i_register += value as u16;
+                let index = self.i_register + self.v_registers[vx as usize] as u16;
+                self.i_register = index & ADDRESS_MASK;
 
-                // Not clear if this is supposed to wrap or carry on here
-                if self.i_register > 0xFFF {
-                    self.v_registers[0xF] = 1;
+                if self.quirks.index_overflow_sets_vf {
+                    self.v_registers[0xF] = (index > ADDRESS_MASK) as u8;
                 }
             },
             Instruction::WaitForKeyPress(vx) => {
-                if keyboard.has_signal_keypress() {
-                    self.v_registers[vx as usize] = keyboard.key_last_pressed().unwrap();
+                // A release with nothing held before it isn't a key press, so keeps waiting
+                if let (true, Some(key)) = (keyboard.has_signal_keypress(), keyboard.key_last_pressed()) {
+                    self.v_registers[vx as usize] = key;
                 } else {
-                    self.program_counter -= 2;
+                    self.program_counter = self.program_counter.wrapping_sub(2) & ADDRESS_MASK;
                 }
             },
             Instruction::SetIndexToSprite(vx) => {
@@ -196,9 +416,9 @@ impl Cpu {
             Instruction::StoreBCD(vx) => {
                 let value = self.v_registers[vx as usize];
                     
-                let hundreds = (value / 100) as u8;
-                let tens = ((value % 100) / 10) as u8;
-                let ones = (value % 10) as u8;
+                let hundreds = value / 100;
+                let tens = (value % 100) / 10;
+                let ones = value % 10;
 
                 memory.write_byte(self.i_register, hundreds);
                 memory.write_byte(self.i_register + 1, tens);
@@ -211,6 +431,10 @@ impl Cpu {
                     let value = self.v_registers[i as usize];
                     memory.write_byte(index + i as u16, value);
                 }
+
+                if self.quirks.load_store_increments_index {
+                    self.i_register = (self.i_register + vx as u16 + 1) & ADDRESS_MASK;
+                }
             },
             Instruction::LoadRegisters(vx) => {
                 let index = self.i_register;
@@ -219,14 +443,107 @@ impl Cpu {
                     let value = memory.read_byte(index + i as u16);
                     self.v_registers[i as usize] = value;
                 }
+
+                if self.quirks.load_store_increments_index {
+                    self.i_register = (self.i_register + vx as u16 + 1) & ADDRESS_MASK;
+                }
             },
         }
+
+        Ok(())
+    }
+
+    fn halt(&mut self, fault: Fault) -> Result<(), Fault> {
+        self.program_counter = fault.address();
+        self.fault = Some(fault);
+        Err(fault)
+    }
+
+    // Runs a compiled block of up to `budget` instructions from the program counter, returning
+    // how many ran. None run if the next instruction has to be interpreted.
+    #[cfg(feature = "jit")]
+    pub fn run_compiled(&mut self, jit: &mut Jit, memory: &Memory, budget: u32) -> u32 {
+        if self.fault.is_some() {
+            return 0;
+        }
+
+        let mut state = JitState {
+            v_registers: self.v_registers,
+            i_register: self.i_register,
+            delay_timer: self.delay_timer,
+            sound_timer: self.sound_timer,
+        };
+
+        let Some((next, instructions)) = jit.run(self.program_counter, budget, memory, self.quirks, &mut state) else {
+            return 0;
+        };
+
+        self.v_registers = state.v_registers;
+        self.i_register = state.i_register;
+        self.delay_timer = state.delay_timer;
+        self.sound_timer = state.sound_timer;
+        self.program_counter = next;
+
+        instructions
+    }
+
+    // Runs a block of recompiled code, halting on the fault it returns like an interpreted one
+    pub fn run_recompiled<T: DisplayDriver, F>(&mut self, memory: &mut Memory, display: &mut Display<T>, keyboard: &Keyboard, block: F) -> Result<(), Fault>
+    where
+        F: FnOnce(&mut RecompiledState<T>) -> Result<(), Fault>,
+    {
+        if let Some(fault) = self.fault {
+            return Err(fault);
+        }
+
+        let mut state = RecompiledState {
+            v_registers: self.v_registers,
+            i_register: self.i_register,
+            delay_timer: self.delay_timer,
+            sound_timer: self.sound_timer,
+            program_counter: self.program_counter,
+            memory,
+            display,
+            keyboard,
+            stack: &mut self.stack,
+            stack_pointer: &mut self.stack_pointer,
+            rng: &mut self.rng,
+        };
+
+        let result = block(&mut state);
+
+        self.v_registers = state.v_registers;
+        self.i_register = state.i_register;
+        self.delay_timer = state.delay_timer;
+        self.sound_timer = state.sound_timer;
+        self.program_counter = state.program_counter;
+
+        result.or_else(|fault| self.halt(fault))
     }
 
     pub fn timer_cycle(&mut self) {
         self.delay_timer = self.delay_timer.saturating_sub(1);
         self.sound_timer = self.sound_timer.saturating_sub(1);
     }
+
+    // Moves on to the next instruction, wrapping at the end of memory
+    fn skip(&mut self) {
+        self.program_counter = (self.program_counter + 2) & ADDRESS_MASK;
+    }
+
+    fn shift_source(&self, vx: Register, vy: Register) -> Value {
+        if self.quirks.shift_uses_vy {
+            self.v_registers[vy as usize]
+        } else {
+            self.v_registers[vx as usize]
+        }
+    }
+
+    fn reset_flag_after_logic(&mut self) {
+        if self.quirks.logic_resets_vf {
+            self.v_registers[0xF] = 0;
+        }
+    }
 }
 
 // impl fmt::Debug for Memory {
@@ -234,3 +551,484 @@ impl Cpu {
 //         write!(f, "{{ data: {:?} }}", self.data)
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::WIDTH;
+    use crate::null_display_driver::NullDisplayDriver;
+
+    const START: Address = chip8::PROGRAM_START;
+
+    // Builds a machine in a given state, then runs opcodes against it one at a time
+    struct TestMachine {
+        cpu: Cpu,
+        memory: Memory,
+        display: Display<NullDisplayDriver>,
+        keyboard: Keyboard,
+    }
+
+    impl TestMachine {
+        fn new(quirks: Quirks) -> TestMachine {
+            let mut cpu = Cpu::new(START);
+            cpu.set_quirks(quirks);
+
+            TestMachine {
+                cpu,
+                memory: Memory::new(),
+                display: Display::new(NullDisplayDriver::new()),
+                keyboard: Keyboard::new(),
+            }
+        }
+
+        fn register(mut self, register: usize, value: Value) -> TestMachine {
+            self.cpu.v_registers[register] = value;
+            self
+        }
+
+        fn index(mut self, address: Address) -> TestMachine {
+            self.cpu.i_register = address;
+            self
+        }
+
+        fn at(mut self, address: Address) -> TestMachine {
+            self.cpu.program_counter = address;
+            self
+        }
+
+        fn memory(mut self, address: Address, data: &[u8]) -> TestMachine {
+            self.memory.write_bytes(address, data);
+            self
+        }
+
+        fn delay_timer(mut self, value: Value) -> TestMachine {
+            self.cpu.delay_timer = value;
+            self
+        }
+
+        fn key_down(mut self, key: u8) -> TestMachine {
+            self.keyboard.key_down(key);
+            self
+        }
+
+        fn key_released(mut self, key: u8) -> TestMachine {
+            self.keyboard.key_down(key);
+            self.keyboard.key_up();
+            self
+        }
+
+        // Places the opcode at the program counter and executes it
+        fn run(mut self, opcode: u16) -> TestMachine {
+            self.memory.write_bytes(self.cpu.program_counter, &opcode.to_be_bytes());
+            self.step(1)
+        }
+
+        // Executes whatever is already in memory
+        fn step(mut self, count: usize) -> TestMachine {
+            for _ in 0..count {
+                self.cycle().unwrap();
+            }
+            self
+        }
+
+        fn cycle(&mut self) -> Result<(), Fault> {
+            let result = self.cpu.cycle(&mut self.memory, &mut self.display, &self.keyboard);
+            self.display.draw_if_dirty();
+            result
+        }
+
+        fn v(&self, register: usize) -> Value {
+            self.cpu.v_registers[register]
+        }
+
+        fn pc(&self) -> Address {
+            self.cpu.program_counter
+        }
+
+        fn pixel(&self, x: usize, y: usize) -> bool {
+            self.display.framebuffer()[x + y * WIDTH]
+        }
+    }
+
+    fn each_profile<F: Fn(&str, Quirks)>(test: F) {
+        for (name, quirks) in Quirks::PROFILES {
+            test(name, quirks);
+        }
+    }
+
+    #[test]
+    fn test_clear_screen() {
+        each_profile(|name, quirks| {
+            let machine = TestMachine::new(quirks)
+                .index(0x300)
+                .memory(0x300, &[0x80])
+                .run(0xD011)
+                .run(0x00E0);
+            assert!(!machine.pixel(0, 0), "{}", name);
+        });
+    }
+
+    #[test]
+    fn test_call_overflows_stack() {
+        // Calls itself forever
+        let mut machine = TestMachine::new(Quirks::default()).memory(START, &[0x22, 0x00]).step(STACK_SIZE);
+        let fault = Fault::StackOverflow { address: START };
+
+        assert_eq!(machine.cycle(), Err(fault));
+        assert_eq!((machine.cpu.fault(), machine.pc()), (Some(fault), START));
+        assert_eq!(machine.cycle(), Err(fault), "stays halted");
+    }
+
+    #[test]
+    fn test_return_with_empty_stack_faults() {
+        let mut machine = TestMachine::new(Quirks::default()).memory(START, &[0x00, 0xEE]);
+        assert_eq!(machine.cycle(), Err(Fault::StackUnderflow { address: START }));
+        assert_eq!(machine.pc(), START);
+    }
+
+    #[test]
+    fn test_unknown_opcode_faults() {
+        for decode_cache in [true, false] {
+            let mut machine = TestMachine::new(Quirks::default()).memory(START, &[0xFF, 0xFF]);
+            machine.cpu.set_decode_cache(decode_cache);
+
+            assert_eq!(machine.cycle(), Err(Fault::UnknownOpcode { address: START, opcode: 0xFFFF }));
+        }
+    }
+
+    #[test]
+    fn test_call_and_return() {
+        each_profile(|name, quirks| {
+            let machine = TestMachine::new(quirks).run(0x2400);
+            assert_eq!(machine.pc(), 0x400, "{}", name);
+            assert_eq!(machine.cpu.stack[..machine.cpu.stack_pointer], [START + 2], "{}", name);
+
+            let machine = machine.run(0x00EE);
+            assert_eq!(machine.pc(), START + 2, "{}", name);
+            assert_eq!(machine.cpu.stack_pointer, 0, "{}", name);
+        });
+    }
+
+    #[test]
+    fn test_jump() {
+        each_profile(|name, quirks| {
+            assert_eq!(TestMachine::new(quirks).run(0x1ABC).pc(), 0xABC, "{}", name);
+        });
+    }
+
+    #[test]
+    fn test_jump_with_offset() {
+        let machine = |quirks| TestMachine::new(quirks).register(0x0, 0x10).register(0x2, 0x20).run(0xB234);
+
+        assert_eq!(machine(Quirks::default()).pc(), 0x244);
+        assert_eq!(machine(Quirks::COSMAC_VIP).pc(), 0x244);
+        assert_eq!(machine(Quirks::SUPER_CHIP).pc(), 0x254);
+    }
+
+    #[test]
+    fn test_skips() {
+        each_profile(|name, quirks| {
+            let machine = || TestMachine::new(quirks).register(0x1, 0x42).register(0x2, 0x42).register(0x3, 0x07);
+
+            assert_eq!(machine().run(0x3142).pc(), START + 4, "3XNN equal {}", name);
+            assert_eq!(machine().run(0x3143).pc(), START + 2, "3XNN not equal {}", name);
+            assert_eq!(machine().run(0x4143).pc(), START + 4, "4XNN not equal {}", name);
+            assert_eq!(machine().run(0x4142).pc(), START + 2, "4XNN equal {}", name);
+            assert_eq!(machine().run(0x5120).pc(), START + 4, "5XY0 equal {}", name);
+            assert_eq!(machine().run(0x5130).pc(), START + 2, "5XY0 not equal {}", name);
+            assert_eq!(machine().run(0x9130).pc(), START + 4, "9XY0 not equal {}", name);
+            assert_eq!(machine().run(0x9120).pc(), START + 2, "9XY0 equal {}", name);
+        });
+    }
+
+    #[test]
+    fn test_set_and_add_value() {
+        each_profile(|name, quirks| {
+            let machine = TestMachine::new(quirks).register(0xF, 0x5).run(0x61FE).run(0x7103);
+            assert_eq!(machine.v(0x1), 0x01, "7XNN wraps {}", name);
+            assert_eq!(machine.v(0xF), 0x5, "7XNN leaves VF alone {}", name);
+        });
+    }
+
+    #[test]
+    fn test_set_register() {
+        each_profile(|name, quirks| {
+            assert_eq!(TestMachine::new(quirks).register(0x2, 0x99).run(0x8120).v(0x1), 0x99, "{}", name);
+        });
+    }
+
+    #[test]
+    fn test_logic() {
+        each_profile(|name, quirks| {
+            let machine = || TestMachine::new(quirks).register(0x1, 0b1100).register(0x2, 0b1010).register(0xF, 0x7);
+            let flag = if quirks.logic_resets_vf { 0 } else { 0x7 };
+
+            let or = machine().run(0x8121);
+            assert_eq!((or.v(0x1), or.v(0xF)), (0b1110, flag), "OR {}", name);
+
+            let and = machine().run(0x8122);
+            assert_eq!((and.v(0x1), and.v(0xF)), (0b1000, flag), "AND {}", name);
+
+            let xor = machine().run(0x8123);
+            assert_eq!((xor.v(0x1), xor.v(0xF)), (0b0110, flag), "XOR {}", name);
+        });
+    }
+
+    #[test]
+    fn test_add_with_carry() {
+        each_profile(|name, quirks| {
+            let carry = TestMachine::new(quirks).register(0x1, 0xFF).register(0x2, 0x02).run(0x8124);
+            assert_eq!((carry.v(0x1), carry.v(0xF)), (0x01, 1), "{}", name);
+
+            let no_carry = TestMachine::new(quirks).register(0x1, 0x01).register(0x2, 0x02).register(0xF, 1).run(0x8124);
+            assert_eq!((no_carry.v(0x1), no_carry.v(0xF)), (0x03, 0), "{}", name);
+
+            // The flag is written last, so it wins when VF is the destination
+            let into_vf = TestMachine::new(quirks).register(0xF, 0xFF).register(0x2, 0x02).run(0x8F24);
+            assert_eq!(into_vf.v(0xF), 1, "{}", name);
+        });
+    }
+
+    #[test]
+    fn test_subtract_with_borrow() {
+        each_profile(|name, quirks| {
+            let no_borrow = TestMachine::new(quirks).register(0x1, 0x05).register(0x2, 0x03).run(0x8125);
+            assert_eq!((no_borrow.v(0x1), no_borrow.v(0xF)), (0x02, 1), "8XY5 {}", name);
+
+            let borrow = TestMachine::new(quirks).register(0x1, 0x03).register(0x2, 0x05).run(0x8125);
+            assert_eq!((borrow.v(0x1), borrow.v(0xF)), (0xFE, 0), "8XY5 {}", name);
+
+            let reversed = TestMachine::new(quirks).register(0x1, 0x03).register(0x2, 0x05).run(0x8127);
+            assert_eq!((reversed.v(0x1), reversed.v(0xF)), (0x02, 1), "8XY7 {}", name);
+
+            let into_vf = TestMachine::new(quirks).register(0xF, 0x03).register(0x2, 0x05).run(0x8F25);
+            assert_eq!(into_vf.v(0xF), 0, "8FY5 {}", name);
+
+            let reversed_into_vf = TestMachine::new(quirks).register(0xF, 0x03).register(0x2, 0x05).run(0x8F27);
+            assert_eq!(reversed_into_vf.v(0xF), 1, "8FY7 {}", name);
+        });
+    }
+
+    #[test]
+    fn test_shifts() {
+        each_profile(|name, quirks| {
+            let machine = || TestMachine::new(quirks).register(0x1, 0b1000_0001).register(0x2, 0b0100_0010);
+
+            let right = machine().run(0x8126);
+            let left = machine().run(0x812E);
+
+            if quirks.shift_uses_vy {
+                assert_eq!((right.v(0x1), right.v(0xF)), (0b0010_0001, 0), "8XY6 {}", name);
+                assert_eq!((left.v(0x1), left.v(0xF)), (0b1000_0100, 0), "8XYE {}", name);
+            } else {
+                assert_eq!((right.v(0x1), right.v(0xF)), (0b0100_0000, 1), "8XY6 {}", name);
+                assert_eq!((left.v(0x1), left.v(0xF)), (0b0000_0010, 1), "8XYE {}", name);
+            }
+        });
+    }
+
+    #[test]
+    fn test_self_modifying_code() {
+        // Calls a subroutine that sets V1, patches its 61NN with FX55, then calls it again
+        let program = [0x23, 0x00, 0xA3, 0x01, 0x60, 0x02, 0xF0, 0x55, 0x23, 0x00];
+        let subroutine = [0x61, 0x01, 0x00, 0xEE];
+
+        for decode_cache in [true, false] {
+            let mut machine = TestMachine::new(Quirks::default()).memory(START, &program).memory(0x300, &subroutine);
+            machine.cpu.set_decode_cache(decode_cache);
+
+            let machine = machine.step(3);
+            assert_eq!(machine.v(0x1), 1);
+
+            let machine = machine.step(5);
+            assert_eq!(machine.v(0x1), 2, "decode cache {}", decode_cache);
+        }
+    }
+
+    #[test]
+    fn test_self_modifying_code_across_the_end_of_memory() {
+        // As above, with the subroutine's 61NN at 0xFFF so the patched NN byte is at 0x000
+        let program = [0x2F, 0xFF, 0xA0, 0x00, 0x60, 0x02, 0xF0, 0x55, 0x2F, 0xFF];
+
+        for decode_cache in [true, false] {
+            let mut machine = TestMachine::new(Quirks::default())
+                .memory(START, &program)
+                .memory(0xFFF, &[0x61])
+                .memory(0x000, &[0x01, 0x00, 0xEE]);
+            machine.cpu.set_decode_cache(decode_cache);
+
+            let machine = machine.step(3);
+            assert_eq!(machine.v(0x1), 1);
+
+            let machine = machine.step(5);
+            assert_eq!(machine.v(0x1), 2, "decode cache {}", decode_cache);
+        }
+    }
+
+    #[test]
+    fn test_set_index() {
+        each_profile(|name, quirks| {
+            assert_eq!(TestMachine::new(quirks).run(0xA123).cpu.i_register, 0x123, "{}", name);
+        });
+    }
+
+    #[test]
+    fn test_random_is_masked() {
+        each_profile(|name, quirks| {
+            for _ in 0..32 {
+                let machine = TestMachine::new(quirks).run(0xC10F);
+                assert_eq!(machine.v(0x1) & 0xF0, 0, "{}", name);
+            }
+            assert_eq!(TestMachine::new(quirks).register(0x1, 0xFF).run(0xC100).v(0x1), 0, "{}", name);
+        });
+    }
+
+    #[test]
+    fn test_display_and_collision() {
+        each_profile(|name, quirks| {
+            let machine = TestMachine::new(quirks)
+                .register(0x1, 2)
+                .register(0x2, 3)
+                .index(0x300)
+                .memory(0x300, &[0b1100_0000, 0b0100_0000])
+                .run(0xD122);
+
+            assert!(machine.pixel(2, 3) && machine.pixel(3, 3) && machine.pixel(3, 4), "{}", name);
+            assert!(!machine.pixel(2, 4), "{}", name);
+            assert_eq!(machine.v(0xF), 0, "{}", name);
+
+            let machine = machine.run(0xD121);
+            assert!(!machine.pixel(2, 3), "{}", name);
+            assert_eq!(machine.v(0xF), 1, "{}", name);
+        });
+    }
+
+    #[test]
+    fn test_key_skips() {
+        each_profile(|name, quirks| {
+            let machine = || TestMachine::new(quirks).register(0x1, 0xA);
+
+            assert_eq!(machine().key_down(0xA).run(0xE19E).pc(), START + 4, "EX9E pressed {}", name);
+            assert_eq!(machine().run(0xE19E).pc(), START + 2, "EX9E not pressed {}", name);
+            assert_eq!(machine().run(0xE1A1).pc(), START + 4, "EXA1 not pressed {}", name);
+            assert_eq!(machine().key_down(0xA).run(0xE1A1).pc(), START + 2, "EXA1 pressed {}", name);
+        });
+    }
+
+    #[test]
+    fn test_wait_for_key_press() {
+        each_profile(|name, quirks| {
+            let waiting = TestMachine::new(quirks).key_down(0x3).run(0xF10A);
+            assert_eq!(waiting.pc(), START, "blocks until released {}", name);
+
+            let released = TestMachine::new(quirks).key_released(0x3).run(0xF10A);
+            assert_eq!((released.pc(), released.v(0x1)), (START + 2, 0x3), "{}", name);
+
+            let mut nothing_held = TestMachine::new(quirks);
+            nothing_held.keyboard.key_up();
+            assert_eq!(nothing_held.run(0xF10A).pc(), START, "ignores a release of nothing {}", name);
+        });
+    }
+
+    #[test]
+    fn test_timers() {
+        each_profile(|name, quirks| {
+            let machine = TestMachine::new(quirks).delay_timer(0x20).run(0xF107);
+            assert_eq!(machine.v(0x1), 0x20, "FX07 {}", name);
+
+            let machine = TestMachine::new(quirks).register(0x1, 0x30).run(0xF115).run(0xF118);
+            assert_eq!((machine.cpu.delay_timer, machine.cpu.sound_timer), (0x30, 0x30), "FX15/FX18 {}", name);
+        });
+    }
+
+    #[test]
+    fn test_add_register_to_index() {
+        each_profile(|name, quirks| {
+            let in_range = TestMachine::new(quirks).register(0x1, 0x10).register(0xF, 0x5).index(0x100).run(0xF11E);
+            let overflow = TestMachine::new(quirks).register(0x1, 0x10).register(0xF, 0x5).index(0xFF8).run(0xF11E);
+
+            assert_eq!(in_range.cpu.i_register, 0x110, "{}", name);
+            assert_eq!(overflow.cpu.i_register, 0x008, "{}", name);
+
+            if quirks.index_overflow_sets_vf {
+                assert_eq!((in_range.v(0xF), overflow.v(0xF)), (0, 1), "{}", name);
+            } else {
+                assert_eq!((in_range.v(0xF), overflow.v(0xF)), (0x5, 0x5), "{}", name);
+            }
+        });
+    }
+
+    #[test]
+    fn test_addresses_wrap_at_end_of_memory() {
+        each_profile(|name, quirks| {
+            let fetched = TestMachine::new(quirks).at(0xFFE).memory(0xFFE, &[0x00, 0xE0]).memory(0x000, &[0x12, 0x34]).step(2);
+            assert_eq!(fetched.pc(), 0x234, "fetch wraps {}", name);
+
+            let skipped = TestMachine::new(quirks).at(0xFFC).run(0x3000);
+            assert_eq!(skipped.pc(), 0x000, "skip wraps {}", name);
+
+            let jumped = TestMachine::new(quirks).register(0x0, 0xFF).register(0xF, 0xFF).run(0xBFFF);
+            assert_eq!(jumped.pc(), 0x0FE, "BNNN wraps {}", name);
+
+            let word = TestMachine::new(quirks).memory(0xFFF, &[0xAB]).memory(0x000, &[0xCD]);
+            assert_eq!(word.memory.read_word(0xFFF), 0xABCD, "{}", name);
+
+            let stored = TestMachine::new(quirks).register(0x0, 1).register(0x1, 2).register(0x2, 3).index(0xFFE).run(0xF255);
+            let memory = [0xFFE, 0xFFF, 0x000].map(|address| stored.memory.read_byte(address));
+            assert_eq!(memory, [1, 2, 3], "FX55 wraps {}", name);
+            if quirks.load_store_increments_index {
+                assert_eq!(stored.cpu.i_register, 0x001, "FX55 wraps I {}", name);
+            }
+
+            let loaded = TestMachine::new(quirks).index(0xFFF).memory(0xFFF, &[7]).memory(0x000, &[8]).run(0xF165);
+            assert_eq!([loaded.v(0), loaded.v(1)], [7, 8], "FX65 wraps {}", name);
+
+            let bcd = TestMachine::new(quirks).register(0x1, 254).index(0xFFF).run(0xF133);
+            assert_eq!([0xFFF, 0x000, 0x001].map(|address| bcd.memory.read_byte(address)), [2, 5, 4], "FX33 wraps {}", name);
+
+            let drawn = TestMachine::new(quirks).index(0xFFF).memory(0xFFF, &[0x80]).memory(0x000, &[0x80]).run(0xD012);
+            assert!(drawn.pixel(0, 0) && drawn.pixel(0, 1), "DXYN wraps {}", name);
+        });
+    }
+
+    #[test]
+    fn test_set_index_to_sprite() {
+        each_profile(|name, quirks| {
+            let machine = TestMachine::new(quirks).register(0x1, 0xA).run(0xF129);
+            assert_eq!(machine.cpu.i_register, chip8::FONT_START + 50, "{}", name);
+        });
+    }
+
+    #[test]
+    fn test_store_bcd() {
+        each_profile(|name, quirks| {
+            let machine = TestMachine::new(quirks).register(0x1, 254).index(0x300).run(0xF133);
+            let digits = [0x300, 0x301, 0x302].map(|address| machine.memory.read_byte(address));
+
+            assert_eq!(digits, [2, 5, 4], "{}", name);
+            assert_eq!(machine.cpu.i_register, 0x300, "{}", name);
+        });
+    }
+
+    #[test]
+    fn test_store_and_load_registers() {
+        each_profile(|name, quirks| {
+            let expected_index = if quirks.load_store_increments_index { 0x303 } else { 0x300 };
+
+            let stored = TestMachine::new(quirks)
+                .register(0x0, 1)
+                .register(0x1, 2)
+                .register(0x2, 3)
+                .register(0x3, 4)
+                .index(0x300)
+                .run(0xF255);
+            let memory = [0x300, 0x301, 0x302, 0x303].map(|address| stored.memory.read_byte(address));
+            assert_eq!(memory, [1, 2, 3, 0], "FX55 {}", name);
+            assert_eq!(stored.cpu.i_register, expected_index, "FX55 {}", name);
+
+            let loaded = TestMachine::new(quirks).index(0x300).memory(0x300, &[7, 8, 9, 10]).run(0xF265);
+            assert_eq!([loaded.v(0), loaded.v(1), loaded.v(2), loaded.v(3)], [7, 8, 9, 0], "FX65 {}", name);
+            assert_eq!(loaded.cpu.i_register, expected_index, "FX65 {}", name);
+        });
+    }
+}
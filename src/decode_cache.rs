@@ -0,0 +1,76 @@
+use crate::instructions::Instruction;
+
+// Decoded instructions by address, so each opcode is only decoded once. Memory
+// invalidates entries as it's written, so self-modifying programs still work.
+#[derive(Clone)]
+pub struct DecodeCache<const SIZE: usize> {
+    entries: [Option<Instruction>; SIZE],
+}
+
+impl<const SIZE: usize> DecodeCache<SIZE> {
+    pub fn new() -> DecodeCache<SIZE> {
+        DecodeCache {
+            entries: [None; SIZE],
+        }
+    }
+
+    // None for an unknown opcode, which is decoded again each time in case it's been fixed up
+    pub fn get_or_decode<F: FnOnce() -> u16>(&mut self, address: u16, opcode: F) -> Option<Instruction> {
+        let entry = &mut self.entries[address as usize];
+        if entry.is_none() {
+            *entry = Instruction::try_decode(opcode());
+        }
+        *entry
+    }
+
+    // An instruction spans two bytes, so the one starting just before the write is affected too.
+    // Fetches wrap, so before the first address is the last one.
+    pub fn invalidate(&mut self, address: u16, length: usize) {
+        let start = address as usize;
+        let end = (start + length).min(SIZE);
+
+        self.entries[(start + SIZE - 1) % SIZE] = None;
+        self.entries[start..end].fill(None);
+    }
+}
+
+impl<const SIZE: usize> Default for DecodeCache<SIZE> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalidate_covers_overlapping_instructions() {
+        let mut cache = DecodeCache::<16>::new();
+        for address in 0..16 {
+            cache.get_or_decode(address, || 0x00E0);
+        }
+
+        cache.invalidate(5, 2);
+
+        let redecoded: Vec<u16> = (0..16)
+            .filter(|&address| cache.get_or_decode(address, || 0x00EE) == Some(Instruction::Return))
+            .collect();
+        assert_eq!(redecoded, vec![4, 5, 6]);
+    }
+
+    #[test]
+    fn test_invalidate_wraps_to_the_last_address() {
+        let mut cache = DecodeCache::<16>::new();
+        for address in 0..16 {
+            cache.get_or_decode(address, || 0x00E0);
+        }
+
+        cache.invalidate(0, 1);
+
+        let redecoded: Vec<u16> = (0..16)
+            .filter(|&address| cache.get_or_decode(address, || 0x00EE) == Some(Instruction::Return))
+            .collect();
+        assert_eq!(redecoded, vec![0, 15]);
+    }
+}
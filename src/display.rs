@@ -1,13 +1,44 @@
+use crate::state;
+
 pub const WIDTH: usize = 64;
 pub const HEIGHT: usize = 32;
 
+// Each row of the screen is packed into one word, with the leftmost pixel in the highest bit
+pub type Row = u64;
+const _: () = assert!(Row::BITS as usize == WIDTH);
+
+pub const STATE_SIZE: usize = WIDTH * HEIGHT / 8;
+
+// One line per row, with '#' for lit pixels and '.' for unlit ones
+#[cfg(feature = "std")]
+pub fn to_ascii(pixels: &[bool; WIDTH * HEIGHT]) -> String {
+    let mut ascii = String::with_capacity((WIDTH + 1) * HEIGHT);
+
+    for row in pixels.chunks(WIDTH) {
+        ascii.extend(row.iter().map(|&pixel_on| if pixel_on { '#' } else { '.' }));
+        ascii.push('\n');
+    }
+
+    ascii
+}
+
+// FNV-1a over the pixels, stable across platforms and releases so it can be stored in test files
+pub fn screen_hash(pixels: &[bool; WIDTH * HEIGHT]) -> u64 {
+    pixels.iter().fold(0xcbf29ce484222325, |hash, &pixel_on| {
+        (hash ^ pixel_on as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
 pub trait DisplayDriver {
-    fn draw_pixels(&mut self, data: [bool; WIDTH * HEIGHT]);
+    fn draw_pixels(&mut self, data: &[bool; WIDTH * HEIGHT]);
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Display<DisplayDriverT: DisplayDriver> {
-    screen: [bool; WIDTH * HEIGHT],
+    rows: [Row; HEIGHT],
+    // Unpacked copy of `rows` as of the last draw, handed to the driver
+    pixels: [bool; WIDTH * HEIGHT],
+    changed_rows: [bool; HEIGHT],
     display_driver: DisplayDriverT,
     dirty: bool,
     collision: bool
@@ -16,7 +47,9 @@ pub struct Display<DisplayDriverT: DisplayDriver> {
 impl<DisplayDriverT: DisplayDriver> Display<DisplayDriverT> {
     pub fn new(display_driver: DisplayDriverT) -> Display<DisplayDriverT> {
         Display{
-            screen: [false; WIDTH * HEIGHT],
+            rows: [0; HEIGHT],
+            pixels: [false; WIDTH * HEIGHT],
+            changed_rows: [true; HEIGHT],
             display_driver,
             dirty: true,
             collision: false
@@ -24,17 +57,54 @@ impl<DisplayDriverT: DisplayDriver> Display<DisplayDriverT> {
     }
 
     pub fn clear(&mut self) {
-        self.screen = [false; WIDTH * HEIGHT];
+        self.rows = [0; HEIGHT];
+        self.changed_rows = [true; HEIGHT];
+        self.dirty = true;
+    }
+
+    pub fn save_state(&self, writer: &mut state::Writer) {
+        for row in self.rows {
+            writer.bytes(&row.to_be_bytes());
+        }
+    }
+
+    pub fn load_state(&mut self, reader: &mut state::Reader) {
+        for row in &mut self.rows {
+            *row = Row::from_be_bytes(reader.bytes(WIDTH / 8).try_into().unwrap());
+        }
+        self.changed_rows = [true; HEIGHT];
+        self.dirty = true;
+    }
+
+    pub fn redraw(&mut self) {
         self.dirty = true;
     }
 
     pub fn draw_if_dirty(&mut self) {
         if self.dirty {
-            self.display_driver.draw_pixels(self.screen);
+            self.unpack();
+            self.display_driver.draw_pixels(&self.pixels);
             self.dirty = false;
         }
     }
 
+    // As of the last draw, which `Chip8` does after every cycle
+    pub fn framebuffer(&self) -> &[bool; WIDTH * HEIGHT] {
+        &self.pixels
+    }
+
+    pub fn rows(&self) -> &[Row; HEIGHT] {
+        &self.rows
+    }
+
+    pub fn display_driver(&self) -> &DisplayDriverT {
+        &self.display_driver
+    }
+
+    pub fn display_driver_mut(&mut self) -> &mut DisplayDriverT {
+        &mut self.display_driver
+    }
+
     pub fn did_collide(&self) -> bool {
         self.collision
     }
@@ -45,21 +115,21 @@ impl<DisplayDriverT: DisplayDriver> Display<DisplayDriverT> {
         if (y as u16) >= clip_y {
             return;
         }
-        
-        let ix = (x as usize % WIDTH) + ((y as usize % HEIGHT) * WIDTH);
-        for i in 0..8 {
-            let pixel = pixels & (1 << (7 - i)) != 0;
-
-            // Screen width
-            let clip_x = WIDTH + ((y as usize % HEIGHT) * WIDTH);
-
-            let pixel_pos = ix + i;
-            
-            if pixel_pos < clip_x {
-                self.collision |= self.screen[pixel_pos] & pixel;
-                self.screen[pixel_pos] ^= pixel;
-            }
-        }
+
+        // Sprites wrap to the start of the screen but are clipped at the right edge
+        let x = x as usize % WIDTH;
+        let shift = (WIDTH - 8) as isize - x as isize;
+        let sprite = if shift >= 0 {
+            (pixels as Row) << shift
+        } else {
+            (pixels as Row) >> -shift
+        };
+
+        let y = y as usize % HEIGHT;
+        let row = &mut self.rows[y];
+        self.collision |= *row & sprite != 0;
+        *row ^= sprite;
+        self.changed_rows[y] |= sprite != 0;
 
         self.dirty = true;
     }
@@ -67,4 +137,81 @@ impl<DisplayDriverT: DisplayDriver> Display<DisplayDriverT> {
     pub fn begin_draw(&mut self) {
         self.collision = false;
     }
-}
\ No newline at end of file
+
+    fn unpack(&mut self) {
+        for (y, row) in self.rows.iter().enumerate() {
+            if !core::mem::take(&mut self.changed_rows[y]) {
+                continue;
+            }
+
+            for (x, pixel) in self.pixels[y * WIDTH..(y + 1) * WIDTH].iter_mut().enumerate() {
+                *pixel = row & (1 << (WIDTH - 1 - x)) != 0;
+            }
+        }
+    }
+}
+
+// The original unpacked implementation, a bool per pixel. Kept to check the packed one against
+// and to benchmark it alongside.
+#[doc(hidden)]
+pub fn reference_set_pixels(screen: &mut [bool; WIDTH * HEIGHT], x: u8, y: u8, pixels: u8, start_y: u8) -> bool {
+    let clip_y = (((start_y as usize / HEIGHT) + 1) * HEIGHT) as u16;
+    let mut collision = false;
+
+    if (y as u16) >= clip_y {
+        return collision;
+    }
+
+    let ix = (x as usize % WIDTH) + ((y as usize % HEIGHT) * WIDTH);
+    for i in 0..8 {
+        let pixel = pixels & (1 << (7 - i)) != 0;
+        let clip_x = WIDTH + ((y as usize % HEIGHT) * WIDTH);
+        let pixel_pos = ix + i;
+
+        if pixel_pos < clip_x {
+            collision |= screen[pixel_pos] & pixel;
+            screen[pixel_pos] ^= pixel;
+        }
+    }
+
+    collision
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::null_display_driver::NullDisplayDriver;
+
+    #[test]
+    fn test_set_pixels_matches_reference() {
+        let mut display = Display::new(NullDisplayDriver::new());
+        let mut screen = [false; WIDTH * HEIGHT];
+
+        for x in (0..=255u8).step_by(7) {
+            for y in (0..=255u8).step_by(5) {
+                let pixels = x.wrapping_mul(31) ^ y;
+                let start_y = y.saturating_sub(3);
+
+                display.begin_draw();
+                display.set_pixels(x, y, pixels, start_y);
+                let collision = reference_set_pixels(&mut screen, x, y, pixels, start_y);
+
+                assert_eq!(display.did_collide(), collision, "collision at x={} y={}", x, y);
+            }
+        }
+
+        display.draw_if_dirty();
+        assert_eq!(display.framebuffer(), &screen);
+    }
+
+    #[test]
+    fn test_sprite_clipped_at_right_edge() {
+        let mut display = Display::new(NullDisplayDriver::new());
+        display.set_pixels(60, 0, 0xFF, 0);
+        display.draw_if_dirty();
+
+        assert_eq!(display.rows()[0], 0xF);
+        assert!(display.framebuffer()[WIDTH - 1]);
+        assert!(!display.framebuffer()[WIDTH]);
+    }
+}
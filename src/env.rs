@@ -0,0 +1,306 @@
+use std::sync::Arc;
+
+use crate::chip8::{self, Chip8, RomTooLarge};
+use crate::display::{WIDTH, HEIGHT};
+use crate::memory;
+use crate::null_display_driver::NullDisplayDriver;
+use crate::quirks::Quirks;
+
+pub type Machine = Chip8<NullDisplayDriver>;
+pub type Observation = [bool; WIDTH * HEIGHT];
+
+// A number a game keeps in memory, like a score or a count of lives
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Counter {
+    Byte(u16),
+    // Big-endian, like everything else CHIP-8 stores
+    Word(u16),
+    // One decimal digit per byte, most significant first, as FX33 writes them
+    Bcd { address: u16, digits: u8 },
+}
+
+impl Counter {
+    // Every byte of the counter has to be in memory
+    fn check(&self) -> Result<(), String> {
+        let (address, length) = match *self {
+            Counter::Byte(address) => (address, 1),
+            Counter::Word(address) => (address, 2),
+            Counter::Bcd { digits: 0, .. } => return Err("BCD counter needs at least one digit".to_string()),
+            Counter::Bcd { address, digits } => (address, digits as usize),
+        };
+        if address as usize + length > memory::SIZE {
+            return Err(format!("{:?} runs past the end of memory", self));
+        }
+        Ok(())
+    }
+
+    pub fn read(&self, chip8: &Machine) -> u32 {
+        match *self {
+            Counter::Byte(address) => chip8.read_byte(address) as u32,
+            Counter::Word(address) => (chip8.read_byte(address) as u32) << 8 | chip8.read_byte(address.wrapping_add(1)) as u32,
+            Counter::Bcd { address, digits } => (0..digits as u16)
+                .fold(0, |value, digit| value * 10 + chip8.read_byte(address.wrapping_add(digit)) as u32),
+        }
+    }
+}
+
+// Scores each step from the machine as it is afterwards. Closures taking the machine work too.
+pub trait Reward {
+    // Called with the machine at the start of each episode
+    fn reset(&mut self, _chip8: &Machine) {}
+
+    fn reward(&mut self, chip8: &Machine) -> f64;
+}
+
+impl<F: FnMut(&Machine) -> f64> Reward for F {
+    fn reward(&mut self, chip8: &Machine) -> f64 {
+        self(chip8)
+    }
+}
+
+// How much a counter changed by over the step, e.g. points scored or lives lost
+#[derive(Debug, Clone)]
+pub struct CounterChange {
+    counter: Counter,
+    last: u32,
+}
+
+impl CounterChange {
+    pub fn new(counter: Counter) -> Result<CounterChange, String> {
+        counter.check()?;
+        Ok(CounterChange { counter, last: 0 })
+    }
+}
+
+impl Reward for CounterChange {
+    fn reset(&mut self, chip8: &Machine) {
+        self.last = self.counter.read(chip8);
+    }
+
+    fn reward(&mut self, chip8: &Machine) -> f64 {
+        let value = self.counter.read(chip8);
+        let change = value as f64 - self.last as f64;
+        self.last = value;
+        change
+    }
+}
+
+// A ROM as a reinforcement learning environment. Each action holds one key, or none, for
+// `frame_skip` frames. Clones copy the machine but share the ROM and actions, so searches can
+// cheaply branch from any point.
+#[derive(Clone)]
+pub struct Env<R: Reward> {
+    chip8: Machine,
+    rom: Arc<[u8]>,
+    quirks: Quirks,
+    actions: Arc<[Option<u8>]>,
+    reward: R,
+    frame_skip: u32,
+    done_when: Option<(Counter, u32)>,
+    max_steps: Option<u64>,
+    steps: u64,
+}
+
+impl<R: Reward> Env<R> {
+    // Starts an episode seeded with 0. By default the actions are no key, then each key in order.
+    pub fn new(rom: &[u8], reward: R) -> Result<Env<R>, String> {
+        if rom.len() > chip8::MAX_ROM_SIZE {
+            return Err(RomTooLarge { size: rom.len() }.to_string());
+        }
+
+        let mut env = Env {
+            chip8: Chip8::new(NullDisplayDriver::new()),
+            rom: rom.into(),
+            quirks: Quirks::default(),
+            actions: [None].into_iter().chain((0..16).map(Some)).collect(),
+            reward,
+            frame_skip: 1,
+            done_when: None,
+            max_steps: None,
+            steps: 0,
+        };
+        env.reset(0);
+        Ok(env)
+    }
+
+    // Restarts the episode with these quirks
+    pub fn with_quirks(mut self, quirks: Quirks) -> Env<R> {
+        self.quirks = quirks;
+        self.reset(0);
+        self
+    }
+
+    // The key each action holds, with None for no key
+    pub fn with_actions(mut self, actions: &[Option<u8>]) -> Env<R> {
+        assert!(actions.iter().flatten().all(|&key| key < 16), "Invalid key in actions: {:?}", actions);
+        self.actions = actions.into();
+        self
+    }
+
+    pub fn with_frame_skip(mut self, frame_skip: u32) -> Env<R> {
+        assert!(frame_skip > 0, "frame_skip must be at least 1");
+        self.frame_skip = frame_skip;
+        self
+    }
+
+    // Ends the episode once the counter reaches the value, e.g. when lives reach 0
+    pub fn with_done_when(mut self, counter: Counter, value: u32) -> Result<Env<R>, String> {
+        counter.check()?;
+        self.done_when = Some((counter, value));
+        Ok(self)
+    }
+
+    pub fn with_max_steps(mut self, max_steps: u64) -> Env<R> {
+        self.max_steps = Some(max_steps);
+        self
+    }
+
+    pub fn reset(&mut self, seed: u64) -> &Observation {
+        self.chip8 = Chip8::new(NullDisplayDriver::new());
+        self.chip8.set_quirks(self.quirks);
+        self.chip8.seed_rng(seed);
+        self.chip8.load_default_font();
+        self.chip8.load_rom(&self.rom).expect("ROM checked to fit when the environment was made");
+
+        self.reward.reset(&self.chip8);
+        self.steps = 0;
+        self.chip8.framebuffer()
+    }
+
+    // Returns the screen, the reward for the step, and whether the episode is over
+    pub fn step(&mut self, action: usize) -> Result<(&Observation, f64, bool), String> {
+        let &key = self.actions.get(action)
+            .ok_or_else(|| format!("Invalid action: {} (there are {})", action, self.actions.len()))?;
+        let mut faulted = false;
+        for _ in 0..self.frame_skip {
+            if self.chip8.run_frame_holding(key, chip8::INSTRUCTIONS_PER_FRAME).is_err() {
+                faulted = true;
+                break;
+            }
+        }
+        self.steps += 1;
+
+        // A ROM that has faulted can't do anything more, so its episode is over
+        let reward = self.reward.reward(&self.chip8);
+        let done = faulted
+            || self.done_when.is_some_and(|(counter, value)| counter.read(&self.chip8) == value)
+            || self.max_steps.is_some_and(|max_steps| self.steps >= max_steps);
+
+        Ok((self.chip8.framebuffer(), reward, done))
+    }
+
+    pub fn actions(&self) -> &[Option<u8>] {
+        &self.actions
+    }
+
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    pub fn chip8(&self) -> &Machine {
+        &self.chip8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Counts up in V0, storing it at 0x300 each time round
+    const COUNTING_ROM: [u8; 8] = [0xA3, 0x00, 0x70, 0x01, 0xF0, 0x55, 0x12, 0x02];
+
+    // Stores random bytes at 0x300
+    const RANDOM_ROM: [u8; 8] = [0xA3, 0x00, 0xC0, 0xFF, 0xF0, 0x55, 0x12, 0x02];
+
+    // Stores 1 at 0x300 once key 5 is held, then loops
+    const KEY_ROM: [u8; 14] = [
+        0x61, 0x05, // V1 = 5
+        0xE1, 0x9E, // skip unless key V1 is held
+        0x12, 0x02, // jump back to the check
+        0x60, 0x01, // V0 = 1
+        0xA3, 0x00, // I = 0x300
+        0xF0, 0x55, // store V0 at 0x300
+        0x12, 0x0C, // loop forever
+    ];
+
+    #[test]
+    fn test_counters_read_memory() {
+        let mut chip8 = Chip8::new(NullDisplayDriver::new());
+        chip8.load_rom(&[0x01, 0x02, 0x03]).unwrap();
+
+        assert_eq!(Counter::Byte(0x201).read(&chip8), 0x02);
+        assert_eq!(Counter::Word(0x200).read(&chip8), 0x0102);
+        assert_eq!(Counter::Bcd { address: 0x200, digits: 3 }.read(&chip8), 123);
+    }
+
+    #[test]
+    fn test_counters_past_the_end_of_memory_are_rejected() {
+        assert!(CounterChange::new(Counter::Byte(0xFFF)).is_ok());
+        assert!(CounterChange::new(Counter::Word(0xFFE)).is_ok());
+        assert!(CounterChange::new(Counter::Bcd { address: 0xFFD, digits: 3 }).is_ok());
+
+        for counter in [
+            Counter::Byte(0x1000),
+            Counter::Word(0xFFF),
+            Counter::Word(0xFFFF),
+            Counter::Bcd { address: 0xFFE, digits: 3 },
+            Counter::Bcd { address: 0xFFFF, digits: 255 },
+            Counter::Bcd { address: 0x300, digits: 0 },
+        ] {
+            assert!(CounterChange::new(counter).is_err(), "{:?}", counter);
+            assert!(Env::new(&COUNTING_ROM, |_: &Machine| 0.0).unwrap().with_done_when(counter, 0).is_err(), "{:?}", counter);
+        }
+    }
+
+    #[test]
+    fn test_invalid_action_is_an_error() {
+        let mut env = Env::new(&COUNTING_ROM, |_: &Machine| 0.0).unwrap().with_actions(&[None, Some(5)]);
+
+        assert!(env.step(2).is_err());
+        assert_eq!(env.steps(), 0);
+        assert!(env.step(1).is_ok());
+    }
+
+    #[test]
+    fn test_counter_change_rewards_each_step() {
+        let mut env = Env::new(&COUNTING_ROM, CounterChange::new(Counter::Byte(0x300)).unwrap()).unwrap();
+
+        let total: f64 = (0..10).map(|_| env.step(0).unwrap().1).sum();
+
+        assert_eq!(total, Counter::Byte(0x300).read(env.chip8()) as f64);
+        assert!(total > 0.0);
+    }
+
+    #[test]
+    fn test_actions_hold_keys_until_done() {
+        let mut env = Env::new(&KEY_ROM, |_: &Machine| 0.0)
+            .unwrap()
+            .with_actions(&[None, Some(5)])
+            .with_done_when(Counter::Byte(0x300), 1)
+            .unwrap()
+            .with_max_steps(5);
+
+        assert!(!env.step(0).unwrap().2);
+        assert!(env.step(1).unwrap().2);
+
+        env.reset(0);
+        let done: Vec<bool> = (0..5).map(|_| env.step(0).unwrap().2).collect();
+        assert_eq!(done, [false, false, false, false, true]);
+    }
+
+    #[test]
+    fn test_clones_branch_independently() {
+        let mut env = Env::new(&RANDOM_ROM, CounterChange::new(Counter::Byte(0x300)).unwrap())
+            .unwrap()
+            .with_frame_skip(2);
+        env.step(0).unwrap();
+
+        let mut branch = env.clone();
+        let from_original: Vec<f64> = (0..5).map(|_| env.step(3).unwrap().1).collect();
+        let from_branch: Vec<f64> = (0..5).map(|_| branch.step(3).unwrap().1).collect();
+
+        assert_eq!(from_original, from_branch);
+        assert_eq!(Counter::Byte(0x300).read(env.chip8()), Counter::Byte(0x300).read(branch.chip8()));
+        assert_eq!(branch.steps(), 6);
+    }
+}
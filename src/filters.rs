@@ -0,0 +1,168 @@
+use crate::image::Image;
+use crate::palette::Colour;
+
+// Brightness of the dark line between CRT scanlines
+const SCANLINE_BRIGHTNESS: f32 = 0.4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Filter {
+    #[default]
+    None,
+    // EPX / AdvMAME2x, rounds off diagonal edges without adding colours
+    Scale2x,
+    // AdvMAME3x
+    Scale3x,
+    // Scale2x with the rounded corners blended halfway, an hq2x-style softer look
+    Hq2x,
+    // Each pixel becomes 3x3 with a darkened bottom row, like a CRT's scanlines
+    Scanlines,
+}
+
+impl Filter {
+    pub const NAMES: [&'static str; 5] = ["none", "scale2x", "scale3x", "hq2x", "scanlines"];
+
+    pub fn parse(name: &str) -> Result<Filter, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "none" => Ok(Filter::None),
+            "scale2x" | "epx" => Ok(Filter::Scale2x),
+            "scale3x" => Ok(Filter::Scale3x),
+            "hq2x" => Ok(Filter::Hq2x),
+            "scanlines" | "crt" => Ok(Filter::Scanlines),
+            _ => Err(format!("Unknown filter: {} (expected one of {})", name, Filter::NAMES.join(", "))),
+        }
+    }
+
+    // How many times larger the filtered image is in each direction
+    pub fn factor(&self) -> usize {
+        match self {
+            Filter::None => 1,
+            Filter::Scale2x | Filter::Hq2x => 2,
+            Filter::Scale3x | Filter::Scanlines => 3,
+        }
+    }
+
+    pub fn apply(&self, image: &Image) -> Image {
+        match self {
+            Filter::None => image.clone(),
+            Filter::Scale2x => scale2x(image, |_, edge| edge),
+            Filter::Scale3x => scale3x(image),
+            Filter::Hq2x => scale2x(image, |centre, edge| centre.blend(edge, 0.5)),
+            Filter::Scanlines => scanlines(image),
+        }
+    }
+}
+
+// `corner` decides the colour of a corner that EPX would round off towards `edge`
+fn scale2x<F: Fn(Colour, Colour) -> Colour>(image: &Image, corner: F) -> Image {
+    Image::from_fn(image.width() * 2, image.height() * 2, |x, y| {
+        let (sx, sy) = ((x / 2) as isize, (y / 2) as isize);
+
+        let p = image.get(sx, sy);
+        let a = image.get(sx, sy - 1);
+        let b = image.get(sx + 1, sy);
+        let c = image.get(sx - 1, sy);
+        let d = image.get(sx, sy + 1);
+
+        let rounded = match (x % 2, y % 2) {
+            (0, 0) => (c == a && c != d && a != b).then_some(a),
+            (1, 0) => (a == b && a != c && b != d).then_some(b),
+            (0, 1) => (d == c && d != b && c != a).then_some(c),
+            _ => (b == d && b != a && d != c).then_some(d),
+        };
+
+        rounded.map_or(p, |edge| corner(p, edge))
+    })
+}
+
+fn scale3x(image: &Image) -> Image {
+    Image::from_fn(image.width() * 3, image.height() * 3, |x, y| {
+        let (sx, sy) = ((x / 3) as isize, (y / 3) as isize);
+
+        // Neighbourhood laid out as
+        //   a b c
+        //   d e f
+        //   g h i
+        let a = image.get(sx - 1, sy - 1);
+        let b = image.get(sx, sy - 1);
+        let c = image.get(sx + 1, sy - 1);
+        let d = image.get(sx - 1, sy);
+        let e = image.get(sx, sy);
+        let f = image.get(sx + 1, sy);
+        let g = image.get(sx - 1, sy + 1);
+        let h = image.get(sx, sy + 1);
+        let i = image.get(sx + 1, sy + 1);
+
+        let pick = |condition: bool, colour: Colour| if condition { colour } else { e };
+
+        match (x % 3, y % 3) {
+            (0, 0) => pick(d == b && b != f && d != h, d),
+            (1, 0) => pick((d == b && b != f && d != h && e != c) || (b == f && b != d && f != h && e != a), b),
+            (2, 0) => pick(b == f && b != d && f != h, f),
+            (0, 1) => pick((d == b && b != f && d != h && e != g) || (d == h && d != b && h != f && e != a), d),
+            (1, 1) => e,
+            (2, 1) => pick((b == f && b != d && f != h && e != i) || (h == f && d != h && b != f && e != c), f),
+            (0, 2) => pick(d == h && d != b && h != f, d),
+            (1, 2) => pick((d == h && d != b && h != f && e != i) || (h == f && d != h && b != f && e != g), h),
+            _ => pick(h == f && d != h && b != f, f),
+        }
+    })
+}
+
+fn scanlines(image: &Image) -> Image {
+    let black = Colour::rgb(0, 0, 0);
+
+    Image::from_fn(image.width() * 3, image.height() * 3, |x, y| {
+        let colour = image.get((x / 3) as isize, (y / 3) as isize);
+
+        if y % 3 == 2 {
+            black.blend(colour, SCANLINE_BRIGHTNESS)
+        } else {
+            colour
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OFF: Colour = Colour::rgb(0, 0, 0);
+    const ON: Colour = Colour::rgb(0xff, 0xff, 0xff);
+
+    // A 2x2 diagonal, which EPX should smooth into a thinner continuous line
+    fn diagonal() -> Image {
+        Image::from_fn(2, 2, |x, y| if x == y { ON } else { OFF })
+    }
+
+    #[test]
+    fn test_scale2x_rounds_diagonal() {
+        let scaled = Filter::Scale2x.apply(&diagonal());
+
+        assert_eq!((scaled.width(), scaled.height()), (4, 4));
+        // The lit pixel's corner facing away from the line is trimmed
+        assert_eq!(scaled.get(1, 1), OFF);
+        // The unlit pixel's corner between the two lit ones is filled in
+        assert_eq!(scaled.get(1, 2), ON);
+        assert_eq!(scaled.get(0, 3), OFF);
+    }
+
+    #[test]
+    fn test_scale3x_keeps_flat_areas() {
+        let flat = Image::from_fn(3, 3, |_, _| ON);
+        assert!(Filter::Scale3x.apply(&flat).pixels().iter().all(|&colour| colour == ON));
+    }
+
+    #[test]
+    fn test_hq2x_blends_corners() {
+        let scaled = Filter::Hq2x.apply(&diagonal());
+        assert_eq!(scaled.get(1, 2), OFF.blend(ON, 0.5));
+    }
+
+    #[test]
+    fn test_scanlines_darken_every_third_row() {
+        let scaled = Filter::Scanlines.apply(&Image::from_fn(1, 1, |_, _| ON));
+
+        assert_eq!(scaled.get(0, 1), ON);
+        assert_eq!(scaled.get(0, 2), OFF.blend(ON, SCANLINE_BRIGHTNESS));
+    }
+}
@@ -0,0 +1,92 @@
+use crate::display::{WIDTH, HEIGHT};
+use crate::filters::Filter;
+use crate::orientation::Orientation;
+use crate::palette::{Colour, Palette};
+
+// An RGB image of the screen on its way to being presented or exported
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Colour>,
+}
+
+impl Image {
+    pub fn from_fn<F: Fn(usize, usize) -> Colour>(width: usize, height: usize, colour_at: F) -> Image {
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                pixels.push(colour_at(x, y));
+            }
+        }
+
+        Image { width, height, pixels }
+    }
+
+    pub fn from_framebuffer(framebuffer: &[bool; WIDTH * HEIGHT], palette: &Palette) -> Image {
+        Image::from_fn(WIDTH, HEIGHT, |x, y| palette.colour(framebuffer[x + y * WIDTH]))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[Colour] {
+        &self.pixels
+    }
+
+    // Coordinates outside the image are clamped to the nearest edge
+    pub fn get(&self, x: isize, y: isize) -> Colour {
+        let x = x.clamp(0, self.width as isize - 1) as usize;
+        let y = y.clamp(0, self.height as isize - 1) as usize;
+
+        self.pixels[x + y * self.width]
+    }
+
+    pub fn scale_nearest(&self, scale: usize) -> Image {
+        if scale <= 1 {
+            return self.clone();
+        }
+
+        Image::from_fn(self.width * scale, self.height * scale, |x, y| {
+            self.pixels[(x / scale) + (y / scale) * self.width]
+        })
+    }
+
+    pub fn to_rgb_bytes(&self) -> Vec<u8> {
+        self.pixels.iter().flat_map(|colour| [colour.r, colour.g, colour.b]).collect()
+    }
+}
+
+// How the screen is turned into images for screenshots and recordings
+#[derive(Debug, Clone, Copy)]
+pub struct ImageSettings {
+    pub palette: Palette,
+    pub orientation: Orientation,
+    pub filter: Filter,
+    // Size of each CHIP-8 pixel in the output, including any upscaling done by the filter
+    pub scale: u32,
+}
+
+impl ImageSettings {
+    pub fn render(&self, framebuffer: &[bool; WIDTH * HEIGHT]) -> Image {
+        let oriented = self.orientation.apply(&Image::from_framebuffer(framebuffer, &self.palette));
+        let filtered = self.filter.apply(&oriented);
+        filtered.scale_nearest(self.nearest_scale())
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        let (width, height) = self.orientation.output_size(WIDTH, HEIGHT);
+        let scale = self.filter.factor() * self.nearest_scale();
+        (width * scale, height * scale)
+    }
+
+    // Whatever scaling the filter doesn't do is done by repeating pixels
+    fn nearest_scale(&self) -> usize {
+        (self.scale as usize / self.filter.factor()).max(1)
+    }
+}
@@ -1,6 +1,6 @@
 use crate::cpu::{Address, Register, Value};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Instruction {
     ClearScreen, // 00E0 
     Return, // 00EE 
@@ -17,11 +17,11 @@ pub enum Instruction {
     Xor(Register, Register), // 8XY3
     Add(Register, Register), // 8XY4
     Subtract{ destination: Register, first: Register, second: Register }, // 8XY5/8XY7
-    ShiftRight(Register), // 8XY6
-    ShiftLeft(Register), // 8XYE
+    ShiftRight(Register, Register), // 8XY6
+    ShiftLeft(Register, Register), // 8XYE
     CheckNotEqual(Register, Register), // 9XY0
     SetIndex(Address), // ANNN
-    // BNNN Jump with offset
+    JumpWithOffset(Address), // BNNN
     Random(Register, Value), // CXNN
     Display{ vx: Register, vy: Register, pixel_height: Value }, // DXYN
     SkipIfKeyPressed(Register), // EX9E
@@ -39,6 +39,10 @@ pub enum Instruction {
 
 impl Instruction {
     pub fn decode(opcode: u16) -> Instruction {
+        Instruction::try_decode(opcode).unwrap_or_else(|| panic!("Unknown opcode: {:#06X}", opcode))
+    }
+
+    pub fn try_decode(opcode: u16) -> Option<Instruction> {
         let nibble1 = ((opcode & 0xF000) >> 12) as u8;
         let nibble2 = ((opcode & 0x0F00) >> 8) as u8;
         let nibble3 = ((opcode & 0x00F0) >> 4) as u8;
@@ -61,11 +65,12 @@ impl Instruction {
             (0x8, x, y, 3) => Instruction::Xor(x, y),
             (0x8, x, y, 4) => Instruction::Add(x, y),
             (0x8, x, y, 5) => Instruction::Subtract{ destination: x, first: x, second: y},
-            (0x8, x, _, 6) => Instruction::ShiftRight(x),
+            (0x8, x, y, 6) => Instruction::ShiftRight(x, y),
             (0x8, x, y, 7) => Instruction::Subtract{ destination: x, first: y, second: x},
-            (0x8, x, _, 0xE) => Instruction::ShiftLeft(x),
+            (0x8, x, y, 0xE) => Instruction::ShiftLeft(x, y),
             (0x9, x, y, 0) => Instruction::CheckNotEqual(x, y),
             (0xA, _, _, _) => Instruction::SetIndex(opcode & 0x0FFF),
+            (0xB, _, _, _) => Instruction::JumpWithOffset(opcode & 0x0FFF),
             (0xC, x, _, _) => Instruction::Random(x, (opcode & 0x00FF) as u8),
             (0xD, x, y, n) => Instruction::Display{ vx: x, vy: y, pixel_height: n },
             (0xE, x, 0x9, 0xE) => Instruction::SkipIfKeyPressed(x),
@@ -79,10 +84,10 @@ impl Instruction {
             (0xF, x, 0x3, 0x3) => Instruction::StoreBCD(x),
             (0xF, x, 0x5, 0x5) => Instruction::StoreRegisters(x),
             (0xF, x, 0x6, 0x5) => Instruction::LoadRegisters(x),
-            _ => panic!("Unknown opcode: {:#06X}", opcode)
+            _ => return None,
         };
 
-        instruction
+        Some(instruction)
     }
 }
 
@@ -125,6 +130,13 @@ mod tests {
         assert_eq!(instruction, Instruction::SetIndex(0xABC));
     }
 
+    #[test]
+    fn test_decode_jump_with_offset() {
+        let opcode = 0xB123;
+        let instruction = Instruction::decode(opcode);
+        assert_eq!(instruction, Instruction::JumpWithOffset(0x123));
+    }
+
     #[test]
     fn test_decode_display() {
         let opcode = 0xD123;
@@ -0,0 +1,455 @@
+// Compiles straight-line runs of CHIP-8 instructions into x86-64 machine code.
+//
+// Only instructions that touch nothing but the registers, the index and the timers
+// are compiled. Draws, key input, the stack, randomness and memory access are left
+// to the interpreter, which runs a single instruction wherever no block can start.
+// Blocks are compared against memory before they run, so code that has been
+// overwritten since it was compiled is never run stale.
+
+#[cfg(not(all(target_arch = "x86_64", unix)))]
+compile_error!("The jit feature needs an x86-64 unix host");
+
+use std::ffi::c_void;
+use std::ptr;
+
+use crate::chip8;
+use crate::cpu::{Address, Register, Value};
+use crate::instructions::Instruction;
+use crate::memory::{self, Memory};
+use crate::quirks::Quirks;
+
+// Once full, everything is thrown away and compiled again as it's reached
+const CODE_BUFFER_SIZE: usize = 4 * 1024 * 1024;
+
+// The machine state compiled code works on, passed in rdi
+#[repr(C)]
+pub struct JitState {
+    pub v_registers: [Value; 16],
+    pub i_register: Address,
+    pub delay_timer: Value,
+    pub sound_timer: Value,
+}
+
+const VF: u8 = 0xF;
+const I_REGISTER: u8 = 16;
+const DELAY_TIMER: u8 = 18;
+const SOUND_TIMER: u8 = 19;
+
+// Returns the next program counter
+type BlockFn = unsafe extern "sysv64" fn(*mut JitState) -> u32;
+
+struct Block {
+    // None when the first instruction has to be interpreted
+    code: Option<BlockFn>,
+    // The bytes the block was compiled from, to spot code that has been overwritten since
+    source: Vec<u8>,
+    instructions: u32,
+    // Whether the block was cut short to fit the instruction budget it was compiled for
+    truncated: bool,
+}
+
+impl Block {
+    // Frames have to end after exactly as many instructions as they would when interpreted
+    fn fits(&self, budget: u32) -> bool {
+        if self.truncated {
+            self.instructions == budget
+        } else {
+            self.instructions <= budget
+        }
+    }
+}
+
+pub struct Jit {
+    // By start address, with a block for each budget it had to be cut short for
+    blocks: Vec<Vec<Block>>,
+    code: CodeBuffer,
+    quirks: Quirks,
+}
+
+impl Jit {
+    pub fn new() -> Jit {
+        Jit {
+            blocks: (0..memory::SIZE).map(|_| Vec::new()).collect(),
+            code: CodeBuffer::new(),
+            quirks: Quirks::default(),
+        }
+    }
+
+    // Runs up to `budget` instructions from `address`, returning the next program counter and
+    // how many instructions ran, or None if the instruction there has to be interpreted
+    pub fn run(&mut self, address: Address, budget: u32, memory: &Memory, quirks: Quirks, state: &mut JitState) -> Option<(Address, u32)> {
+        if quirks != self.quirks {
+            self.flush();
+            self.quirks = quirks;
+        }
+
+        let blocks = &mut self.blocks[address as usize];
+        let found = blocks.iter().position(|block| block.fits(budget));
+
+        let index = match found {
+            Some(index) if memory.read_bytes(address, blocks[index].source.len()) == blocks[index].source => index,
+            _ => {
+                if let Some(stale) = found {
+                    blocks.swap_remove(stale);
+                }
+
+                let block = self.compile(address, budget, memory);
+                let blocks = &mut self.blocks[address as usize];
+                blocks.push(block);
+                blocks.len() - 1
+            },
+        };
+
+        let block = &self.blocks[address as usize][index];
+        let code = block.code?;
+
+        // Safety: the code was generated for exactly this state layout, and only touches the state
+        let next = unsafe { code(state) };
+        Some((next as Address, block.instructions))
+    }
+
+    fn compile(&mut self, address: Address, budget: u32, memory: &Memory) -> Block {
+        let mut emitter = Emitter::default();
+        let mut program_counter = address;
+        let mut instructions = 0;
+        let mut ended = false;
+
+        while !ended && instructions < budget && (program_counter as usize) + 1 < memory::SIZE {
+            let Some(instruction) = Instruction::try_decode(memory.read_word(program_counter)) else {
+                break;
+            };
+
+            match emitter.instruction(instruction, (program_counter + 2) & memory::ADDRESS_MASK, self.quirks) {
+                Some(Flow::Next) => {},
+                Some(Flow::End) => ended = true,
+                None => break,
+            }
+
+            instructions += 1;
+            program_counter += 2;
+        }
+
+        if instructions == 0 {
+            let length = 2.min(memory::SIZE - address as usize);
+            return Block {
+                code: None,
+                source: memory.read_bytes(address, length).to_vec(),
+                instructions,
+                truncated: false,
+            };
+        }
+
+        if !ended {
+            emitter.exit(program_counter & memory::ADDRESS_MASK);
+        }
+
+        let code = self.code.push(&emitter.code).or_else(|| {
+            self.flush();
+            self.code.push(&emitter.code)
+        });
+
+        Block {
+            code,
+            source: memory.read_bytes(address, (program_counter - address) as usize).to_vec(),
+            instructions,
+            truncated: !ended && instructions == budget,
+        }
+    }
+
+    fn flush(&mut self) {
+        self.blocks.iter_mut().for_each(Vec::clear);
+        self.code.clear();
+    }
+}
+
+impl Default for Jit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+enum Flow {
+    Next,
+    // The block has jumped or skipped, so nothing after it is part of the block
+    End,
+}
+
+// Scratch registers, as encoded in the reg field of ModRM
+const AL: u8 = 0;
+const CL: u8 = 1;
+
+// ALU opcodes of the form `op al, [rdi + disp8]`
+const ADD: u8 = 0x02;
+const OR: u8 = 0x0A;
+const AND: u8 = 0x22;
+const SUB: u8 = 0x2A;
+const XOR: u8 = 0x32;
+const CMP: u8 = 0x3A;
+
+// Condition codes for SETcc and CMOVcc
+const CARRY: u8 = 0x2;
+const NO_CARRY: u8 = 0x3;
+const EQUAL: u8 = 0x4;
+const NOT_EQUAL: u8 = 0x5;
+const ABOVE: u8 = 0x7;
+
+#[derive(Default)]
+struct Emitter {
+    code: Vec<u8>,
+}
+
+impl Emitter {
+    // Emits code for the instruction, or nothing if it has to be interpreted
+    fn instruction(&mut self, instruction: Instruction, next: Address, quirks: Quirks) -> Option<Flow> {
+        match instruction {
+            Instruction::Jump(address) => {
+                self.exit(address);
+                return Some(Flow::End);
+            },
+            Instruction::CheckEqualValue(vx, value) => {
+                self.compare_value(vx, value);
+                self.skip_if(EQUAL, next);
+                return Some(Flow::End);
+            },
+            Instruction::CheckNotEqualValue(vx, value) => {
+                self.compare_value(vx, value);
+                self.skip_if(NOT_EQUAL, next);
+                return Some(Flow::End);
+            },
+            Instruction::CheckEqual(vx, vy) => {
+                self.load(AL, vx);
+                self.alu(CMP, vy);
+                self.skip_if(EQUAL, next);
+                return Some(Flow::End);
+            },
+            Instruction::CheckNotEqual(vx, vy) => {
+                self.load(AL, vx);
+                self.alu(CMP, vy);
+                self.skip_if(NOT_EQUAL, next);
+                return Some(Flow::End);
+            },
+            Instruction::SetRegisterToValue(vx, value) => {
+                self.store_value(vx, value);
+            },
+            Instruction::AddValueToRegister(vx, value) => {
+                // add byte [rdi + vx], value
+                self.code.extend([0x80, 0x47, vx, value]);
+            },
+            Instruction::SetRegister(vx, vy) => {
+                self.load(AL, vy);
+                self.store(vx, AL);
+            },
+            Instruction::Or(vx, vy) => self.logic(OR, vx, vy, quirks),
+            Instruction::And(vx, vy) => self.logic(AND, vx, vy, quirks),
+            Instruction::Xor(vx, vy) => self.logic(XOR, vx, vy, quirks),
+            Instruction::Add(vx, vy) => {
+                self.load(AL, vx);
+                self.alu(ADD, vy);
+                self.set_if(CARRY);
+                self.store_with_flag(vx);
+            },
+            Instruction::Subtract { destination, first, second } => {
+                self.load(AL, first);
+                self.alu(SUB, second);
+                self.set_if(NO_CARRY);
+                self.store_with_flag(destination);
+            },
+            Instruction::ShiftRight(vx, vy) => {
+                self.load(AL, if quirks.shift_uses_vy { vy } else { vx });
+                // shr al, 1
+                self.code.extend([0xD0, 0xE8]);
+                self.set_if(CARRY);
+                self.store_with_flag(vx);
+            },
+            Instruction::ShiftLeft(vx, vy) => {
+                self.load(AL, if quirks.shift_uses_vy { vy } else { vx });
+                // shl al, 1
+                self.code.extend([0xD0, 0xE0]);
+                self.set_if(CARRY);
+                self.store_with_flag(vx);
+            },
+            Instruction::SetIndex(address) => {
+                // mov word [rdi + I], address
+                self.code.extend([0x66, 0xC7, 0x47, I_REGISTER]);
+                self.code.extend(address.to_le_bytes());
+            },
+            Instruction::AddRegisterToIndex(vx) => {
+                // movzx eax, byte [rdi + vx]; add [rdi + I], ax
+                self.code.extend([0x0F, 0xB6, 0x47, vx]);
+                self.code.extend([0x66, 0x01, 0x47, I_REGISTER]);
+
+                if quirks.index_overflow_sets_vf {
+                    // movzx ecx, word [rdi + I]; cmp ecx, 0xFFF
+                    self.code.extend([0x0F, 0xB7, 0x4F, I_REGISTER]);
+                    self.code.extend([0x81, 0xF9]);
+                    self.code.extend(0xFFFu32.to_le_bytes());
+                    self.set_if(ABOVE);
+                    self.store(VF, CL);
+                }
+
+                // and word [rdi + I], ADDRESS_MASK
+                self.code.extend([0x66, 0x81, 0x67, I_REGISTER]);
+                self.code.extend(memory::ADDRESS_MASK.to_le_bytes());
+            },
+            Instruction::SetIndexToSprite(vx) => {
+                // movzx eax, byte [rdi + vx]; lea eax, [rax + rax * 4]; add eax, FONT_START; mov [rdi + I], ax
+                self.code.extend([0x0F, 0xB6, 0x47, vx]);
+                self.code.extend([0x8D, 0x04, 0x80]);
+                self.code.push(0x05);
+                self.code.extend((chip8::FONT_START as u32).to_le_bytes());
+                self.code.extend([0x66, 0x89, 0x47, I_REGISTER]);
+            },
+            Instruction::ReadDelayTimer(vx) => {
+                self.load(AL, DELAY_TIMER);
+                self.store(vx, AL);
+            },
+            Instruction::WriteDelayTimer(vx) => {
+                self.load(AL, vx);
+                self.store(DELAY_TIMER, AL);
+            },
+            Instruction::WriteSoundTimer(vx) => {
+                self.load(AL, vx);
+                self.store(SOUND_TIMER, AL);
+            },
+            _ => return None,
+        }
+
+        Some(Flow::Next)
+    }
+
+    // Returns from the block, continuing at the address
+    fn exit(&mut self, address: Address) {
+        self.move_eax(address);
+        self.code.push(0xC3);
+    }
+
+    fn logic(&mut self, operation: u8, vx: Register, vy: Register, quirks: Quirks) {
+        self.load(AL, vx);
+        self.alu(operation, vy);
+        self.store(vx, AL);
+
+        if quirks.logic_resets_vf {
+            self.store_value(VF, 0);
+        }
+    }
+
+    // The result is stored before the flag, so the flag wins when VF is the destination
+    fn store_with_flag(&mut self, destination: Register) {
+        self.store(destination, AL);
+        self.store(VF, CL);
+    }
+
+    // mov reg, byte [rdi + offset]
+    fn load(&mut self, register: u8, offset: u8) {
+        self.code.extend([0x8A, 0x47 | register << 3, offset]);
+    }
+
+    // mov byte [rdi + offset], reg
+    fn store(&mut self, offset: u8, register: u8) {
+        self.code.extend([0x88, 0x47 | register << 3, offset]);
+    }
+
+    // mov byte [rdi + offset], value
+    fn store_value(&mut self, offset: u8, value: Value) {
+        self.code.extend([0xC6, 0x47, offset, value]);
+    }
+
+    // op al, byte [rdi + offset]
+    fn alu(&mut self, operation: u8, offset: u8) {
+        self.code.extend([operation, 0x47, offset]);
+    }
+
+    // cmp byte [rdi + offset], value
+    fn compare_value(&mut self, offset: u8, value: Value) {
+        self.code.extend([0x80, 0x7F, offset, value]);
+    }
+
+    // setcc cl
+    fn set_if(&mut self, condition: u8) {
+        self.code.extend([0x0F, 0x90 | condition, 0xC1]);
+    }
+
+    // mov eax, address
+    fn move_eax(&mut self, address: Address) {
+        self.code.push(0xB8);
+        self.code.extend((address as u32).to_le_bytes());
+    }
+
+    // Returns from the block, skipping the next instruction if the condition holds
+    fn skip_if(&mut self, condition: u8, next: Address) {
+        // mov eax, next; mov ecx, next + 2; cmovcc eax, ecx; ret
+        self.move_eax(next);
+        self.code.push(0xB9);
+        self.code.extend((((next + 2) & memory::ADDRESS_MASK) as u32).to_le_bytes());
+        self.code.extend([0x0F, 0x40 | condition, 0xC1]);
+        self.code.push(0xC3);
+    }
+}
+
+// Memory for generated code, only ever writable or executable, never both at once
+struct CodeBuffer {
+    base: *mut u8,
+    used: usize,
+}
+
+impl CodeBuffer {
+    fn new() -> CodeBuffer {
+        // Safety: an anonymous private mapping has no requirements beyond checking for failure
+        let base = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                CODE_BUFFER_SIZE,
+                libc::PROT_READ | libc::PROT_EXEC,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        assert_ne!(base, libc::MAP_FAILED, "Failed to map memory for compiled code");
+
+        CodeBuffer {
+            base: base as *mut u8,
+            used: 0,
+        }
+    }
+
+    fn push(&mut self, code: &[u8]) -> Option<BlockFn> {
+        if self.used + code.len() > CODE_BUFFER_SIZE {
+            return None;
+        }
+
+        // Safety: the copy is within the mapping, and the pointer returned is the start of
+        // a complete function that stays mapped until the buffer is cleared or dropped
+        unsafe {
+            let start = self.base.add(self.used);
+
+            self.protect(libc::PROT_READ | libc::PROT_WRITE);
+            ptr::copy_nonoverlapping(code.as_ptr(), start, code.len());
+            self.protect(libc::PROT_READ | libc::PROT_EXEC);
+
+            self.used += code.len();
+            Some(std::mem::transmute::<*mut u8, BlockFn>(start))
+        }
+    }
+
+    fn clear(&mut self) {
+        self.used = 0;
+    }
+
+    unsafe fn protect(&self, protection: libc::c_int) {
+        let result = libc::mprotect(self.base as *mut c_void, CODE_BUFFER_SIZE, protection);
+        assert_eq!(result, 0, "Failed to change protection of compiled code");
+    }
+}
+
+impl Drop for CodeBuffer {
+    fn drop(&mut self) {
+        // Safety: the mapping is only used through this buffer and the blocks dropped with it
+        unsafe {
+            libc::munmap(self.base as *mut c_void, CODE_BUFFER_SIZE);
+        }
+    }
+}
+
+// Safety: the mapping is only reachable through the buffer, so it can move between threads with it
+unsafe impl Send for CodeBuffer {}
@@ -1,4 +1,11 @@
+use crate::state;
 
+// The held key and the last one released, with 0xFF for none
+pub const STATE_SIZE: usize = 2;
+
+const NO_KEY: u8 = 0xFF;
+
+#[derive(Clone)]
 pub struct Keyboard {
     key_pressed: Option<u8>,
     key_last_pressed: Option<u8>,
@@ -18,6 +25,25 @@ impl Keyboard {
         self.signal_key_pressed = false;
     }
 
+    pub fn save_state(&self, writer: &mut state::Writer) {
+        writer.byte(self.key_pressed.unwrap_or(NO_KEY));
+        writer.byte(self.key_last_pressed.unwrap_or(NO_KEY));
+    }
+
+    pub fn load_state(&mut self, reader: &mut state::Reader) -> Result<(), &'static str> {
+        let key = |byte| match byte {
+            NO_KEY => Ok(None),
+            0..=15 => Ok(Some(byte)),
+            _ => Err("Invalid key in save state"),
+        };
+        let key_pressed = key(reader.byte())?;
+        let key_last_pressed = key(reader.byte())?;
+
+        self.key_pressed = key_pressed;
+        self.key_last_pressed = key_last_pressed;
+        Ok(())
+    }
+
     pub fn key_down(&mut self, key: u8) {
         self.key_pressed = Some(key);
     }
@@ -28,6 +54,10 @@ impl Keyboard {
         self.key_pressed = None
     }
 
+    pub fn key_pressed(&self) -> Option<u8> {
+        self.key_pressed
+    }
+
     pub fn key_last_pressed(&self) -> Option<u8> {
         self.key_last_pressed
     }
@@ -39,4 +69,10 @@ impl Keyboard {
     pub fn is_key_pressed(&self, key: u8) -> bool {
         self.key_pressed == Some(key)
     }
+}
+
+impl Default for Keyboard {
+    fn default() -> Self {
+        Self::new()
+    }
 }
\ No newline at end of file
@@ -0,0 +1,40 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+pub mod chip8;
+pub mod memory;
+pub mod display;
+pub mod null_display_driver;
+#[cfg(feature = "std")]
+pub mod palette;
+#[cfg(feature = "std")]
+pub mod image;
+#[cfg(feature = "std")]
+pub mod filters;
+#[cfg(feature = "std")]
+pub mod orientation;
+#[cfg(feature = "std")]
+pub mod persistence;
+#[cfg(feature = "std")]
+pub mod screenshot;
+#[cfg(feature = "std")]
+pub mod recorder;
+#[cfg(feature = "std")]
+pub mod cartridge;
+#[cfg(feature = "std")]
+pub mod movie;
+#[cfg(feature = "std")]
+pub mod bench;
+#[cfg(feature = "std")]
+pub mod env;
+#[cfg(feature = "std")]
+pub mod rom_database;
+pub mod cpu;
+pub mod keyboard;
+pub mod quirks;
+pub mod instructions;
+pub mod decode_cache;
+pub mod state;
+#[cfg(feature = "std")]
+pub mod recompiler;
+#[cfg(feature = "jit")]
+pub mod jit;
@@ -1,51 +1,259 @@
-use std::env;
+use std::{env, fs, process};
+use std::path::Path;
 use std::{fs::File, io::Read};
-use sdl2::event::Event;
+use sdl2::event::{Event, WindowEvent};
 use sdl2::keyboard::{Keycode, Scancode};
 use spin_sleep::LoopHelper;
 
-use chip8::Chip8;
+use rust_chip8::bench::{self, BenchLimit};
+use rust_chip8::cartridge::{Cartridge, Program};
+use rust_chip8::chip8::{self, Chip8, RomTooLarge};
+use rust_chip8::cpu::Fault;
+use rust_chip8::display::{self, DisplayDriver};
+use rust_chip8::null_display_driver::NullDisplayDriver;
+use rust_chip8::image::ImageSettings;
+use rust_chip8::movie::Movie;
+use rust_chip8::palette::{self, Palette, Palettes};
+use rust_chip8::persistence::Persistence;
+use rust_chip8::recorder::GifRecorder;
+use rust_chip8::recompiler;
+use rust_chip8::rom_database::{self, RomDatabase, RomInfo};
+use rust_chip8::screenshot;
+use sdl2_display_driver::Sdl2DisplayDriver;
+use terminal_display_driver::{TerminalDisplayDriver, TerminalInput, TerminalKeypad, TerminalMode};
+use options::{Frontend, Options};
 
-mod chip8;
-mod memory;
-mod display;
 mod sdl2_display_driver;
-mod cpu;
-mod keyboard;
-mod instructions;
+mod terminal_display_driver;
+mod options;
 
+const TIMER_FREQUENCY_PER_SECOND: f64 = chip8::TIMER_FREQUENCY as f64;
+
+// How long to run a ROM for before taking its screen as a cartridge label, unless --headless is given
+const LABEL_FRAMES: u64 = 120;
+
+// The keyboard key for each CHIP-8 key, laid out like the COSMAC VIP's keypad
+const KEYBOARD_KEYS: [char; 16] = ['X', '1', '2', '3', 'Q', 'W', 'E', 'A', 'S', 'D', 'Z', 'C', '4', 'R', 'F', 'V'];
 
 fn main() {
     println!("Chip-8 By Dan!");
 
     let args: Vec<String> = env::args().collect();
-    let mut rom = "roms/bc_test.ch8";
+    let mut options = Options::parse(&args).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
 
-    if args.len() > 1 {
-        rom = &args[1];
+    let palettes = load_palettes(&options);
+    if let Err(err) = options.add_palettes(palettes) {
+        eprintln!("{}", err);
+        process::exit(1);
     }
 
-    let mut file = File::open(rom).unwrap();
+    let mut file = File::open(&options.rom).unwrap();
     let mut data = Vec::<u8>::new();
-    file.read_to_end(&mut data);
+    file.read_to_end(&mut data).unwrap();
+
+    // A cartridge's own settings take precedence over the database's
+    let mut cartridge_settings = None;
+    if Cartridge::is_gif(&data) {
+        let cartridge = Cartridge::decode(&data).unwrap_or_else(|err| {
+            eprintln!("{}: {}", options.rom, err);
+            process::exit(1);
+        });
+        data = match cartridge.program {
+            Program::Bytes(program) => program,
+            Program::Source(_) => {
+                eprintln!("{}: the cartridge holds Octo source, which needs assembling in Octo and exporting as a .ch8 first", options.rom);
+                process::exit(1);
+            },
+        };
+        cartridge_settings = Some(cartridge.settings);
+    }
+
+    // Checked before any frontend starts, so the error isn't lost behind a window or screen
+    if data.len() > chip8::MAX_ROM_SIZE {
+        eprintln!("{}: {}", options.rom, RomTooLarge { size: data.len() });
+        process::exit(1);
+    }
+
+    let database = load_rom_database(&options);
+    if let Some(info) = database.lookup(&data) {
+        print_rom_info(info);
+        options.apply_rom_info(info);
+    }
+    if let Some(settings) = &cartridge_settings {
+        options.apply_rom_info(settings);
+    }
+
+    if let Some(path) = &options.recompile_path {
+        recompile(&data, path, &options);
+        return;
+    }
+
+    if let Some(path) = &options.export_cartridge_path {
+        export_cartridge(&data, path, &options);
+        return;
+    }
+
+    if let Some(limit) = options.bench_limit {
+        run_bench(&data, limit, &options);
+        return;
+    }
+
+    if let Some(path) = &options.play_movie_path {
+        play_movie(&data, path, &options);
+        return;
+    }
+
+    match (options.headless_frames, options.frontend) {
+        (Some(frames), _) => run_headless(&data, frames, &options),
+        (None, Frontend::Sdl) => run_sdl(&data, &options),
+        (None, Frontend::Terminal(mode)) => run_terminal(&data, mode, &options),
+    }
+}
+
+fn run_headless(data: &[u8], frames: u64, options: &Options) {
+    let palette = options.palette;
+    let mut chip8 = Chip8::new(NullDisplayDriver::new());
+    load(&mut chip8, data, options);
+
+    let mut recorder = start_recording(options.record_path.as_deref(), &palette, options, &mut Console);
+
+    let mut fault = None;
+    for _ in 0..frames {
+        fault = chip8.run_frame(options.instructions_per_frame).err();
+        capture_frame(&mut recorder, &chip8, &mut Console);
+        if fault.is_some() {
+            break;
+        }
+    }
+    stop_recording(&mut recorder, &mut Console);
+
+    print_screen(&chip8);
+
+    if options.screenshot_on_exit {
+        save_screenshot(&chip8, &palette, options, &mut Console);
+    }
+
+    if let Some(fault) = fault {
+        eprintln!("Halted: {}", fault);
+        process::exit(1);
+    }
+}
+
+fn recompile(data: &[u8], path: &str, options: &Options) {
+    let name = Path::new(&options.rom).file_name().map_or(options.rom.clone(), |name| name.to_string_lossy().into_owned());
+
+    match fs::write(path, recompiler::recompile(data, &name, options.quirks)) {
+        Ok(()) => println!("Recompiled {} to {}", name, path),
+        Err(err) => {
+            eprintln!("Failed to write {}: {}", path, err);
+            process::exit(1);
+        },
+    }
+}
+
+// Saves the ROM with its current settings, labelled with its screen after a few seconds
+fn export_cartridge(data: &[u8], path: &str, options: &Options) {
+    let mut chip8 = Chip8::new(NullDisplayDriver::new());
+    load(&mut chip8, data, options);
+    for _ in 0..options.headless_frames.unwrap_or(LABEL_FRAMES) {
+        // The screen it halted on makes as good a label as any
+        if chip8.run_frame(options.instructions_per_frame).is_err() {
+            break;
+        }
+    }
+
+    let cartridge = Cartridge {
+        program: Program::Bytes(data.to_vec()),
+        settings: RomInfo {
+            quirks: Some(options.quirks),
+            instructions_per_frame: Some(options.instructions_per_frame),
+            palette: Some(options.palette),
+            ..RomInfo::default()
+        },
+    };
+
+    match cartridge.encode(chip8.framebuffer()).and_then(|gif| fs::write(path, gif).map_err(|err| err.to_string())) {
+        Ok(()) => println!("Saved cartridge to {}", path),
+        Err(err) => {
+            eprintln!("Failed to write {}: {}", path, err);
+            process::exit(1);
+        },
+    }
+}
+
+fn run_bench(data: &[u8], limit: BenchLimit, options: &Options) {
+    let mut chip8 = Chip8::new(NullDisplayDriver::new());
+    load(&mut chip8, data, options);
+
+    print!("{}", bench::run(&mut chip8, limit));
+}
+
+// Replays a movie headlessly, exiting with an error if any of its checks fail
+fn play_movie(data: &[u8], path: &str, options: &Options) {
+    let movie = Movie::load(path).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
 
+    let palette = options.palette;
+    let mut chip8 = Chip8::new(NullDisplayDriver::new());
+    load(&mut chip8, data, options);
+    movie.start(&mut chip8);
+
+    let mut recorder = start_recording(options.record_path.as_deref(), &palette, options, &mut Console);
+
+    let mut failures = movie.failed_checks(&chip8, 0);
+    for frame in 0..options.headless_frames.unwrap_or(movie.frames()) {
+        if let Err(fault) = movie.run_frame(&mut chip8, frame) {
+            failures.push(format!("Frame {}: {}", frame, fault));
+            break;
+        }
+        capture_frame(&mut recorder, &chip8, &mut Console);
+        failures.extend(movie.failed_checks(&chip8, frame + 1));
+    }
+    stop_recording(&mut recorder, &mut Console);
+
+    print_screen(&chip8);
+
+    if options.screenshot_on_exit {
+        save_screenshot(&chip8, &palette, options, &mut Console);
+    }
+
+    for failure in &failures {
+        eprintln!("{}", failure);
+    }
+    if !failures.is_empty() {
+        process::exit(1);
+    }
+}
+
+fn print_screen(chip8: &Chip8<NullDisplayDriver>) {
+    print!("{}", display::to_ascii(chip8.framebuffer()));
+    println!("Frames changed: {}", chip8.display_driver().frames_changed());
+    println!("Screen hash: {:016x}", display::screen_hash(chip8.framebuffer()));
+}
+
+fn run_sdl(data: &[u8], options: &Options) {
+    let mut palette = options.palette;
     let sdl_context = sdl2::init().unwrap();
-    let mut chip8 = Chip8::new(&sdl_context);
-    chip8.load_default_font();
-    chip8.load_rom(&data);
+    let mut chip8 = Chip8::new(Sdl2DisplayDriver::new(&sdl_context, palette, options.window));
+    chip8.display_driver_mut().set_persistence(options.persistence_frames.map(Persistence::new));
+    load(&mut chip8, data, options);
+
+    let mut recorder = start_recording(options.record_path.as_deref(), &palette, options, &mut Console);
+    let mut runner = Runner::new(start_movie(&mut chip8, options, &mut Console));
 
     let mut event_pump = sdl_context.event_pump().unwrap();
-    const MAX_INSTRUCTIONS_PER_SECOND: i32 = 700;
-    const TIMER_FREQUENCY_PER_SECOND: f64 = 60.0;
 
-    let mut loop_helper = LoopHelper::builder()
-        .report_interval_s(1.0 / TIMER_FREQUENCY_PER_SECOND)
-        .build_with_target_rate(MAX_INSTRUCTIONS_PER_SECOND);
+    let mut loop_helper = runner.loop_helper(options);
 
 
     'running: loop {
         loop_helper.loop_start();
-        chip8.precycle();
+        runner.precycle(&mut chip8);
 
         for event in event_pump.poll_iter() {
             match event {
@@ -54,13 +262,47 @@ fn main() {
                     keycode: Some(Keycode::Escape),
                     ..
                 } => break 'running,
+                Event::KeyDown {
+                    keycode: Some(Keycode::F12),
+                    repeat: false,
+                    ..
+                } => save_screenshot(&chip8, &palette, options, &mut Console),
+                Event::KeyDown {
+                    keycode: Some(Keycode::F11),
+                    repeat: false,
+                    ..
+                } => toggle_recording(&mut recorder, &palette, options, &mut Console),
+                Event::KeyDown {
+                    keycode: Some(Keycode::F10),
+                    repeat: false,
+                    ..
+                } => {
+                    palette = next_palette(&palette, options, &mut Console);
+                    chip8.display_driver_mut().set_palette(palette);
+                },
+                Event::KeyDown {
+                    keycode: Some(Keycode::F9),
+                    repeat: false,
+                    ..
+                } => chip8.display_driver_mut().toggle_fullscreen(),
+                Event::Window {
+                    win_event: WindowEvent::SizeChanged(..) | WindowEvent::Exposed,
+                    ..
+                } => {
+                    // Borrowing the driver mutably queues a redraw at the new size
+                    chip8.display_driver_mut();
+                },
                 Event::KeyDown {
                     scancode,
                     ..
-                } => handle_keydown(scancode, &mut chip8),
-                Event::KeyUp { 
+                } => if let Some(key) = keypad_key(scancode) {
+                    runner.key_down(&mut chip8, key);
+                },
+                // Hotkeys aren't on the keypad, so releasing them mustn't release a key
+                Event::KeyUp {
+                    scancode,
                     ..
-                } => chip8.key_up(),
+                } if keypad_key(scancode).is_some() => runner.key_up(&mut chip8),
                 // Event::KeyDown {
                 //     keycode: Some(Keycode::Space),
                 //     ..
@@ -73,67 +315,362 @@ fn main() {
             }
         }
 
-        chip8.cycle();
+        // A halted machine keeps showing its last screen until the window is closed
+        let frame_finished = runner.run(&mut chip8, &mut loop_helper).unwrap_or_else(|fault| {
+            eprintln!("Halted: {}", fault);
+            false
+        });
         chip8.show_internals();
 
-        if let Some(_fps) = loop_helper.report_rate() {
-            chip8.timer_cycle();
+        if frame_finished {
+            capture_frame(&mut recorder, &chip8, &mut Console);
+
+            if options.persistence_frames.is_some() {
+                chip8.display_driver_mut().fade();
+            }
         }
 
         loop_helper.loop_sleep(); 
     }
+
+    stop_recording(&mut recorder, &mut Console);
+    save_movie(runner.movie, options);
 }
 
-fn handle_keydown(scancode: Option<Scancode>, chip8: &mut Chip8) {
-    match scancode {
-        Some(Scancode::Num1) => {
-            chip8.key_down(0x01);
-        },
-        Some(Scancode::Num2) => {
-            chip8.key_down(0x02);
-        },
-        Some(Scancode::Num3) => {
-            chip8.key_down(0x03);
-        },
-        Some(Scancode::Num4) => {
-            chip8.key_down(0x0C);
-        },
-        Some(Scancode::Q) => {
-            chip8.key_down(0x04);
-        },
-        Some(Scancode::W) => {
-            chip8.key_down(0x05);
-        },
-        Some(Scancode::E) => {
-            chip8.key_down(0x06);
-        },
-        Some(Scancode::R) => {
-            chip8.key_down(0x0D);
-        },
-        Some(Scancode::A) => {
-            chip8.key_down(0x07);
-        },
-        Some(Scancode::S) => {
-            chip8.key_down(0x08);
-        },
-        Some(Scancode::D) => {
-            chip8.key_down(0x09);
-        },
-        Some(Scancode::F) => {
-            chip8.key_down(0x0E);
+fn run_terminal(data: &[u8], mode: TerminalMode, options: &Options) {
+    let mut palette = options.palette;
+    let mut chip8 = Chip8::new(TerminalDisplayDriver::new(mode, palette, options.window.orientation));
+    chip8.display_driver_mut().set_persistence(options.persistence_frames.map(Persistence::new));
+    load(&mut chip8, data, options);
+
+    // Printing would draw over the screen, so messages go on the status line below it
+    let mut status = StatusLine::default();
+    let mut recorder = start_recording(options.record_path.as_deref(), &palette, options, &mut status);
+    let mut runner = Runner::new(start_movie(&mut chip8, options, &mut status));
+    let mut fault = None;
+
+    let mut keypad = TerminalKeypad::new();
+
+    let mut loop_helper = runner.loop_helper(options);
+
+    'running: loop {
+        loop_helper.loop_start();
+        runner.precycle(&mut chip8);
+
+        for input in keypad.poll() {
+            match input {
+                TerminalInput::Quit => break 'running,
+                TerminalInput::KeyDown(key) => runner.key_down(&mut chip8, key),
+                TerminalInput::KeyUp => runner.key_up(&mut chip8),
+                TerminalInput::Screenshot => save_screenshot(&chip8, &palette, options, &mut status),
+                TerminalInput::ToggleRecording => toggle_recording(&mut recorder, &palette, options, &mut status),
+                TerminalInput::NextPalette => {
+                    palette = next_palette(&palette, options, &mut status);
+                    chip8.display_driver_mut().set_palette(palette);
+                },
+            }
+        }
+
+        let frame_finished = runner.run(&mut chip8, &mut loop_helper).unwrap_or_else(|err| {
+            fault = Some(err);
+            false
+        });
+
+        if frame_finished {
+            capture_frame(&mut recorder, &chip8, &mut status);
+
+            if options.persistence_frames.is_some() {
+                chip8.display_driver_mut().fade();
+            }
+        }
+
+        if let Some(message) = status.latest.take() {
+            chip8.display_driver_mut().set_status(&message);
+        }
+
+        loop_helper.loop_sleep();
+    }
+
+    // Leaves the alternate screen, so what's printed from here on stays visible
+    drop(chip8);
+    stop_recording(&mut recorder, &mut Console);
+    save_movie(runner.movie, options);
+    if let Some(fault) = fault {
+        eprintln!("Halted: {}", fault);
+    }
+}
+
+fn instructions_per_second(options: &Options) -> f64 {
+    options.instructions_per_frame as f64 * TIMER_FREQUENCY_PER_SECOND
+}
+
+// Where the frontends report screenshots, recordings and palette changes
+trait Log {
+    fn info(&mut self, message: String);
+    fn error(&mut self, message: String);
+}
+
+struct Console;
+
+impl Log for Console {
+    fn info(&mut self, message: String) {
+        println!("{}", message);
+    }
+
+    fn error(&mut self, message: String) {
+        eprintln!("{}", message);
+    }
+}
+
+// Holds the latest message until the terminal frontend can draw it on its status line
+#[derive(Default)]
+struct StatusLine {
+    latest: Option<String>,
+}
+
+impl Log for StatusLine {
+    fn info(&mut self, message: String) {
+        self.latest = Some(message);
+    }
+
+    fn error(&mut self, message: String) {
+        self.latest = Some(message);
+    }
+}
+
+fn next_palette(palette: &Palette, options: &Options, log: &mut impl Log) -> Palette {
+    let (name, next) = options.palettes.next(palette);
+    log.info(format!("Palette: {}", name));
+    next
+}
+
+fn image_settings(palette: &Palette, options: &Options) -> ImageSettings {
+    ImageSettings {
+        palette: *palette,
+        orientation: options.window.orientation,
+        filter: options.window.filter,
+        scale: options.export_scale,
+    }
+}
+
+fn save_screenshot<T: DisplayDriver>(chip8: &Chip8<T>, palette: &Palette, options: &Options, log: &mut impl Log) {
+    let path = screenshot::timestamped_filename("chip8", "png");
+
+    match screenshot::save_png(&path, chip8.framebuffer(), &image_settings(palette, options)) {
+        Ok(()) => log.info(format!("Saved screenshot to {}", path)),
+        Err(err) => log.error(format!("Failed to save screenshot: {}", err)),
+    }
+}
+
+fn start_recording(path: Option<&str>, palette: &Palette, options: &Options, log: &mut impl Log) -> Option<GifRecorder> {
+    let path = path?;
+
+    match GifRecorder::create(path, image_settings(palette, options)) {
+        Ok(recorder) => {
+            log.info(format!("Recording to {}", path));
+            Some(recorder)
         },
-        Some(Scancode::Z) => {
-            chip8.key_down(0x0A);
+        Err(err) => {
+            log.error(format!("Failed to start recording: {}", err));
+            None
         },
-        Some(Scancode::X) => {
-            chip8.key_down(0x00);
+    }
+}
+
+fn toggle_recording(recorder: &mut Option<GifRecorder>, palette: &Palette, options: &Options, log: &mut impl Log) {
+    if recorder.is_some() {
+        stop_recording(recorder, log);
+    } else {
+        let path = screenshot::timestamped_filename("chip8", "gif");
+        *recorder = start_recording(Some(&path), palette, options, log);
+    }
+}
+
+fn stop_recording(recorder: &mut Option<GifRecorder>, log: &mut impl Log) {
+    if let Some(recorder) = recorder.take() {
+        log.info(format!("Recorded {} frames", recorder.frames_captured()));
+    }
+}
+
+fn capture_frame<T: DisplayDriver>(recorder: &mut Option<GifRecorder>, chip8: &Chip8<T>, log: &mut impl Log) {
+    if let Some(active) = recorder {
+        if let Err(err) = active.capture(chip8.framebuffer()) {
+            log.error(format!("Failed to record frame: {}", err));
+            *recorder = None;
+        }
+    }
+}
+
+// Input is recorded against a known seed, picking one if none was given
+fn start_movie<T: DisplayDriver>(chip8: &mut Chip8<T>, options: &Options, log: &mut impl Log) -> Option<Movie> {
+    let path = options.record_movie_path.as_ref()?;
+    let seed = options.seed.unwrap_or_else(rand::random);
+    chip8.seed_rng(seed);
+
+    log.info(format!("Recording input to {} with seed {}", path, seed));
+    let mut movie = Movie::new(seed);
+    movie.set_instructions_per_frame(options.instructions_per_frame);
+    Some(movie)
+}
+
+// Runs the machine for the SDL and terminal frontends. Normally that's an instruction per
+// loop, with keys applied as they're pressed and the timers ticked by the clock. While
+// recording a movie it's a frame per loop through Movie::record_frame, sampling the key at the
+// start of each frame the way playing the movie back will apply it.
+struct Runner {
+    movie: Option<Movie>,
+    key: Option<u8>,
+    halted: bool,
+}
+
+impl Runner {
+    fn new(movie: Option<Movie>) -> Runner {
+        Runner { movie, key: None, halted: false }
+    }
+
+    fn loop_helper(&self, options: &Options) -> LoopHelper {
+        let rate = if self.movie.is_some() { TIMER_FREQUENCY_PER_SECOND } else { instructions_per_second(options) };
+        LoopHelper::builder()
+            .report_interval_s(1.0 / TIMER_FREQUENCY_PER_SECOND)
+            .build_with_target_rate(rate)
+    }
+
+    fn precycle<T: DisplayDriver>(&self, chip8: &mut Chip8<T>) {
+        if self.movie.is_none() {
+            chip8.precycle();
+        }
+    }
+
+    fn key_down<T: DisplayDriver>(&mut self, chip8: &mut Chip8<T>, key: u8) {
+        self.key = Some(key);
+        if self.movie.is_none() {
+            chip8.key_down(key);
+        }
+    }
+
+    fn key_up<T: DisplayDriver>(&mut self, chip8: &mut Chip8<T>) {
+        self.key = None;
+        if self.movie.is_none() {
+            chip8.key_up();
+        }
+    }
+
+    // Whether a frame finished, or the fault the first time the machine halts
+    fn run<T: DisplayDriver>(&mut self, chip8: &mut Chip8<T>, loop_helper: &mut LoopHelper) -> Result<bool, Fault> {
+        let result = match &mut self.movie {
+            Some(movie) => movie.record_frame(chip8, self.key).map(|()| true),
+            None => chip8.cycle().map(|()| {
+                let frame_finished = loop_helper.report_rate().is_some();
+                if frame_finished {
+                    chip8.timer_cycle();
+                }
+                frame_finished
+            }),
+        };
+
+        match result {
+            Err(fault) if !self.halted => {
+                self.halted = true;
+                Err(fault)
+            },
+            Err(_) => Ok(false),
+            Ok(frame_finished) => Ok(frame_finished),
+        }
+    }
+}
+
+fn save_movie(movie: Option<Movie>, options: &Options) {
+    if let (Some(movie), Some(path)) = (movie, &options.record_movie_path) {
+        match movie.save(path) {
+            Ok(()) => println!("Saved {} frames of input to {}", movie.frames(), path),
+            Err(err) => eprintln!("Failed to save movie: {}", err),
+        }
+    }
+}
+
+// The bundled database, with the user's own entries and overrides on top. These come from
+// --rom-db if given, or the user database file if there is one.
+fn load_rom_database(options: &Options) -> RomDatabase {
+    let mut database = RomDatabase::bundled();
+
+    let path = match &options.rom_database_path {
+        Some(path) => path.clone(),
+        None => match rom_database::user_database_path() {
+            Some(path) if path.exists() => path.to_string_lossy().into_owned(),
+            _ => return database,
         },
-        Some(Scancode::C) => {
-            chip8.key_down(0x0B);
+    };
+
+    match RomDatabase::load(&path) {
+        Ok(user_database) => database.merge(user_database),
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
         },
-        Some(Scancode::V) => {
-            chip8.key_down(0x0F);
+    }
+    database
+}
+
+// The user's own palettes, from --palettes if given, or the user palette file if there is one
+fn load_palettes(options: &Options) -> Palettes {
+    let path = match &options.palettes_path {
+        Some(path) => path.clone(),
+        None => match palette::user_palettes_path() {
+            Some(path) if path.exists() => path.to_string_lossy().into_owned(),
+            _ => return Palettes::default(),
         },
-        _ => {}
+    };
+
+    Palettes::load(&path).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    })
+}
+
+fn print_rom_info(info: &RomInfo) {
+    match (&info.title, &info.author) {
+        (Some(title), Some(author)) => println!("{} by {}", title, author),
+        (Some(title), None) => println!("{}", title),
+        _ => {},
+    }
+    if let Some(platform) = &info.platform {
+        println!("Platform: {}", platform);
+    }
+    for (key, description) in &info.keys {
+        println!("Key {:X} ({}): {}", key, KEYBOARD_KEYS[*key as usize], description);
+    }
+}
+
+fn load<T: DisplayDriver>(chip8: &mut Chip8<T>, data: &[u8], options: &Options) {
+    chip8.set_quirks(options.quirks);
+    #[cfg(feature = "jit")]
+    chip8.set_jit(options.jit);
+    if let Some(seed) = options.seed {
+        chip8.seed_rng(seed);
+    }
+    chip8.load_default_font();
+    chip8.load_rom(data).expect("ROM checked to fit on starting");
+}
+
+// The keypad key each scancode maps to, laid out like the COSMAC VIP's hex keypad
+fn keypad_key(scancode: Option<Scancode>) -> Option<u8> {
+    match scancode? {
+        Scancode::Num1 => Some(0x01),
+        Scancode::Num2 => Some(0x02),
+        Scancode::Num3 => Some(0x03),
+        Scancode::Num4 => Some(0x0C),
+        Scancode::Q => Some(0x04),
+        Scancode::W => Some(0x05),
+        Scancode::E => Some(0x06),
+        Scancode::R => Some(0x0D),
+        Scancode::A => Some(0x07),
+        Scancode::S => Some(0x08),
+        Scancode::D => Some(0x09),
+        Scancode::F => Some(0x0E),
+        Scancode::Z => Some(0x0A),
+        Scancode::X => Some(0x00),
+        Scancode::C => Some(0x0B),
+        Scancode::V => Some(0x0F),
+        _ => None,
     }
 }
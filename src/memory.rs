@@ -1,30 +1,77 @@
-use std::fmt;
+use core::fmt;
 
+use crate::decode_cache::DecodeCache;
+use crate::instructions::Instruction;
+use crate::state;
+
+pub const SIZE: usize = 4096;
+
+// Addresses are 12 bits, so anything past the end of memory wraps around to the start
+pub const ADDRESS_MASK: u16 = (SIZE - 1) as u16;
+
+#[derive(Clone)]
 pub struct Memory {
-    data: [u8; 4096],
+    data: [u8; SIZE],
+    decode_cache: DecodeCache<SIZE>,
 }
 
 impl Memory {
     pub fn new() -> Memory {
         Memory {
-            data: [0; 4096],
+            data: [0; SIZE],
+            decode_cache: DecodeCache::new(),
         }
     }
 
     pub fn write_byte(&mut self, address: u16, value: u8) {
+        let address = address & ADDRESS_MASK;
         self.data[address as usize] = value;
+        self.decode_cache.invalidate(address, 1);
     }
 
+    // Unlike write_byte this doesn't wrap, so the bytes must fit before the end of memory
     pub fn write_bytes(&mut self, address: u16, data: &[u8]) {
+        assert!(address as usize + data.len() <= SIZE, "Writing {} bytes at 0x{:03X} runs past the end of memory", data.len(), address);
         self.data[address as usize..(address as usize + data.len())].copy_from_slice(data);
+        self.decode_cache.invalidate(address, data.len());
+    }
+
+    // The instruction at the address, decoded the first time it's fetched after being written.
+    // None if the opcode is unknown.
+    pub fn fetch(&mut self, address: u16) -> Option<Instruction> {
+        let address = address & ADDRESS_MASK;
+        let data = &self.data;
+        self.decode_cache.get_or_decode(address, || {
+            (data[address as usize] as u16) << 8 | data[((address + 1) & ADDRESS_MASK) as usize] as u16
+        })
+    }
+
+    pub fn save_state(&self, writer: &mut state::Writer) {
+        writer.bytes(&self.data);
+    }
+
+    pub fn load_state(&mut self, reader: &mut state::Reader) {
+        self.write_bytes(0, reader.bytes(SIZE));
     }
 
     pub fn read_word(&self, address: u16) -> u16 {
-        (self.data[address as usize] as u16) << 8 | self.data[address as usize + 1] as u16
+        (self.read_byte(address) as u16) << 8 | self.read_byte(address.wrapping_add(1)) as u16
     }
 
     pub fn read_byte(&self, address: u16) -> u8 {
-        self.data[address as usize]
+        self.data[(address & ADDRESS_MASK) as usize]
+    }
+
+    // Unlike read_byte this doesn't wrap, so the bytes must be before the end of memory
+    pub fn read_bytes(&self, address: u16, length: usize) -> &[u8] {
+        assert!(address as usize + length <= SIZE, "Reading {} bytes at 0x{:03X} runs past the end of memory", length, address);
+        &self.data[address as usize..address as usize + length]
+    }
+}
+
+impl Default for Memory {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
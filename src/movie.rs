@@ -0,0 +1,327 @@
+use std::fmt;
+use std::fs;
+use std::io;
+
+use crate::chip8::{self, Chip8};
+use crate::cpu::Fault;
+use crate::display::{self, DisplayDriver};
+use crate::memory;
+
+// Keypad input recorded frame by frame, along with the RNG seed, so a run can be
+// replayed exactly. Checks on the screen or memory at chosen frames turn a movie
+// into a regression test.
+//
+// Movies are stored as text, one entry per line, with # starting a comment:
+//
+//   seed 1234
+//   speed 15              instructions per frame, if not the default
+//   frames 600
+//   input 30 5            hold key 5 from frame 30
+//   input 42 -            release it at frame 42
+//   screen 600 0123456789abcdef
+//   memory 600 3f0 0c     byte at 0x3f0 is 0x0c after 600 frames
+#[derive(Debug, Clone, PartialEq)]
+pub struct Movie {
+    seed: u64,
+    instructions_per_frame: u32,
+    frames: u64,
+    // Changes to the held key, by the frame they take effect from
+    inputs: Vec<(u64, Option<u8>)>,
+    // Checked once the given number of frames have run
+    checks: Vec<(u64, Check)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Check {
+    ScreenHash(u64),
+    Memory { address: u16, value: u8 },
+}
+
+impl Movie {
+    pub fn new(seed: u64) -> Movie {
+        Movie {
+            seed,
+            instructions_per_frame: chip8::INSTRUCTIONS_PER_FRAME,
+            frames: 0,
+            inputs: Vec::new(),
+            checks: Vec::new(),
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Movie, String> {
+        let text = fs::read_to_string(path).map_err(|err| format!("Failed to read {}: {}", path, err))?;
+        Movie::parse(&text).map_err(|err| format!("{}: {}", path, err))
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    pub fn parse(text: &str) -> Result<Movie, String> {
+        let mut movie = Movie::new(0);
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default();
+            let fields: Vec<&str> = line.split_whitespace().collect();
+
+            let error = || format!("Invalid movie line {}: {}", number + 1, line.trim());
+            let decimal = |field: &str| field.parse::<u64>().map_err(|_| error());
+            let hex = |field: &str| u64::from_str_radix(field, 16).map_err(|_| error());
+
+            match fields.as_slice() {
+                [] => {},
+                ["seed", seed] => movie.seed = decimal(seed)?,
+                ["speed", speed] => {
+                    movie.instructions_per_frame = u32::try_from(decimal(speed)?).map_err(|_| error())?;
+                    if movie.instructions_per_frame == 0 {
+                        return Err(error());
+                    }
+                },
+                ["frames", frames] => movie.frames = decimal(frames)?,
+                ["input", frame, "-"] => movie.inputs.push((decimal(frame)?, None)),
+                ["input", frame, key] => {
+                    let key = hex(key)?;
+                    if key > 0xF {
+                        return Err(error());
+                    }
+                    movie.inputs.push((decimal(frame)?, Some(key as u8)));
+                },
+                ["screen", frame, hash] => movie.checks.push((decimal(frame)?, Check::ScreenHash(hex(hash)?))),
+                ["memory", frame, address, value] => {
+                    let address = u16::try_from(hex(address)?).ok().filter(|&address| (address as usize) < memory::SIZE);
+                    let address = address.ok_or_else(error)?;
+                    let value = u8::try_from(hex(value)?).map_err(|_| error())?;
+                    movie.checks.push((decimal(frame)?, Check::Memory { address, value }));
+                },
+                _ => return Err(error()),
+            }
+        }
+
+        movie.inputs.sort_by_key(|(frame, _)| *frame);
+        Ok(movie)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    pub fn instructions_per_frame(&self) -> u32 {
+        self.instructions_per_frame
+    }
+
+    // For recording at a speed other than the default
+    pub fn set_instructions_per_frame(&mut self, instructions_per_frame: u32) {
+        assert!(instructions_per_frame > 0, "A frame needs at least one instruction");
+        self.instructions_per_frame = instructions_per_frame;
+    }
+
+    // Notes the key held from the given frame on, extending the movie to that frame
+    pub fn record(&mut self, frame: u64, key: Option<u8>) {
+        self.frames = self.frames.max(frame);
+
+        if self.key_at(frame) == key {
+            return;
+        }
+
+        match self.inputs.last_mut() {
+            Some(last) if last.0 == frame => last.1 = key,
+            _ => self.inputs.push((frame, key)),
+        }
+    }
+
+    pub fn add_check(&mut self, frame: u64, check: Check) {
+        self.checks.push((frame, check));
+    }
+
+    pub fn key_at(&self, frame: u64) -> Option<u8> {
+        let applied = self.inputs.partition_point(|(input_frame, _)| *input_frame <= frame);
+        applied.checked_sub(1).and_then(|last| self.inputs[last].1)
+    }
+
+    pub fn start<T: DisplayDriver>(&self, chip8: &mut Chip8<T>) {
+        chip8.seed_rng(self.seed);
+    }
+
+    // Records `key` as held from the next frame, then runs that frame exactly as play will
+    pub fn record_frame<T: DisplayDriver>(&mut self, chip8: &mut Chip8<T>, key: Option<u8>) -> Result<(), Fault> {
+        let frame = self.frames;
+        self.record(frame, key);
+        self.frames = frame + 1;
+        self.run_frame(chip8, frame)
+    }
+
+    // Chip8::run_frame_holding with the movie's key for the frame, compiled if the JIT is on
+    pub fn run_frame<T: DisplayDriver>(&self, chip8: &mut Chip8<T>, frame: u64) -> Result<(), Fault> {
+        chip8.run_frame_holding(self.key_at(frame), self.instructions_per_frame)
+    }
+
+    // Describes each check due once `frames` frames have run that doesn't match
+    pub fn failed_checks<T: DisplayDriver>(&self, chip8: &Chip8<T>, frames: u64) -> Vec<String> {
+        self.checks
+            .iter()
+            .filter(|(frame, _)| *frame == frames)
+            .filter_map(|(frame, check)| match *check {
+                Check::ScreenHash(expected) => {
+                    let actual = display::screen_hash(chip8.framebuffer());
+                    (actual != expected).then(|| format!(
+                        "Frame {}: screen hash {:016x}, expected {:016x}\n{}",
+                        frame, actual, expected, display::to_ascii(chip8.framebuffer())
+                    ))
+                },
+                Check::Memory { address, value } => {
+                    let actual = chip8.read_byte(address);
+                    (actual != value).then(|| format!(
+                        "Frame {}: memory at {:03x} is {:02x}, expected {:02x}",
+                        frame, address, actual, value
+                    ))
+                },
+            })
+            .collect()
+    }
+
+    // Runs the whole movie from a freshly loaded ROM
+    pub fn play<T: DisplayDriver>(&self, chip8: &mut Chip8<T>) -> Result<(), Vec<String>> {
+        self.start(chip8);
+
+        let mut failures = self.failed_checks(chip8, 0);
+        for frame in 0..self.frames {
+            if let Err(fault) = self.run_frame(chip8, frame) {
+                failures.push(format!("Frame {}: {}", frame, fault));
+                break;
+            }
+            failures.extend(self.failed_checks(chip8, frame + 1));
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures)
+        }
+    }
+}
+
+impl fmt::Display for Movie {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "seed {}", self.seed)?;
+        if self.instructions_per_frame != chip8::INSTRUCTIONS_PER_FRAME {
+            writeln!(f, "speed {}", self.instructions_per_frame)?;
+        }
+        writeln!(f, "frames {}", self.frames)?;
+
+        for (frame, key) in &self.inputs {
+            match key {
+                Some(key) => writeln!(f, "input {} {:x}", frame, key)?,
+                None => writeln!(f, "input {} -", frame)?,
+            }
+        }
+
+        for (frame, check) in &self.checks {
+            match check {
+                Check::ScreenHash(hash) => writeln!(f, "screen {} {:016x}", frame, hash)?,
+                Check::Memory { address, value } => writeln!(f, "memory {} {:03x} {:02x}", frame, address, value)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::null_display_driver::NullDisplayDriver;
+
+    fn machine(rom: &[u8]) -> Chip8<NullDisplayDriver> {
+        let mut chip8 = Chip8::new(NullDisplayDriver::new());
+        chip8.load_default_font();
+        chip8.load_rom(rom).unwrap();
+        chip8
+    }
+
+    #[test]
+    fn test_parse_and_format_round_trip() {
+        let text = "seed 42\nframes 10\ninput 2 a\ninput 5 -\nscreen 10 00000000000000ff\nmemory 10 3f0 0c\n";
+        let movie = Movie::parse(&format!("# A comment\n\n{}", text)).unwrap();
+
+        assert_eq!(movie.seed(), 42);
+        assert_eq!(movie.frames(), 10);
+        assert_eq!(movie.to_string(), text);
+        assert!(Movie::parse("input 1 10").is_err());
+        assert!(Movie::parse("speed 0").is_err());
+        assert!(Movie::parse("memory 1 1000 00").is_err());
+        assert!(Movie::parse("memory 1 fff 00").is_ok());
+
+        let fast = Movie::parse("seed 1\nspeed 30\nframes 0\n").unwrap();
+        assert_eq!(fast.instructions_per_frame(), 30);
+        assert_eq!(fast.to_string(), "seed 1\nspeed 30\nframes 0\n");
+        assert!(Movie::parse("jump 1").is_err());
+    }
+
+    #[test]
+    fn test_record_keeps_only_changes() {
+        let mut movie = Movie::new(1);
+        for frame in 1..=6 {
+            movie.record(frame, if (3..5).contains(&frame) { Some(0x5) } else { None });
+        }
+
+        assert_eq!(movie.frames(), 6);
+        assert_eq!(movie.inputs, vec![(3, Some(0x5)), (5, None)]);
+        assert_eq!((movie.key_at(2), movie.key_at(4), movie.key_at(5)), (None, Some(0x5), None));
+    }
+
+    #[test]
+    fn test_play_replays_input_and_checks() {
+        // I = 0x300, wait for a key into V0, store V0 at I, loop forever
+        let rom = [0xA3, 0x00, 0xF0, 0x0A, 0xF0, 0x55, 0x12, 0x06];
+        let movie = Movie::parse("frames 6\ninput 2 7\ninput 4 -\nmemory 3 300 00\nmemory 6 300 07").unwrap();
+
+        assert_eq!(movie.play(&mut machine(&rom)), Ok(()));
+
+        let wrong = Movie::parse("frames 6\ninput 2 7\ninput 4 -\nmemory 6 300 08").unwrap();
+        assert_eq!(wrong.play(&mut machine(&rom)).unwrap_err().len(), 1);
+    }
+
+    #[test]
+    fn test_recorded_frames_replay_identically() {
+        // Draws a random sprite, waits for a key and draws it at a random position, forever
+        let rom = [0xC0, 0xFF, 0xC1, 0x1F, 0xD0, 0x15, 0xF2, 0x0A, 0xF2, 0x29, 0xD0, 0x15, 0x12, 0x00];
+        let keys = [None, Some(0x3), Some(0x3), None, None, Some(0xA), None, Some(0x1), None];
+
+        let mut recording = Movie::new(5);
+        let mut recorded = machine(&rom);
+        recording.start(&mut recorded);
+        for frame in 0..60 {
+            recording.record_frame(&mut recorded, keys[frame % keys.len()]).unwrap();
+        }
+        recording.add_check(60, Check::ScreenHash(display::screen_hash(recorded.framebuffer())));
+
+        let replayed = Movie::parse(&recording.to_string()).unwrap();
+        assert_eq!(replayed.frames(), 60);
+        assert_eq!(replayed.play(&mut machine(&rom)), Ok(()));
+        assert_eq!(replayed.key_at(59), keys[59 % keys.len()]);
+    }
+
+    #[test]
+    fn test_same_seed_gives_same_random_numbers() {
+        // I = 0x300, then fill V0-VF with random bytes and store them
+        let mut rom = vec![0xA3, 0x00];
+        for register in 0..16 {
+            rom.extend([0xC0 | register, 0xFF]);
+        }
+        rom.extend([0xFF, 0x55, 0x12, 0x24]);
+
+        let random_bytes = |seed| {
+            let mut chip8 = machine(&rom);
+            Movie::new(seed).start(&mut chip8);
+            chip8.run_frame(chip8::INSTRUCTIONS_PER_FRAME * 2).unwrap();
+            (0x300..0x310).map(|address| chip8.read_byte(address)).collect::<Vec<u8>>()
+        };
+
+        assert_eq!(random_bytes(7), random_bytes(7));
+        assert_ne!(random_bytes(7), random_bytes(8));
+    }
+}
@@ -0,0 +1,60 @@
+use crate::display::{DisplayDriver, WIDTH, HEIGHT};
+
+// Headless driver for running ROMs without a window, e.g. on CI
+#[derive(Clone)]
+pub struct NullDisplayDriver {
+    frame: [bool; WIDTH * HEIGHT],
+    frames_changed: u64,
+}
+
+impl NullDisplayDriver {
+    pub fn new() -> NullDisplayDriver {
+        NullDisplayDriver {
+            frame: [false; WIDTH * HEIGHT],
+            frames_changed: 0,
+        }
+    }
+
+    pub fn latest_frame(&self) -> &[bool; WIDTH * HEIGHT] {
+        &self.frame
+    }
+
+    pub fn frames_changed(&self) -> u64 {
+        self.frames_changed
+    }
+}
+
+impl Default for NullDisplayDriver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DisplayDriver for NullDisplayDriver {
+    fn draw_pixels(&mut self, pixels: &[bool; WIDTH * HEIGHT]) {
+        if *pixels != self.frame {
+            self.frame = *pixels;
+            self.frames_changed += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_only_changed_frames() {
+        let mut driver = NullDisplayDriver::new();
+        let mut frame = [false; WIDTH * HEIGHT];
+
+        driver.draw_pixels(&frame);
+        assert_eq!(driver.frames_changed(), 0);
+
+        frame[0] = true;
+        driver.draw_pixels(&frame);
+        driver.draw_pixels(&frame);
+        assert_eq!(driver.frames_changed(), 1);
+        assert!(driver.latest_frame()[0]);
+    }
+}
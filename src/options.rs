@@ -0,0 +1,219 @@
+use rust_chip8::bench::BenchLimit;
+use rust_chip8::chip8;
+use rust_chip8::filters::Filter;
+use rust_chip8::orientation::Orientation;
+use rust_chip8::palette::{Palette, Palettes};
+use rust_chip8::quirks::Quirks;
+use rust_chip8::rom_database::RomInfo;
+use rust_chip8::screenshot;
+
+use crate::sdl2_display_driver::{ScaleMode, WindowSettings};
+use crate::terminal_display_driver::TerminalMode;
+
+const DEFAULT_ROM: &str = "roms/bc_test.ch8";
+
+#[derive(Clone, Copy)]
+pub enum Frontend {
+    Sdl,
+    Terminal(TerminalMode),
+}
+
+pub struct Options {
+    pub rom: String,
+    pub headless_frames: Option<u64>,
+    pub bench_limit: Option<BenchLimit>,
+    pub frontend: Frontend,
+    pub screenshot_on_exit: bool,
+    pub record_path: Option<String>,
+    pub export_scale: u32,
+    pub palette: Palette,
+    // The built-in palettes, and the user's once add_palettes is called
+    pub palettes: Palettes,
+    pub palettes_path: Option<String>,
+    pub persistence_frames: Option<u32>,
+    pub quirks: Quirks,
+    pub instructions_per_frame: u32,
+    pub rom_database_path: Option<String>,
+    pub seed: Option<u64>,
+    pub record_movie_path: Option<String>,
+    pub play_movie_path: Option<String>,
+    pub recompile_path: Option<String>,
+    pub export_cartridge_path: Option<String>,
+    #[cfg(feature = "jit")]
+    pub jit: bool,
+    pub window: WindowSettings,
+    // Options given on the command line, which take precedence over the ROM database
+    flags: Vec<String>,
+    // --palette, which may name one of the user's palettes, so isn't read until they're loaded
+    palette_spec: Option<String>,
+}
+
+impl Options {
+    pub fn parse(args: &[String]) -> Result<Options, String> {
+        let mut options = Options {
+            rom: DEFAULT_ROM.to_string(),
+            headless_frames: None,
+            bench_limit: None,
+            frontend: Frontend::Sdl,
+            screenshot_on_exit: false,
+            record_path: None,
+            export_scale: screenshot::DEFAULT_SCALE,
+            palette: Palette::default(),
+            palettes: Palettes::builtin(),
+            palettes_path: None,
+            persistence_frames: None,
+            quirks: Quirks::default(),
+            instructions_per_frame: chip8::INSTRUCTIONS_PER_FRAME,
+            rom_database_path: None,
+            seed: None,
+            record_movie_path: None,
+            play_movie_path: None,
+            recompile_path: None,
+            export_cartridge_path: None,
+            #[cfg(feature = "jit")]
+            jit: false,
+            window: WindowSettings::default(),
+            flags: Vec::new(),
+            palette_spec: None,
+        };
+
+        let mut args = args.iter().skip(1);
+
+        while let Some(arg) = args.next() {
+            if arg.starts_with("--") {
+                options.flags.push(arg.clone());
+            }
+
+            match arg.as_str() {
+                "--headless" => {
+                    let frames = args.next().ok_or("--headless needs a frame count")?;
+                    let frames = frames.parse().map_err(|_| format!("Invalid frame count: {}", frames))?;
+                    options.headless_frames = Some(frames);
+                },
+                "--bench" => {
+                    let limit = args.next().ok_or("--bench needs an instruction count or seconds")?;
+                    options.bench_limit = Some(BenchLimit::parse(limit)?);
+                },
+                "--display" => {
+                    options.frontend = match args.next().map(String::as_str) {
+                        Some("sdl") => Frontend::Sdl,
+                        Some("terminal") => Frontend::Terminal(TerminalMode::HalfBlocks),
+                        Some("braille") => Frontend::Terminal(TerminalMode::Braille),
+                        _ => return Err("--display needs one of: sdl, terminal, braille".to_string()),
+                    };
+                },
+                "--screenshot" => options.screenshot_on_exit = true,
+                "--record" => {
+                    let path = args.next().ok_or("--record needs an output file")?;
+                    options.record_path = Some(path.clone());
+                },
+                "--export-scale" => {
+                    let scale = args.next().ok_or("--export-scale needs a scale")?;
+                    options.export_scale = scale.parse().map_err(|_| format!("Invalid scale: {}", scale))?;
+                },
+                "--palette" => {
+                    let spec = args.next().ok_or("--palette needs a name or hex colours")?;
+                    options.palette_spec = Some(spec.clone());
+                },
+                "--palettes" => {
+                    let path = args.next().ok_or("--palettes needs a palette file")?;
+                    options.palettes_path = Some(path.clone());
+                },
+                "--persistence" => {
+                    let frames = args.next().ok_or("--persistence needs a frame count")?;
+                    options.persistence_frames = Some(frames.parse().map_err(|_| format!("Invalid frame count: {}", frames))?);
+                },
+                "--scale" => {
+                    let scale = args.next().ok_or("--scale needs a scale")?;
+                    options.window.scale = scale.parse().map_err(|_| format!("Invalid scale: {}", scale))?;
+                },
+                "--scale-mode" => {
+                    options.window.scale_mode = match args.next().map(String::as_str) {
+                        Some("integer") => ScaleMode::Integer,
+                        Some("fit") => ScaleMode::Fit,
+                        _ => return Err("--scale-mode needs one of: integer, fit".to_string()),
+                    };
+                },
+                "--fullscreen" => options.window.fullscreen = true,
+                "--rotate" => {
+                    let degrees = args.next().ok_or("--rotate needs an angle")?;
+                    options.window.orientation.rotation = Orientation::parse_rotation(degrees)?;
+                },
+                "--flip" => {
+                    let axes = args.next().ok_or("--flip needs one of: horizontal, vertical, both")?;
+                    options.window.orientation.flip(axes)?;
+                },
+                "--filter" => {
+                    let name = args.next().ok_or("--filter needs a filter name")?;
+                    options.window.filter = Filter::parse(name)?;
+                },
+                "--quirks" => {
+                    let name = args.next().ok_or("--quirks needs a profile name")?;
+                    options.quirks = Quirks::parse(name)?;
+                },
+                "--speed" => {
+                    let speed = args.next().ok_or("--speed needs an instruction count")?;
+                    options.instructions_per_frame = speed.parse().ok().filter(|&speed| speed > 0)
+                        .ok_or_else(|| format!("Invalid instruction count: {}", speed))?;
+                },
+                "--rom-db" => {
+                    let path = args.next().ok_or("--rom-db needs a database file")?;
+                    options.rom_database_path = Some(path.clone());
+                },
+                "--seed" => {
+                    let seed = args.next().ok_or("--seed needs a number")?;
+                    options.seed = Some(seed.parse().map_err(|_| format!("Invalid seed: {}", seed))?);
+                },
+                "--record-movie" => {
+                    let path = args.next().ok_or("--record-movie needs an output file")?;
+                    options.record_movie_path = Some(path.clone());
+                },
+                "--play-movie" => {
+                    let path = args.next().ok_or("--play-movie needs a movie file")?;
+                    options.play_movie_path = Some(path.clone());
+                },
+                "--recompile" => {
+                    let path = args.next().ok_or("--recompile needs an output file")?;
+                    options.recompile_path = Some(path.clone());
+                },
+                "--export-cartridge" => {
+                    let path = args.next().ok_or("--export-cartridge needs an output file")?;
+                    options.export_cartridge_path = Some(path.clone());
+                },
+                #[cfg(feature = "jit")]
+                "--jit" => options.jit = true,
+                _ if arg.starts_with("--") => return Err(format!("Unknown option: {}", arg)),
+                _ => options.rom = arg.clone(),
+            }
+        }
+
+        Ok(options)
+    }
+
+    // Adds the user's own palettes, then reads --palette, which may name one of them
+    pub fn add_palettes(&mut self, palettes: Palettes) -> Result<(), String> {
+        self.palettes.merge(palettes);
+        if let Some(spec) = &self.palette_spec {
+            self.palette = self.palettes.resolve(spec)?;
+        }
+        Ok(())
+    }
+
+    // Takes the ROM's recommended settings, except where given on the command line
+    pub fn apply_rom_info(&mut self, info: &RomInfo) {
+        let given = |flag: &str| self.flags.iter().any(|arg| arg == flag);
+
+        if let (Some(quirks), false) = (info.quirks, given("--quirks")) {
+            self.quirks = quirks;
+        }
+        if let (Some(speed), false) = (info.instructions_per_frame, given("--speed")) {
+            self.instructions_per_frame = speed;
+        }
+        if let (Some(palette), false) = (info.palette, given("--palette")) {
+            self.palette = palette;
+        }
+        if let (Some(orientation), false) = (info.orientation, given("--rotate") || given("--flip")) {
+            self.window.orientation = orientation;
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate::image::Image;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Rotation {
+    #[default]
+    None,
+    Clockwise90,
+    Clockwise180,
+    Clockwise270,
+}
+
+// How the screen is turned for presentation. The emulated screen and keypad are unaffected.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Orientation {
+    pub rotation: Rotation,
+    // Flips are applied after rotating, so they are relative to the output
+    pub flip_horizontal: bool,
+    pub flip_vertical: bool,
+}
+
+impl Orientation {
+    pub fn parse_rotation(degrees: &str) -> Result<Rotation, String> {
+        match degrees {
+            "0" => Ok(Rotation::None),
+            "90" => Ok(Rotation::Clockwise90),
+            "180" => Ok(Rotation::Clockwise180),
+            "270" => Ok(Rotation::Clockwise270),
+            _ => Err(format!("Invalid rotation: {} (expected 0, 90, 180 or 270)", degrees)),
+        }
+    }
+
+    // Flips along "horizontal", "vertical" or "both"
+    pub fn flip(&mut self, axes: &str) -> Result<(), String> {
+        match axes {
+            "horizontal" => self.flip_horizontal = true,
+            "vertical" => self.flip_vertical = true,
+            "both" => {
+                self.flip_horizontal = true;
+                self.flip_vertical = true;
+            },
+            _ => return Err(format!("Invalid flip: {} (expected horizontal, vertical or both)", axes)),
+        }
+        Ok(())
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Orientation::default()
+    }
+
+    pub fn output_size(&self, width: usize, height: usize) -> (usize, usize) {
+        match self.rotation {
+            Rotation::None | Rotation::Clockwise180 => (width, height),
+            Rotation::Clockwise90 | Rotation::Clockwise270 => (height, width),
+        }
+    }
+
+    // Maps a position in the output back to the source it shows
+    pub fn source_position(&self, x: usize, y: usize, width: usize, height: usize) -> (usize, usize) {
+        let (output_width, output_height) = self.output_size(width, height);
+
+        let x = if self.flip_horizontal { output_width - 1 - x } else { x };
+        let y = if self.flip_vertical { output_height - 1 - y } else { y };
+
+        match self.rotation {
+            Rotation::None => (x, y),
+            Rotation::Clockwise90 => (y, height - 1 - x),
+            Rotation::Clockwise180 => (width - 1 - x, height - 1 - y),
+            Rotation::Clockwise270 => (width - 1 - y, x),
+        }
+    }
+
+    pub fn apply(&self, image: &Image) -> Image {
+        if self.is_identity() {
+            return image.clone();
+        }
+
+        let (width, height) = (image.width(), image.height());
+        let (output_width, output_height) = self.output_size(width, height);
+
+        Image::from_fn(output_width, output_height, |x, y| {
+            let (source_x, source_y) = self.source_position(x, y, width, height);
+            image.get(source_x as isize, source_y as isize)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::palette::Colour;
+
+    // 3x2 image where every pixel has a distinct colour
+    fn numbered() -> Image {
+        Image::from_fn(3, 2, |x, y| Colour::rgb((x + y * 3) as u8, 0, 0))
+    }
+
+    fn values(image: &Image) -> Vec<u8> {
+        image.pixels().iter().map(|colour| colour.r).collect()
+    }
+
+    #[test]
+    fn test_rotations() {
+        let rotate = |rotation| Orientation { rotation, ..Orientation::default() }.apply(&numbered());
+
+        // 0 1 2
+        // 3 4 5
+        assert_eq!(values(&rotate(Rotation::Clockwise90)), vec![3, 0, 4, 1, 5, 2]);
+        assert_eq!(values(&rotate(Rotation::Clockwise180)), vec![5, 4, 3, 2, 1, 0]);
+        assert_eq!(values(&rotate(Rotation::Clockwise270)), vec![2, 5, 1, 4, 0, 3]);
+        assert_eq!((rotate(Rotation::Clockwise90).width(), rotate(Rotation::Clockwise90).height()), (2, 3));
+    }
+
+    #[test]
+    fn test_flips_apply_after_rotation() {
+        let orientation = Orientation {
+            rotation: Rotation::Clockwise90,
+            flip_horizontal: true,
+            flip_vertical: false,
+        };
+
+        assert_eq!(values(&orientation.apply(&numbered())), vec![0, 3, 1, 4, 2, 5]);
+    }
+}
@@ -0,0 +1,288 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::rom_database;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Colour {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Colour {
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Colour {
+        Colour { r, g, b }
+    }
+
+    // Accepts "#rrggbb" or "rrggbb"
+    pub fn from_hex(hex: &str) -> Result<Colour, String> {
+        let digits = hex.trim().trim_start_matches('#');
+
+        if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Invalid colour: {}", hex));
+        }
+
+        let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).unwrap();
+        Ok(Colour::rgb(channel(0), channel(2), channel(4)))
+    }
+
+    // Linear mix towards `other`, where amount 0.0 is self and 1.0 is other
+    pub fn blend(self, other: Colour, amount: f32) -> Colour {
+        let amount = amount.clamp(0.0, 1.0);
+        let mix = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * amount).round() as u8;
+
+        Colour::rgb(mix(self.r, other.r), mix(self.g, other.g), mix(self.b, other.b))
+    }
+}
+
+pub const PALETTE_SIZE: usize = 16;
+
+// Colours for pixel values 4-15, used by palettes that only define the first four
+const EXTENDED_COLOURS: [Colour; PALETTE_SIZE - 4] = [
+    Colour::rgb(0xff, 0x00, 0x00), Colour::rgb(0x00, 0xff, 0x00),
+    Colour::rgb(0x00, 0x00, 0xff), Colour::rgb(0xff, 0xff, 0x00),
+    Colour::rgb(0x88, 0x00, 0x00), Colour::rgb(0x00, 0x88, 0x00),
+    Colour::rgb(0x00, 0x00, 0x88), Colour::rgb(0x88, 0x88, 0x00),
+    Colour::rgb(0xff, 0x00, 0xff), Colour::rgb(0x00, 0xff, 0xff),
+    Colour::rgb(0x88, 0x00, 0x88), Colour::rgb(0x00, 0x88, 0x88),
+];
+
+// Background, foreground, then the second plane and blend colours used by XO-CHIP
+pub const NAMED_PALETTES: [(&str, [Colour; 4]); 7] = [
+    ("default", [Colour::rgb(0x95, 0x34, 0x0a), Colour::rgb(0xd9, 0x91, 0x49), Colour::rgb(0xf2, 0xc2, 0x8c), Colour::rgb(0x4a, 0x1a, 0x05)]),
+    ("green", [Colour::rgb(0x00, 0x1a, 0x00), Colour::rgb(0x33, 0xff, 0x33), Colour::rgb(0x1f, 0x9f, 0x1f), Colour::rgb(0x0d, 0x4d, 0x0d)]),
+    ("amber", [Colour::rgb(0x1a, 0x0f, 0x00), Colour::rgb(0xff, 0xb0, 0x00), Colour::rgb(0xcc, 0x7a, 0x00), Colour::rgb(0x66, 0x3d, 0x00)]),
+    ("mono", [Colour::rgb(0x00, 0x00, 0x00), Colour::rgb(0xff, 0xff, 0xff), Colour::rgb(0xaa, 0xaa, 0xaa), Colour::rgb(0x55, 0x55, 0x55)]),
+    ("high-contrast", [Colour::rgb(0x00, 0x00, 0x00), Colour::rgb(0xff, 0xff, 0x00), Colour::rgb(0x00, 0xff, 0xff), Colour::rgb(0xff, 0x00, 0xff)]),
+    // Okabe-Ito colours, distinguishable with the common forms of colour blindness
+    ("colour-blind", [Colour::rgb(0x00, 0x00, 0x00), Colour::rgb(0xe6, 0x9f, 0x00), Colour::rgb(0x56, 0xb4, 0xe9), Colour::rgb(0x00, 0x9e, 0x73)]),
+    ("octo", [Colour::rgb(0x99, 0x66, 0x00), Colour::rgb(0xff, 0xcc, 0x00), Colour::rgb(0xff, 0x66, 0x00), Colour::rgb(0x66, 0x22, 0x00)]),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Palette {
+    colours: [Colour; PALETTE_SIZE],
+}
+
+impl Palette {
+    // Any colours not given are taken from the default palette
+    pub fn from_colours(colours: &[Colour]) -> Palette {
+        let mut palette = Palette::default();
+        for (slot, colour) in palette.colours.iter_mut().zip(colours) {
+            *slot = *colour;
+        }
+        palette
+    }
+
+    pub fn named(name: &str) -> Option<Palette> {
+        NAMED_PALETTES
+            .iter()
+            .find(|(palette_name, _)| palette_name.eq_ignore_ascii_case(name))
+            .map(|(_, colours)| Palette::from_colours(colours))
+    }
+
+    // Either a built-in palette name or a comma separated list of hex colours, background first
+    pub fn parse(spec: &str) -> Result<Palette, String> {
+        Palettes::builtin().resolve(spec)
+    }
+
+    // A comma separated list of hex colours, background first
+    fn from_hex(spec: &str) -> Result<Palette, String> {
+        let colours = spec.split(',').map(Colour::from_hex).collect::<Result<Vec<Colour>, String>>()?;
+        if colours.len() > PALETTE_SIZE {
+            return Err(format!("A palette has at most {} colours", PALETTE_SIZE));
+        }
+
+        Ok(Palette::from_colours(&colours))
+    }
+
+    // Cycles through the built-in palettes, starting from the first if this one is custom
+    pub fn next_named(&self) -> (String, Palette) {
+        Palettes::builtin().next(self)
+    }
+
+    pub fn background(&self) -> Colour {
+        self.colours[0]
+    }
+
+    pub fn foreground(&self) -> Colour {
+        self.colours[1]
+    }
+
+    pub fn colours(&self) -> &[Colour; PALETTE_SIZE] {
+        &self.colours
+    }
+
+    pub fn colour(&self, pixel_on: bool) -> Colour {
+        self.colour_for(pixel_on as usize)
+    }
+
+    // Pixel values above 1 come from combining display planes
+    pub fn colour_for(&self, value: usize) -> Colour {
+        self.colours[value % PALETTE_SIZE]
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        let mut colours = [Colour::rgb(0, 0, 0); PALETTE_SIZE];
+        colours[..4].copy_from_slice(&NAMED_PALETTES[0].1);
+        colours[4..].copy_from_slice(&EXTENDED_COLOURS);
+
+        Palette { colours }
+    }
+}
+
+// The palettes that can be chosen by name: the built-in ones, then the user's own. The user's
+// are text, with a palette per line giving its name and its colours as --palette takes them,
+// and lines starting with # are comments:
+//
+//   sepia #2b1d0e,#e0c9a6
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Palettes {
+    named: Vec<(String, Palette)>,
+}
+
+impl Palettes {
+    pub fn builtin() -> Palettes {
+        let named = NAMED_PALETTES.iter().map(|(name, colours)| (name.to_string(), Palette::from_colours(colours))).collect();
+        Palettes { named }
+    }
+
+    pub fn load(path: &str) -> Result<Palettes, String> {
+        let text = fs::read_to_string(path).map_err(|err| format!("Failed to read {}: {}", path, err))?;
+        Palettes::parse(&text).map_err(|err| format!("{}: {}", path, err))
+    }
+
+    // Only the palettes in the text, without the built-in ones
+    pub fn parse(text: &str) -> Result<Palettes, String> {
+        let mut palettes = Palettes::default();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            let error = |reason: String| format!("Invalid palette line {}: {}", number + 1, reason);
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (name, colours) = line.split_once(char::is_whitespace).ok_or_else(|| error(format!("{} has no colours", line)))?;
+            if name.contains(',') {
+                return Err(error(format!("Invalid palette name: {}", name)));
+            }
+            let palette = Palette::from_hex(colours.trim()).map_err(error)?;
+            palettes.merge(Palettes { named: vec![(name.to_string(), palette)] });
+        }
+
+        Ok(palettes)
+    }
+
+    // Palettes in `other` are added, replacing any with the same name
+    pub fn merge(&mut self, other: Palettes) {
+        for (name, palette) in other.named {
+            match self.named.iter_mut().find(|(existing, _)| existing.eq_ignore_ascii_case(&name)) {
+                Some(existing) => existing.1 = palette,
+                None => self.named.push((name, palette)),
+            }
+        }
+    }
+
+    pub fn named(&self, name: &str) -> Option<Palette> {
+        self.named.iter().find(|(palette_name, _)| palette_name.eq_ignore_ascii_case(name)).map(|(_, palette)| *palette)
+    }
+
+    // Either one of these palettes' names or a comma separated list of hex colours
+    pub fn resolve(&self, spec: &str) -> Result<Palette, String> {
+        if let Some(palette) = self.named(spec) {
+            return Ok(palette);
+        }
+
+        if !spec.contains('#') && !spec.contains(',') {
+            let names: Vec<&str> = self.named.iter().map(|(name, _)| name.as_str()).collect();
+            return Err(format!("Unknown palette: {} (expected one of {} or hex colours)", spec, names.join(", ")));
+        }
+
+        Palette::from_hex(spec)
+    }
+
+    // Cycles through these palettes, starting from the first if this one isn't among them
+    pub fn next(&self, palette: &Palette) -> (String, Palette) {
+        let current = self.named.iter().position(|(_, named)| named == palette);
+        let next = current.map_or(0, |i| (i + 1) % self.named.len());
+        self.named[next].clone()
+    }
+}
+
+// Where the user's own palettes live, next to their ROM database
+pub fn user_palettes_path() -> Option<PathBuf> {
+    Some(rom_database::user_config_dir()?.join("palettes.txt"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_named_palette() {
+        let palette = Palette::parse("Amber").unwrap();
+        assert_eq!(palette.foreground(), Colour::rgb(0xff, 0xb0, 0x00));
+        assert_eq!(palette.colour_for(4), EXTENDED_COLOURS[0]);
+    }
+
+    #[test]
+    fn test_parse_hex_palette() {
+        let palette = Palette::parse("#102030,405060").unwrap();
+        assert_eq!(palette.background(), Colour::rgb(0x10, 0x20, 0x30));
+        assert_eq!(palette.foreground(), Colour::rgb(0x40, 0x50, 0x60));
+        assert_eq!(palette.colour_for(2), Palette::default().colour_for(2));
+    }
+
+    #[test]
+    fn test_next_named_wraps_around() {
+        let (name, palette) = Palette::named("octo").unwrap().next_named();
+        assert_eq!(name, "default");
+        assert_eq!(palette, Palette::default());
+
+        let (name, _) = Palette::parse("#000000,#ffffff,#123456").unwrap().next_named();
+        assert_eq!(name, "default");
+    }
+
+    #[test]
+    fn test_blend() {
+        let black = Colour::rgb(0, 0, 0);
+        let white = Colour::rgb(0xff, 0xff, 0xff);
+
+        assert_eq!(black.blend(white, 0.0), black);
+        assert_eq!(black.blend(white, 0.5), Colour::rgb(0x80, 0x80, 0x80));
+        assert_eq!(black.blend(white, 2.0), white);
+    }
+
+    #[test]
+    fn test_user_palettes_add_to_the_builtin_ones() {
+        let mut palettes = Palettes::builtin();
+        let user = Palettes::parse("# Mine\nsepia #2b1d0e,#e0c9a6\n\nMono #111111,#eeeeee\n").unwrap();
+
+        palettes.merge(user);
+
+        let sepia = palettes.resolve("Sepia").unwrap();
+        assert_eq!(sepia.foreground(), Colour::rgb(0xe0, 0xc9, 0xa6));
+        assert_eq!(palettes.named("mono").unwrap().background(), Colour::rgb(0x11, 0x11, 0x11));
+        assert_eq!(palettes.next(&Palette::named("octo").unwrap()), ("sepia".to_string(), sepia));
+        assert_eq!(palettes.next(&sepia).0, "default");
+        assert!(Palette::parse("sepia").is_err());
+    }
+
+    #[test]
+    fn test_parse_invalid_user_palettes() {
+        for text in ["sepia", "sepia #12345", "a,b #000000", "sepia red"] {
+            assert!(Palettes::parse(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid_palette() {
+        assert!(Palette::parse("sepia").is_err());
+        assert!(Palette::parse("#12345,#000000").is_err());
+    }
+}
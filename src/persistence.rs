@@ -0,0 +1,70 @@
+use crate::display::{WIDTH, HEIGHT};
+use crate::palette::{Colour, Palette};
+
+// Simulates phosphor persistence so XOR-redrawn sprites don't flicker. Pixels light
+// instantly but take `fade_frames` 60Hz frames to fade out once switched off. Only
+// the presented colours are affected, never the emulated screen.
+pub struct Persistence {
+    fade_step: f32,
+    intensities: [f32; WIDTH * HEIGHT],
+}
+
+impl Persistence {
+    pub fn new(fade_frames: u32) -> Persistence {
+        Persistence {
+            fade_step: 1.0 / fade_frames.max(1) as f32,
+            intensities: [0.0; WIDTH * HEIGHT],
+        }
+    }
+
+    pub fn light(&mut self, pixels: &[bool; WIDTH * HEIGHT]) {
+        for (intensity, &pixel_on) in self.intensities.iter_mut().zip(pixels.iter()) {
+            if pixel_on {
+                *intensity = 1.0;
+            }
+        }
+    }
+
+    // Called once per 60Hz frame
+    pub fn fade(&mut self, pixels: &[bool; WIDTH * HEIGHT]) {
+        for (intensity, &pixel_on) in self.intensities.iter_mut().zip(pixels.iter()) {
+            if !pixel_on {
+                *intensity = (*intensity - self.fade_step).max(0.0);
+            }
+        }
+    }
+
+    pub fn intensity(&self, index: usize) -> f32 {
+        self.intensities[index]
+    }
+
+    pub fn colour(&self, index: usize, palette: &Palette) -> Colour {
+        palette.background().blend(palette.foreground(), self.intensities[index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pixels_fade_after_switching_off() {
+        let mut persistence = Persistence::new(4);
+        let mut pixels = [false; WIDTH * HEIGHT];
+
+        pixels[0] = true;
+        persistence.light(&pixels);
+        persistence.fade(&pixels);
+        assert_eq!(persistence.intensity(0), 1.0);
+
+        pixels[0] = false;
+        persistence.fade(&pixels);
+        assert_eq!(persistence.intensity(0), 0.75);
+
+        for _ in 0..3 {
+            persistence.fade(&pixels);
+        }
+        assert_eq!(persistence.intensity(0), 0.0);
+        assert_eq!(persistence.colour(0, &Palette::default()), Palette::default().background());
+    }
+}
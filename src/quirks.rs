@@ -0,0 +1,68 @@
+// Behaviours that differ between CHIP-8 interpreters, which ROMs may depend on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quirks {
+    // 8XY6/8XYE copy VY into VX before shifting, instead of shifting VX in place
+    pub shift_uses_vy: bool,
+    // FX55/FX65 leave I pointing after the last register stored or loaded
+    pub load_store_increments_index: bool,
+    // 8XY1/8XY2/8XY3 reset VF to 0
+    pub logic_resets_vf: bool,
+    // BNNN is treated as BXNN, jumping to XNN + VX instead of NNN + V0
+    pub jump_uses_vx: bool,
+    // FX1E sets VF when I goes past 0xFFF, as on the Amiga interpreter
+    pub index_overflow_sets_vf: bool,
+}
+
+impl Quirks {
+    pub const COSMAC_VIP: Quirks = Quirks {
+        shift_uses_vy: true,
+        load_store_increments_index: true,
+        logic_resets_vf: true,
+        jump_uses_vx: false,
+        index_overflow_sets_vf: false,
+    };
+
+    pub const SUPER_CHIP: Quirks = Quirks {
+        shift_uses_vy: false,
+        load_store_increments_index: false,
+        logic_resets_vf: false,
+        jump_uses_vx: true,
+        index_overflow_sets_vf: false,
+    };
+
+    pub const PROFILES: [(&'static str, Quirks); 3] = [
+        ("default", Quirks::DEFAULT),
+        ("cosmac", Quirks::COSMAC_VIP),
+        ("superchip", Quirks::SUPER_CHIP),
+    ];
+
+    // What this emulator has always done
+    const DEFAULT: Quirks = Quirks {
+        shift_uses_vy: false,
+        load_store_increments_index: false,
+        logic_resets_vf: false,
+        jump_uses_vx: false,
+        index_overflow_sets_vf: true,
+    };
+
+    pub fn named(name: &str) -> Option<Quirks> {
+        Quirks::PROFILES
+            .iter()
+            .find(|(profile, _)| profile.eq_ignore_ascii_case(name))
+            .map(|(_, quirks)| *quirks)
+    }
+
+    #[cfg(feature = "std")]
+    pub fn parse(name: &str) -> Result<Quirks, String> {
+        Quirks::named(name).ok_or_else(|| {
+            let names: Vec<&str> = Quirks::PROFILES.iter().map(|(name, _)| *name).collect();
+            format!("Unknown quirks profile: {} (expected one of {})", name, names.join(", "))
+        })
+    }
+}
+
+impl Default for Quirks {
+    fn default() -> Self {
+        Quirks::DEFAULT
+    }
+}
@@ -0,0 +1,421 @@
+use std::collections::BTreeSet;
+
+use crate::chip8;
+use crate::cpu::Address;
+use crate::instructions::Instruction;
+use crate::memory;
+use crate::quirks::Quirks;
+
+// Bytes of the embedded ROM per line of generated source
+const ROM_BYTES_PER_LINE: usize = 16;
+
+// A run of instructions only ever entered at the top
+struct Block {
+    start: Address,
+    instructions: Vec<(Address, u16, Instruction)>,
+}
+
+// Whether control carries on to the next instruction, and if not where it can go
+enum Exit {
+    Continue,
+    // Ends the block. Only statically known targets are listed.
+    Branch(Vec<Address>),
+}
+
+// Turns a ROM into the source of a Rust module that runs each basic block found by following
+// its control flow as a native function, with each instruction written out in Rust for
+// `quirks`. BNNN and anything else the analysis couldn't follow are left to the interpreter, as
+// are blocks the ROM has overwritten since.
+pub fn recompile(rom: &[u8], name: &str, quirks: Quirks) -> String {
+    let blocks = find_blocks(rom);
+    let indirect = blocks.iter().flat_map(|block| &block.instructions).any(|&(_, _, instruction)| matches!(instruction, Instruction::JumpWithOffset(_)));
+
+    let mut source = header(rom, name, quirks, indirect);
+    source.push_str(&dispatcher(&blocks));
+
+    for block in &blocks {
+        source.push_str(&block_function(block, quirks));
+    }
+
+    source
+}
+
+fn instruction_at(rom: &[u8], address: Address) -> Option<(u16, Instruction)> {
+    let offset = address.checked_sub(chip8::PROGRAM_START)? as usize;
+    let bytes = rom.get(offset..offset + 2)?;
+    let opcode = u16::from_be_bytes([bytes[0], bytes[1]]);
+
+    Instruction::try_decode(opcode).map(|instruction| (opcode, instruction))
+}
+
+fn exit(instruction: Instruction, address: Address) -> Exit {
+    let next = address + 2;
+
+    match instruction {
+        Instruction::Jump(target) => Exit::Branch(vec![target]),
+        Instruction::Call(target) => Exit::Branch(vec![target, next]),
+        Instruction::Return | Instruction::JumpWithOffset(_) => Exit::Branch(vec![]),
+        Instruction::CheckEqualValue(..)
+        | Instruction::CheckNotEqualValue(..)
+        | Instruction::CheckEqual(..)
+        | Instruction::CheckNotEqual(..)
+        | Instruction::SkipIfKeyPressed(_)
+        | Instruction::SkipIfKeyNotPressed(_) => Exit::Branch(vec![next, next + 2]),
+        // Repeats until a key is released
+        Instruction::WaitForKeyPress(_) => Exit::Branch(vec![address, next]),
+        _ => Exit::Continue,
+    }
+}
+
+// Addresses that start a block, following every statically known branch from the entry point
+fn find_leaders(rom: &[u8]) -> BTreeSet<Address> {
+    let mut leaders = BTreeSet::new();
+    let mut pending = vec![chip8::PROGRAM_START];
+
+    while let Some(start) = pending.pop() {
+        if instruction_at(rom, start).is_none() || !leaders.insert(start) {
+            continue;
+        }
+
+        let mut address = start;
+        while let Some((_, instruction)) = instruction_at(rom, address) {
+            match exit(instruction, address) {
+                Exit::Continue => address += 2,
+                Exit::Branch(targets) => {
+                    pending.extend(targets);
+                    break;
+                },
+            }
+        }
+    }
+
+    leaders
+}
+
+// Blocks run from each leader to the first branch, stopping short of the next leader
+fn find_blocks(rom: &[u8]) -> Vec<Block> {
+    let leaders = find_leaders(rom);
+
+    leaders
+        .iter()
+        .map(|&start| {
+            let mut block = Block { start, instructions: Vec::new() };
+            let mut address = start;
+
+            while let Some((opcode, instruction)) = instruction_at(rom, address) {
+                block.instructions.push((address, opcode, instruction));
+
+                if let Exit::Branch(_) = exit(instruction, address) {
+                    break;
+                }
+
+                address += 2;
+                if leaders.contains(&address) {
+                    break;
+                }
+            }
+
+            block
+        })
+        .collect()
+}
+
+fn header(rom: &[u8], name: &str, quirks: Quirks, indirect: bool) -> String {
+    let mut source = format!(
+        "// Recompiled from {} by rust-chip8 --recompile. Each basic block reachable from 0x{:03X}
+// through jumps, calls and skips is a native function, with its instructions written out in
+// Rust for the quirks below. BNNN and anything else the analysis couldn't follow are
+// interpreted, as are blocks the ROM has overwritten, blocks that wouldn't fit in what's left
+// of the frame and every instruction when the machine's quirks have been changed.
+
+use rust_chip8::chip8::{{self, Chip8}};
+use rust_chip8::cpu::Fault;
+use rust_chip8::display::DisplayDriver;
+{}use rust_chip8::quirks::Quirks;
+
+pub const QUIRKS: Quirks = {:?};
+
+pub const ROM: [u8; {}] = [
+",
+        name,
+        chip8::PROGRAM_START,
+        if indirect { "use rust_chip8::instructions::Instruction;\n" } else { "" },
+        quirks,
+        rom.len()
+    );
+
+    for line in rom.chunks(ROM_BYTES_PER_LINE) {
+        let bytes: Vec<String> = line.iter().map(|byte| format!("0x{:02X}", byte)).collect();
+        source.push_str(&format!("    {},\n", bytes.join(", ")));
+    }
+
+    source.push_str(
+        "];
+
+pub fn load<D: DisplayDriver>(chip8: &mut Chip8<D>) {
+    chip8.set_quirks(QUIRKS);
+    chip8.load_default_font();
+    chip8.load_rom(&ROM).expect(\"ROM fits in memory\");
+}
+
+fn unchanged<D: DisplayDriver>(chip8: &Chip8<D>, address: u16, length: usize) -> bool {
+    let offset = (address - chip8::PROGRAM_START) as usize;
+    chip8.memory().read_bytes(address, length) == &ROM[offset..offset + length]
+}
+",
+    );
+
+    source
+}
+
+fn dispatcher(blocks: &[Block]) -> String {
+    let mut source = String::from(
+        "
+// Like Chip8::run_frame, running recompiled blocks wherever they can be
+pub fn run_frame<D: DisplayDriver>(chip8: &mut Chip8<D>, instructions: u32) -> Result<(), Fault> {
+    if chip8.cpu().quirks() != QUIRKS {
+        return chip8.run_frame(instructions);
+    }
+
+    let mut remaining = instructions;
+
+    while remaining > 0 {
+        chip8.precycle();
+
+        remaining -= match chip8.cpu().program_counter() {
+",
+    );
+
+    for block in blocks {
+        let length = block.instructions.len();
+        // The loop already guarantees room for one instruction
+        let fits = if length > 1 { format!("remaining >= {} && ", length) } else { String::new() };
+
+        source.push_str(&format!(
+            "            0x{:03X} if {}unchanged(chip8, 0x{:03X}, {}) => block_{:03x}(chip8)?,\n",
+            block.start, fits, block.start, length * 2, block.start
+        ));
+    }
+
+    source.push_str(
+        "            _ => {
+                chip8.cycle()?;
+                1
+            },
+        };
+    }
+    chip8.timer_cycle();
+    Ok(())
+}
+",
+    );
+
+    source
+}
+
+fn block_function(block: &Block, quirks: Quirks) -> String {
+    let mut source = format!("\nfn block_{:03x}<D: DisplayDriver>(chip8: &mut Chip8<D>) -> Result<u32, Fault> {{\n", block.start);
+
+    // BNNN's target depends on a register, so it's interpreted and leaves the dispatcher to find it
+    let (native, indirect) = match block.instructions.split_last() {
+        Some((&(address, opcode, instruction @ Instruction::JumpWithOffset(_)), native)) => (native, Some((address, opcode, instruction))),
+        _ => (&block.instructions[..], None),
+    };
+
+    if let Some(&(last, _, last_instruction)) = native.last() {
+        source.push_str("    chip8.run_recompiled(|m| {\n");
+        for &(address, opcode, instruction) in native {
+            source.push_str(&format!("        // 0x{:03X}: {:04X}\n", address, opcode));
+            for line in native_instruction(address, instruction, quirks) {
+                source.push_str(&format!("        {}\n", line));
+            }
+        }
+        if let Exit::Continue = exit(last_instruction, last) {
+            source.push_str(&format!("        m.program_counter = 0x{:03X};\n", next(last)));
+        }
+        source.push_str("        Ok(())\n    })?;\n");
+    }
+
+    if let Some((address, opcode, instruction)) = indirect {
+        // Instruction's Debug output is also the Rust expression that builds it
+        source.push_str(&format!("    chip8.execute_at(0x{:03X}, Instruction::{:?})?; // {:04X}\n", address, instruction, opcode));
+    }
+
+    source.push_str(&format!("    Ok({})\n}}\n", block.instructions.len()));
+    source
+}
+
+fn next(address: Address) -> Address {
+    (address + 2) & memory::ADDRESS_MASK
+}
+
+// The Rust for an instruction at `address`, working on RecompiledState `m` as Cpu::execute
+// works on the CPU. Instructions that end a block set the program counter.
+fn native_instruction(address: Address, instruction: Instruction, quirks: Quirks) -> Vec<String> {
+    let v = |register: u8| format!("m.v_registers[0x{:X}]", register);
+    let skip_if = |condition: String| {
+        vec![format!(
+            "m.program_counter = if {} {{ 0x{:03X} }} else {{ 0x{:03X} }};",
+            condition, next(next(address)), next(address)
+        )]
+    };
+    let flag_after_logic = || if quirks.logic_resets_vf { vec![format!("{} = 0;", v(0xF))] } else { vec![] };
+    let increment_index = |vx: u8| {
+        if quirks.load_store_increments_index {
+            vec![format!("m.i_register = (m.i_register + 0x{:X}) & 0x{:03X};", vx as u16 + 1, memory::ADDRESS_MASK)]
+        } else {
+            vec![]
+        }
+    };
+
+    match instruction {
+        Instruction::ClearScreen => vec!["m.display.clear();".to_string(), "m.display.draw_if_dirty();".to_string()],
+        Instruction::Return => vec![format!("m.ret(0x{:03X})?;", address)],
+        Instruction::Jump(target) => vec![format!("m.program_counter = 0x{:03X};", target)],
+        Instruction::Call(target) => vec![format!("m.call(0x{:03X}, 0x{:03X})?;", address, target)],
+        Instruction::CheckEqualValue(vx, value) => skip_if(format!("{} == 0x{:02X}", v(vx), value)),
+        Instruction::CheckNotEqualValue(vx, value) => skip_if(format!("{} != 0x{:02X}", v(vx), value)),
+        Instruction::CheckEqual(vx, vy) => skip_if(format!("{} == {}", v(vx), v(vy))),
+        Instruction::CheckNotEqual(vx, vy) => skip_if(format!("{} != {}", v(vx), v(vy))),
+        Instruction::SetRegisterToValue(vx, value) => vec![format!("{} = 0x{:02X};", v(vx), value)],
+        Instruction::AddValueToRegister(vx, value) => vec![format!("{} = {}.wrapping_add(0x{:02X});", v(vx), v(vx), value)],
+        Instruction::SetRegister(vx, vy) => vec![format!("{} = {};", v(vx), v(vy))],
+        Instruction::Or(vx, vy) => [vec![format!("{} |= {};", v(vx), v(vy))], flag_after_logic()].concat(),
+        Instruction::And(vx, vy) => [vec![format!("{} &= {};", v(vx), v(vy))], flag_after_logic()].concat(),
+        Instruction::Xor(vx, vy) => [vec![format!("{} ^= {};", v(vx), v(vy))], flag_after_logic()].concat(),
+        Instruction::Add(vx, vy) => vec![
+            format!("let (value, carry) = {}.overflowing_add({});", v(vx), v(vy)),
+            format!("{} = value;", v(vx)),
+            format!("{} = carry as u8;", v(0xF)),
+        ],
+        Instruction::Subtract { destination, first, second } => vec![
+            format!("let (value, borrow) = {}.overflowing_sub({});", v(first), v(second)),
+            format!("{} = value;", v(destination)),
+            format!("{} = !borrow as u8;", v(0xF)),
+        ],
+        Instruction::ShiftRight(vx, vy) => vec![
+            format!("let value = {};", v(if quirks.shift_uses_vy { vy } else { vx })),
+            format!("{} = value >> 1;", v(vx)),
+            format!("{} = value & 0x01;", v(0xF)),
+        ],
+        Instruction::ShiftLeft(vx, vy) => vec![
+            format!("let value = {};", v(if quirks.shift_uses_vy { vy } else { vx })),
+            format!("{} = value << 1;", v(vx)),
+            format!("{} = value >> 7;", v(0xF)),
+        ],
+        Instruction::SetIndex(index) => vec![format!("m.i_register = 0x{:03X};", index)],
+        Instruction::JumpWithOffset(_) => unreachable!("BNNN is interpreted"),
+        Instruction::Random(vx, mask) => vec![format!("{} = m.random() & 0x{:02X};", v(vx), mask)],
+        Instruction::Display { vx, vy, pixel_height } => vec![
+            format!("let (x, y) = ({}, {});", v(vx), v(vy)),
+            "m.display.begin_draw();".to_string(),
+            format!("for row in 0..{}u16 {{", pixel_height),
+            "    m.display.set_pixels(x, y.saturating_add(row as u8), m.memory.read_byte(m.i_register + row), y);".to_string(),
+            "}".to_string(),
+            format!("{} = m.display.did_collide() as u8;", v(0xF)),
+            "m.display.draw_if_dirty();".to_string(),
+        ],
+        Instruction::SkipIfKeyPressed(vx) => skip_if(format!("m.keyboard.is_key_pressed({})", v(vx))),
+        Instruction::SkipIfKeyNotPressed(vx) => skip_if(format!("!m.keyboard.is_key_pressed({})", v(vx))),
+        Instruction::ReadDelayTimer(vx) => vec![format!("{} = m.delay_timer;", v(vx))],
+        Instruction::WriteDelayTimer(vx) => vec![format!("m.delay_timer = {};", v(vx))],
+        Instruction::WriteSoundTimer(vx) => vec![format!("m.sound_timer = {};", v(vx))],
+        Instruction::AddRegisterToIndex(vx) => {
+            let mut lines = vec![
+                format!("let index = m.i_register + {} as u16;", v(vx)),
+                format!("m.i_register = index & 0x{:03X};", memory::ADDRESS_MASK),
+            ];
+            if quirks.index_overflow_sets_vf {
+                lines.push(format!("{} = (index > 0x{:03X}) as u8;", v(0xF), memory::ADDRESS_MASK));
+            }
+            lines
+        },
+        // A release with nothing held before it isn't a key press, so keeps waiting
+        Instruction::WaitForKeyPress(vx) => vec![
+            "if let (true, Some(key)) = (m.keyboard.has_signal_keypress(), m.keyboard.key_last_pressed()) {".to_string(),
+            format!("    {} = key;", v(vx)),
+            format!("    m.program_counter = 0x{:03X};", next(address)),
+            "} else {".to_string(),
+            format!("    m.program_counter = 0x{:03X};", address),
+            "}".to_string(),
+        ],
+        Instruction::SetIndexToSprite(vx) => vec![format!("m.i_register = 0x{:03X} + {} as u16 * 5;", chip8::FONT_START, v(vx))],
+        Instruction::StoreBCD(vx) => vec![
+            format!("let value = {};", v(vx)),
+            "m.memory.write_byte(m.i_register, value / 100);".to_string(),
+            "m.memory.write_byte(m.i_register + 1, value / 10 % 10);".to_string(),
+            "m.memory.write_byte(m.i_register + 2, value % 10);".to_string(),
+        ],
+        Instruction::StoreRegisters(vx) => [
+            vec![
+                format!("for register in 0..=0x{:X} {{", vx),
+                "    m.memory.write_byte(m.i_register + register as u16, m.v_registers[register]);".to_string(),
+                "}".to_string(),
+            ],
+            increment_index(vx),
+        ].concat(),
+        Instruction::LoadRegisters(vx) => [
+            vec![
+                format!("for register in 0..=0x{:X} {{", vx),
+                "    m.v_registers[register] = m.memory.read_byte(m.i_register + register as u16);".to_string(),
+                "}".to_string(),
+            ],
+            increment_index(vx),
+        ].concat(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocks_follow_control_flow() {
+        let rom = [
+            0x22, 0x06, // 0x200: call 0x206
+            0x30, 0x00, // 0x202: skip if V0 == 0
+            0x12, 0x02, // 0x204: jump to 0x202
+            0x00, 0xEE, // 0x206: return
+            0xB3, 0x00, // 0x208: never reached
+        ];
+
+        let starts: Vec<Address> = find_blocks(&rom).iter().map(|block| block.start).collect();
+        assert_eq!(starts, vec![0x200, 0x202, 0x204, 0x206]);
+    }
+
+    #[test]
+    fn test_blocks_stop_at_unknown_opcodes() {
+        let rom = [0x60, 0x01, 0x70, 0x01, 0xFF, 0xFF];
+
+        let blocks = find_blocks(&rom);
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].instructions.len(), 2);
+    }
+
+    #[test]
+    fn test_only_bnnn_is_interpreted() {
+        let rom = [0x60, 0x01, 0x80, 0x14, 0xF0, 0x33, 0xB2, 0x00];
+
+        let source = recompile(&rom, "test", Quirks::default());
+        assert!(source.contains("m.v_registers[0x0] = 0x01;"));
+        assert!(source.contains("let (value, carry) = m.v_registers[0x0].overflowing_add(m.v_registers[0x1]);"));
+        assert!(source.contains("m.memory.write_byte(m.i_register, value / 100);"));
+        assert_eq!(source.matches("execute_at").count(), 1);
+        assert!(source.contains("chip8.execute_at(0x206, Instruction::JumpWithOffset(512))?;"));
+    }
+
+    #[test]
+    fn test_instructions_follow_quirks() {
+        let rom = [0x80, 0x16, 0x80, 0x11, 0xF1, 0x55];
+
+        let cosmac = recompile(&rom, "test", Quirks::COSMAC_VIP);
+        assert!(cosmac.contains("let value = m.v_registers[0x1];"));
+        assert!(cosmac.contains("m.i_register = (m.i_register + 0x2) & 0xFFF;"));
+        assert!(cosmac.contains("m.v_registers[0xF] = 0;"));
+
+        let superchip = recompile(&rom, "test", Quirks::SUPER_CHIP);
+        assert!(superchip.contains("let value = m.v_registers[0x0];"));
+        assert!(!superchip.contains("m.i_register = (m.i_register"));
+        assert!(!superchip.contains("m.v_registers[0xF] = 0;"));
+    }
+}
@@ -0,0 +1,180 @@
+use std::fs::File;
+use std::io::{self, BufWriter};
+
+use gif::{Encoder, Frame, Repeat};
+
+use crate::display::{WIDTH, HEIGHT};
+use crate::image::ImageSettings;
+
+const FRAMES_PER_SECOND: u64 = 60;
+
+// Most GIF viewers treat delays under 2 centiseconds as 10, so shorter frames are dropped
+const MIN_DELAY_CENTISECONDS: u64 = 2;
+
+// Only used when a filter produces more than 256 colours, 1 is best and 30 fastest
+const QUANTISE_SPEED: i32 = 10;
+
+// Records frames captured at 60 fps into an animated GIF. Identical consecutive
+// frames are merged into one with a longer delay, so static screens cost nothing.
+pub struct GifRecorder {
+    encoder: Encoder<BufWriter<File>>,
+    settings: ImageSettings,
+    frame_count: u64,
+    pending: Option<PendingFrame>,
+}
+
+struct PendingFrame {
+    pixels: [bool; WIDTH * HEIGHT],
+    start_frame: u64,
+}
+
+impl GifRecorder {
+    pub fn create(path: &str, settings: ImageSettings) -> io::Result<GifRecorder> {
+        let (width, height) = settings.size();
+        let (Ok(width), Ok(height)) = (u16::try_from(width), u16::try_from(height)) else {
+            let message = format!("A {}x{} recording is larger than a GIF can be", width, height);
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, message));
+        };
+
+        // Frames carry their own colour tables, as filters can add colours beyond the palette
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = Encoder::new(file, width, height, &[])
+            .map_err(io::Error::other)?;
+        encoder.set_repeat(Repeat::Infinite).map_err(io::Error::other)?;
+
+        Ok(GifRecorder {
+            encoder,
+            settings,
+            frame_count: 0,
+            pending: None,
+        })
+    }
+
+    pub fn capture(&mut self, pixels: &[bool; WIDTH * HEIGHT]) -> io::Result<()> {
+        let now = self.frame_count;
+        self.frame_count += 1;
+
+        match &mut self.pending {
+            Some(pending) if pending.pixels == *pixels => Ok(()),
+            Some(pending) if to_centiseconds(now) - to_centiseconds(pending.start_frame) < MIN_DELAY_CENTISECONDS => {
+                pending.pixels = *pixels;
+                Ok(())
+            },
+            _ => {
+                let result = self.write_pending(now);
+                self.pending = Some(PendingFrame { pixels: *pixels, start_frame: now });
+                result
+            },
+        }
+    }
+
+    pub fn frames_captured(&self) -> u64 {
+        self.frame_count
+    }
+
+    fn write_pending(&mut self, end_frame: u64) -> io::Result<()> {
+        let pending = match self.pending.take() {
+            Some(pending) => pending,
+            None => return Ok(()),
+        };
+
+        let image = self.settings.render(&pending.pixels);
+
+        // Delay is derived from absolute frame times so rounding doesn't drift
+        let delay = to_centiseconds(end_frame) - to_centiseconds(pending.start_frame);
+
+        // Both fit in a u16, as create checked
+        let mut frame = Frame::from_rgb_speed(image.width() as u16, image.height() as u16, &image.to_rgb_bytes(), QUANTISE_SPEED);
+        frame.delay = delay.max(MIN_DELAY_CENTISECONDS).min(u16::MAX as u64) as u16;
+
+        self.encoder.write_frame(&frame).map_err(io::Error::other)
+    }
+}
+
+impl Drop for GifRecorder {
+    fn drop(&mut self) {
+        let end_frame = self.frame_count.max(1);
+        let _ = self.write_pending(end_frame);
+    }
+}
+
+fn to_centiseconds(frames: u64) -> u64 {
+    (frames * 100 + FRAMES_PER_SECOND / 2) / FRAMES_PER_SECOND
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filters::Filter;
+    use crate::orientation::Orientation;
+    use crate::palette::Palette;
+
+    fn settings() -> ImageSettings {
+        ImageSettings { palette: Palette::default(), orientation: Orientation::default(), filter: Filter::None, scale: 1 }
+    }
+
+    fn read_delays(path: &str) -> Vec<u16> {
+        let mut decoder = gif::DecodeOptions::new().read_info(File::open(path).unwrap()).unwrap();
+        let mut delays = Vec::new();
+
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            delays.push(frame.delay);
+        }
+
+        delays
+    }
+
+    #[test]
+    fn test_static_frames_are_merged() {
+        let path = std::env::temp_dir().join("rust_chip8_test_static_frames.gif");
+        let path = path.to_str().unwrap();
+
+        let blank = [false; WIDTH * HEIGHT];
+        let mut lit = blank;
+        lit[0] = true;
+
+        {
+            let mut recorder = GifRecorder::create(path, settings()).unwrap();
+            for _ in 0..60 {
+                recorder.capture(&blank).unwrap();
+            }
+            for _ in 0..30 {
+                recorder.capture(&lit).unwrap();
+            }
+        }
+
+        assert_eq!(read_delays(path), vec![100, 50]);
+    }
+
+    #[test]
+    fn test_rejects_sizes_too_large_for_a_gif() {
+        let path = std::env::temp_dir().join("rust_chip8_test_too_large.gif");
+        let path = path.to_str().unwrap();
+        let _ = std::fs::remove_file(path);
+
+        let scale = u16::MAX as u32 / WIDTH as u32 + 1;
+        assert!(GifRecorder::create(path, ImageSettings { scale, ..settings() }).is_err());
+        assert!(!std::path::Path::new(path).exists());
+    }
+
+    #[test]
+    fn test_short_frames_are_dropped() {
+        let path = std::env::temp_dir().join("rust_chip8_test_short_frames.gif");
+        let path = path.to_str().unwrap();
+
+        let blank = [false; WIDTH * HEIGHT];
+        let mut lit = blank;
+        lit[0] = true;
+
+        {
+            let mut recorder = GifRecorder::create(path, settings()).unwrap();
+            for i in 0..6 {
+                recorder.capture(if i % 2 == 0 { &blank } else { &lit }).unwrap();
+            }
+        }
+
+        let delays = read_delays(path);
+        assert!(delays.iter().all(|&delay| delay >= 2));
+        assert_eq!(delays.iter().sum::<u16>(), 10);
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::orientation::Orientation;
+use crate::palette::Palette;
+use crate::quirks::Quirks;
+
+const BUNDLED: &str = include_str!("rom_database.txt");
+
+// What's known about a ROM, identified by its SHA-1. Databases are text, with an entry per
+// ROM made of a line per field. Every field is optional, and # starts a comment at the start of
+// a line or after a space, unless it begins a hex colour:
+//
+//   [f13766c14aeb02ad8d4d103cb5eadd282d20cddc]
+//   title Brix
+//   author Andreas Gustafsson
+//   platform chip-8
+//   quirks default        a --quirks profile
+//   speed 15              instructions per frame
+//   key 4 left            what a key does, one line per key
+//   palette amber         a --palette name or hex colours, like #2b1d0e,#e0c9a6
+//   rotate 90             a --rotate angle, for ROMs designed for a turned display
+//   flip horizontal       a --flip direction
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RomInfo {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub platform: Option<String>,
+    pub quirks: Option<Quirks>,
+    pub instructions_per_frame: Option<u32>,
+    pub keys: Vec<(u8, String)>,
+    pub palette: Option<Palette>,
+    pub orientation: Option<Orientation>,
+}
+
+impl RomInfo {
+    // Fields set in `other` replace these, with its keys replacing all of these keys
+    fn merge(&mut self, other: RomInfo) {
+        self.title = other.title.or(self.title.take());
+        self.author = other.author.or(self.author.take());
+        self.platform = other.platform.or(self.platform.take());
+        self.quirks = other.quirks.or(self.quirks);
+        self.instructions_per_frame = other.instructions_per_frame.or(self.instructions_per_frame);
+        if !other.keys.is_empty() {
+            self.keys = other.keys;
+        }
+        self.palette = other.palette.or(self.palette);
+        self.orientation = other.orientation.or(self.orientation);
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RomDatabase {
+    entries: HashMap<String, RomInfo>,
+}
+
+impl RomDatabase {
+    // The database built into the emulator, covering the ROMs in roms
+    pub fn bundled() -> RomDatabase {
+        RomDatabase::parse(BUNDLED).expect("Invalid bundled ROM database")
+    }
+
+    pub fn load(path: &str) -> Result<RomDatabase, String> {
+        let text = fs::read_to_string(path).map_err(|err| format!("Failed to read {}: {}", path, err))?;
+        RomDatabase::parse(&text).map_err(|err| format!("{}: {}", path, err))
+    }
+
+    pub fn parse(text: &str) -> Result<RomDatabase, String> {
+        let mut database = RomDatabase::default();
+        let mut current: Option<&mut RomInfo> = None;
+
+        for (number, line) in text.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            let error = |reason: String| format!("Invalid ROM database line {}: {}", number + 1, reason);
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(hash) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+                if hash.len() != 40 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(error(format!("{} isn't a SHA-1", hash)));
+                }
+                current = Some(database.entries.entry(hash.to_ascii_lowercase()).or_default());
+                continue;
+            }
+
+            let info = current.as_deref_mut().ok_or_else(|| error("field before the first [sha1]".to_string()))?;
+            let (field, value) = line.split_once(char::is_whitespace).ok_or_else(|| error(format!("{} has no value", line)))?;
+            let value = value.trim();
+
+            match field {
+                "title" => info.title = Some(value.to_string()),
+                "author" => info.author = Some(value.to_string()),
+                "platform" => info.platform = Some(value.to_string()),
+                "quirks" => info.quirks = Some(Quirks::parse(value).map_err(error)?),
+                "speed" => {
+                    let speed = value.parse().ok().filter(|&speed| speed > 0);
+                    info.instructions_per_frame = Some(speed.ok_or_else(|| error(format!("Invalid speed: {}", value)))?);
+                },
+                "key" => {
+                    let (key, description) = value.split_once(char::is_whitespace).unwrap_or((value, ""));
+                    let key = u8::from_str_radix(key, 16).ok().filter(|&key| key < 16);
+                    let key = key.ok_or_else(|| error(format!("Invalid key: {}", value)))?;
+                    info.keys.push((key, description.trim().to_string()));
+                },
+                "palette" => info.palette = Some(Palette::parse(value).map_err(error)?),
+                "rotate" => {
+                    info.orientation.get_or_insert_default().rotation = Orientation::parse_rotation(value).map_err(error)?;
+                },
+                "flip" => info.orientation.get_or_insert_default().flip(value).map_err(error)?,
+                _ => return Err(error(format!("Unknown field: {}", field))),
+            }
+        }
+
+        Ok(database)
+    }
+
+    // Entries in `other` add to the database, overriding the fields they set
+    pub fn merge(&mut self, other: RomDatabase) {
+        for (hash, info) in other.entries {
+            self.entries.entry(hash).or_default().merge(info);
+        }
+    }
+
+    pub fn lookup(&self, rom: &[u8]) -> Option<&RomInfo> {
+        self.entries.get(&sha1(rom))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    let comment = line.match_indices('#').find(|&(index, _)| match line[..index].chars().next_back() {
+        None => true,
+        Some(previous) => previous.is_whitespace() && !starts_colour(&line[index + 1..]),
+    });
+    comment.map_or(line, |(index, _)| &line[..index])
+}
+
+fn starts_colour(text: &str) -> bool {
+    let digits = text.find(|c: char| c == ',' || c.is_whitespace()).map_or(text, |end| &text[..end]);
+    digits.len() == 6 && digits.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+// Lowercase hex, as sha1sum prints it
+pub fn sha1(rom: &[u8]) -> String {
+    sha1_smol::Sha1::from(rom).digest().to_string()
+}
+
+// Where the user's own settings live, following the XDG base directory spec
+pub fn user_config_dir() -> Option<PathBuf> {
+    let config = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(config.join("rust-chip8"))
+}
+
+// Where the user's own entries and overrides live
+pub fn user_database_path() -> Option<PathBuf> {
+    Some(user_config_dir()?.join("roms.txt"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orientation::Rotation;
+
+    const BRIX: &str = "f13766c14aeb02ad8d4d103cb5eadd282d20cddc";
+
+    #[test]
+    fn test_sha1_matches_sha1sum() {
+        assert_eq!(sha1(b""), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+        assert_eq!(sha1(b"abc"), "a9993e364706816aba3e25717850c26c9cd0d89d");
+    }
+
+    #[test]
+    fn test_parse_reads_every_field() {
+        let database = RomDatabase::parse(&format!(
+            "# A comment\n[{}]\ntitle Brix  # Breakout\nauthor Andreas Gustafsson\nplatform chip-8\n\
+             quirks cosmac\nspeed 20\nkey 4 left\nkey 6 right\npalette amber\nrotate 90\nflip vertical\n",
+            BRIX.to_uppercase()
+        )).unwrap();
+
+        let info = &database.entries[BRIX];
+        assert_eq!(info.title.as_deref(), Some("Brix"));
+        assert_eq!(info.author.as_deref(), Some("Andreas Gustafsson"));
+        assert_eq!(info.platform.as_deref(), Some("chip-8"));
+        assert_eq!(info.quirks, Some(Quirks::COSMAC_VIP));
+        assert_eq!(info.instructions_per_frame, Some(20));
+        assert_eq!(info.keys, [(4, "left".to_string()), (6, "right".to_string())]);
+        assert_eq!(info.palette, Palette::named("amber"));
+        assert_eq!(info.orientation, Some(Orientation { rotation: Rotation::Clockwise90, flip_horizontal: false, flip_vertical: true }));
+    }
+
+    #[test]
+    fn test_parse_reads_hex_palette() {
+        let database = RomDatabase::parse(&format!("[{}]\npalette #2b1d0e,#e0c9a6  # Sepia\n", BRIX)).unwrap();
+
+        assert_eq!(database.entries[BRIX].palette, Some(Palette::parse("#2b1d0e,#e0c9a6").unwrap()));
+        assert_eq!(strip_comment("title Pong #1"), "title Pong ");
+        assert_eq!(strip_comment("title Pong#1 #2b1d0e"), "title Pong#1 #2b1d0e");
+        assert_eq!(strip_comment("#2b1d0e"), "");
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_entries() {
+        assert!(RomDatabase::parse("title Brix").is_err());
+        assert!(RomDatabase::parse("[f137]").is_err());
+        for field in ["speed 0", "key 10 up", "quirks vip", "colour red", "title", "rotate 45", "flip sideways"] {
+            assert!(RomDatabase::parse(&format!("[{}]\n{}", BRIX, field)).is_err(), "{}", field);
+        }
+    }
+
+    #[test]
+    fn test_merge_overrides_fields() {
+        let mut database = RomDatabase::bundled();
+        let overrides = RomDatabase::parse(&format!("[{}]\nspeed 30\npalette green\n[{}]\ntitle Mine\n", BRIX, "0".repeat(40))).unwrap();
+        let entries = database.len();
+
+        database.merge(overrides);
+
+        let brix = fs::read(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("roms").join("BRIX")).unwrap();
+        let info = database.lookup(&brix).unwrap();
+        assert_eq!(info.title.as_deref(), Some("Brix"));
+        assert_eq!(info.keys.len(), 2);
+        assert_eq!(info.instructions_per_frame, Some(30));
+        assert_eq!(info.palette, Palette::named("green"));
+        assert_eq!(database.len(), entries + 1);
+    }
+
+    #[test]
+    fn test_bundled_database_covers_every_rom() {
+        let database = RomDatabase::bundled();
+
+        for entry in fs::read_dir(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("roms")).unwrap() {
+            let path = entry.unwrap().path();
+            let rom = fs::read(&path).unwrap();
+            assert!(database.lookup(&rom).is_some(), "{}", path.display());
+        }
+    }
+}
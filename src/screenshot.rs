@@ -0,0 +1,86 @@
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::display::{WIDTH, HEIGHT};
+use crate::image::ImageSettings;
+
+pub const DEFAULT_SCALE: u32 = 10;
+
+pub fn save_png(path: &str, pixels: &[bool; WIDTH * HEIGHT], settings: &ImageSettings) -> io::Result<()> {
+    let image = settings.render(pixels);
+
+    let file = File::create(path)?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), image.width() as u32, image.height() as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header().map_err(io::Error::other)?;
+    writer.write_image_data(&image.to_rgb_bytes()).map_err(io::Error::other)?;
+
+    Ok(())
+}
+
+// e.g. chip8-20240131-235959.png, in UTC
+pub fn timestamped_filename(prefix: &str, extension: &str) -> String {
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (year, month, day) = civil_from_days((seconds / 86400) as i64);
+    let time_of_day = seconds % 86400;
+
+    format!(
+        "{}-{:04}{:02}{:02}-{:02}{:02}{:02}.{}",
+        prefix, year, month, day,
+        time_of_day / 3600, (time_of_day % 3600) / 60, time_of_day % 60,
+        extension
+    )
+}
+
+// Howard Hinnant's days-since-epoch to Gregorian date conversion
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filters::Filter;
+    use crate::orientation::Orientation;
+    use crate::palette::Palette;
+
+    #[test]
+    fn test_save_png_scales_image() {
+        let mut pixels = [false; WIDTH * HEIGHT];
+        pixels[0] = true;
+
+        let path = std::env::temp_dir().join("rust_chip8_test_save_png.png");
+        let path = path.to_str().unwrap();
+        let settings = ImageSettings { palette: Palette::default(), orientation: Orientation::default(), filter: Filter::None, scale: 2 };
+        save_png(path, &pixels, &settings).unwrap();
+
+        let decoder = png::Decoder::new(File::open(path).unwrap());
+        let mut reader = decoder.read_info().unwrap();
+        let mut data = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut data).unwrap();
+
+        assert_eq!((info.width, info.height), (WIDTH as u32 * 2, HEIGHT as u32 * 2));
+        assert_eq!(&data[0..3], &[0xd9, 0x91, 0x49]);
+        assert_eq!(&data[6..9], &[0x95, 0x34, 0x0a]);
+    }
+
+    #[test]
+    fn test_civil_from_days() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(19753), (2024, 1, 31));
+        assert_eq!(civil_from_days(11016), (2000, 2, 29));
+    }
+}
@@ -1,30 +1,77 @@
 use sdl2::{self, Sdl};
-use sdl2::pixels;
+use sdl2::pixels::{self, PixelFormatEnum};
 use sdl2::rect::Rect;
-use sdl2::render::Canvas;
-use sdl2::video::Window;
+use sdl2::render::{Canvas, Texture};
+use sdl2::video::{FullscreenType, Window};
 
-use crate::display::{DisplayDriver, WIDTH, HEIGHT};
+use rust_chip8::display::{DisplayDriver, WIDTH, HEIGHT};
+use rust_chip8::filters::Filter;
+use rust_chip8::image::Image;
+use rust_chip8::orientation::Orientation;
+use rust_chip8::palette::Palette;
+use rust_chip8::persistence::Persistence;
 
-const SCALE_FACTOR: u32 = 20;
-const SCREEN_WIDTH: u32 = (WIDTH as u32) * SCALE_FACTOR;
-const SCREEN_HEIGHT: u32 = (HEIGHT as u32) * SCALE_FACTOR;
+pub const DEFAULT_WINDOW_SCALE: u32 = 20;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ScaleMode {
+    // Whole multiples of the CHIP-8 resolution, keeping pixels perfectly square
+    Integer,
+    // Largest size that fits the window, keeping the aspect ratio
+    Fit,
+}
+
+#[derive(Clone, Copy)]
+pub struct WindowSettings {
+    pub scale: u32,
+    pub scale_mode: ScaleMode,
+    pub fullscreen: bool,
+    pub filter: Filter,
+    pub orientation: Orientation,
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        WindowSettings {
+            scale: DEFAULT_WINDOW_SCALE,
+            scale_mode: ScaleMode::Integer,
+            fullscreen: false,
+            filter: Filter::None,
+            orientation: Orientation::default(),
+        }
+    }
+}
+
+const BYTES_PER_PIXEL: usize = 3;
 
 pub struct Sdl2DisplayDriver {
     canvas: Canvas<Window>,
+    // The filtered frame is uploaded here and scaled up by the GPU in a single copy
+    texture: Texture,
+    palette: Palette,
+    scale_mode: ScaleMode,
+    filter: Filter,
+    orientation: Orientation,
+    persistence: Option<Persistence>,
+    frame: [bool; WIDTH * HEIGHT],
 }
 
 impl Sdl2DisplayDriver {
-    pub fn new(sdl_context: &Sdl) -> Sdl2DisplayDriver {
-        // let sdl_context = sdl2::init().unwrap();
+    pub fn new(sdl_context: &Sdl, palette: Palette, settings: WindowSettings) -> Sdl2DisplayDriver {
+        // Nearest neighbour scaling keeps pixel edges sharp
+        sdl2::hint::set("SDL_RENDER_SCALE_QUALITY", "0");
+
         let video_subsys = sdl_context.video().unwrap();
+        let (frame_width, frame_height) = settings.orientation.output_size(WIDTH, HEIGHT);
+        let (window_width, window_height) = window_size(frame_width, frame_height, settings.scale);
         let window = video_subsys
             .window(
                 "rust-chip8",
-                SCREEN_WIDTH,
-                SCREEN_HEIGHT,
+                window_width,
+                window_height,
             )
             .position_centered()
+            .resizable()
             .opengl()
             .build()
             .unwrap();
@@ -35,30 +82,132 @@ impl Sdl2DisplayDriver {
         canvas.clear();
         canvas.present();
 
-        Sdl2DisplayDriver { canvas }
+        let texture = canvas
+            .texture_creator()
+            .create_texture_streaming(
+                PixelFormatEnum::RGB24,
+                (frame_width * settings.filter.factor()) as u32,
+                (frame_height * settings.filter.factor()) as u32,
+            )
+            .unwrap();
+
+        let mut driver = Sdl2DisplayDriver {
+            canvas,
+            texture,
+            palette,
+            scale_mode: settings.scale_mode,
+            filter: settings.filter,
+            orientation: settings.orientation,
+            persistence: None,
+            frame: [false; WIDTH * HEIGHT],
+        };
+
+        if settings.fullscreen {
+            driver.toggle_fullscreen();
+        }
+
+        driver
+    }
+
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+    }
+
+    pub fn set_persistence(&mut self, persistence: Option<Persistence>) {
+        self.persistence = persistence;
+    }
+
+    pub fn fade(&mut self) {
+        if let Some(persistence) = &mut self.persistence {
+            persistence.fade(&self.frame);
+        }
+    }
+
+    pub fn toggle_fullscreen(&mut self) {
+        let window = self.canvas.window_mut();
+        let fullscreen = match window.fullscreen_state() {
+            FullscreenType::Off => FullscreenType::Desktop,
+            _ => FullscreenType::Off,
+        };
+
+        let _ = window.set_fullscreen(fullscreen);
     }
 }
 
 impl DisplayDriver for Sdl2DisplayDriver {
-    fn draw_pixels(&mut self, pixels: [bool; WIDTH * HEIGHT]) {
-        self.canvas.clear();
-        
-        for (i, pixel_on) in pixels.iter().enumerate() {
-            let x = i % WIDTH * SCALE_FACTOR as usize;
-            let y = i / WIDTH * SCALE_FACTOR as usize;
-
-            self.canvas.set_draw_color(to_colour(*pixel_on));
-            let _ = self.canvas
-                .fill_rect(Rect::new(x as i32, y as i32, SCALE_FACTOR, SCALE_FACTOR));
+    fn draw_pixels(&mut self, pixels: &[bool; WIDTH * HEIGHT]) {
+        self.frame = *pixels;
+        if let Some(persistence) = &mut self.persistence {
+            persistence.light(pixels);
         }
+
+        let image = Image::from_fn(WIDTH, HEIGHT, |x, y| {
+            let i = x + y * WIDTH;
+            match &self.persistence {
+                Some(persistence) => persistence.colour(i, &self.palette),
+                None => self.palette.colour(pixels[i]),
+            }
+        });
+        let image = self.filter.apply(&self.orientation.apply(&image));
+
+        let _ = self.texture.with_lock(None, |buffer, pitch| {
+            for (row, colours) in image.pixels().chunks(image.width()).enumerate() {
+                for (x, colour) in colours.iter().enumerate() {
+                    let offset = row * pitch + x * BYTES_PER_PIXEL;
+                    buffer[offset..offset + BYTES_PER_PIXEL].copy_from_slice(&[colour.r, colour.g, colour.b]);
+                }
+            }
+        });
+
+        // Letterbox bars around the viewport are always black
+        self.canvas.set_draw_color(pixels::Color::RGB(0, 0, 0));
+        self.canvas.clear();
+
+        let (output_width, output_height) = self.canvas.output_size().unwrap_or((0, 0));
+        let (frame_width, frame_height) = self.orientation.output_size(WIDTH, HEIGHT);
+        let (left, top, scale) = viewport(output_width, output_height, frame_width, frame_height, self.scale_mode);
+        let destination = Rect::new(
+            left,
+            top,
+            (frame_width as f32 * scale).round() as u32,
+            (frame_height as f32 * scale).round() as u32,
+        );
+
+        let _ = self.canvas.copy(&self.texture, None, destination);
         self.canvas.present();
     }
 }
 
-fn to_colour(value: bool) -> pixels::Color {
-    if value {
-        pixels::Color::RGB(0xd9, 0x91, 0x49)
-    } else {
-        pixels::Color::RGB(0x95, 0x34, 0x0a)
+fn window_size(frame_width: usize, frame_height: usize, scale: u32) -> (u32, u32) {
+    (frame_width as u32 * scale.max(1), frame_height as u32 * scale.max(1))
+}
+
+// Returns the top-left corner and pixel size of the frame centred within the output
+fn viewport(output_width: u32, output_height: u32, frame_width: usize, frame_height: usize, scale_mode: ScaleMode) -> (i32, i32, f32) {
+    let fit = (output_width as f32 / frame_width as f32).min(output_height as f32 / frame_height as f32);
+
+    let scale = match scale_mode {
+        ScaleMode::Integer => fit.floor().max(1.0),
+        ScaleMode::Fit => fit,
+    };
+
+    let left = (output_width as f32 - frame_width as f32 * scale) / 2.0;
+    let top = (output_height as f32 - frame_height as f32 * scale) / 2.0;
+
+    (left.round() as i32, top.round() as i32, scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_viewport_integer_letterboxes() {
+        assert_eq!(viewport(1300, 700, 64, 32, ScaleMode::Integer), (10, 30, 20.0));
+    }
+
+    #[test]
+    fn test_viewport_fit_keeps_aspect_ratio() {
+        assert_eq!(viewport(1000, 1000, 64, 32, ScaleMode::Fit), (0, 250, 15.625));
     }
 }
@@ -0,0 +1,127 @@
+use crate::{cpu, display, keyboard, memory};
+
+// Marks a buffer as a save state, with the layout version in the last byte
+const HEADER: [u8; 4] = *b"C8S\x01";
+
+// Save states are always this size, so they can live in a fixed buffer without allocating
+pub const SIZE: usize = HEADER.len() + cpu::STATE_SIZE + memory::SIZE + display::STATE_SIZE + keyboard::STATE_SIZE;
+
+pub struct Writer<'a> {
+    buffer: &'a mut [u8; SIZE],
+    position: usize,
+}
+
+impl<'a> Writer<'a> {
+    pub fn new(buffer: &'a mut [u8; SIZE]) -> Writer<'a> {
+        let mut writer = Writer { buffer, position: 0 };
+        writer.bytes(&HEADER);
+        writer
+    }
+
+    pub fn bytes(&mut self, data: &[u8]) {
+        self.buffer[self.position..self.position + data.len()].copy_from_slice(data);
+        self.position += data.len();
+    }
+
+    pub fn byte(&mut self, value: u8) {
+        self.bytes(&[value]);
+    }
+
+    pub fn word(&mut self, value: u16) {
+        self.bytes(&value.to_be_bytes());
+    }
+}
+
+#[derive(Clone)]
+pub struct Reader<'a> {
+    buffer: &'a [u8; SIZE],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(buffer: &'a [u8; SIZE]) -> Result<Reader<'a>, &'static str> {
+        let mut reader = Reader { buffer, position: 0 };
+        if reader.bytes(HEADER.len()) != HEADER {
+            return Err("Not a save state from this version");
+        }
+        Ok(reader)
+    }
+
+    pub fn bytes(&mut self, length: usize) -> &'a [u8] {
+        let data = &self.buffer[self.position..self.position + length];
+        self.position += length;
+        data
+    }
+
+    pub fn byte(&mut self) -> u8 {
+        self.bytes(1)[0]
+    }
+
+    pub fn word(&mut self) -> u16 {
+        u16::from_be_bytes([self.byte(), self.byte()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chip8::{Chip8, INSTRUCTIONS_PER_FRAME};
+    use crate::null_display_driver::NullDisplayDriver;
+
+    fn machine() -> Chip8<NullDisplayDriver> {
+        let mut chip8 = Chip8::new(NullDisplayDriver::new());
+        chip8.seed_rng(3);
+        chip8.load_default_font();
+        // Draw the 0 glyph at random positions forever
+        chip8.load_rom(&[0xC0, 0xFF, 0xC1, 0x1F, 0xD0, 0x15, 0x12, 0x00]).unwrap();
+        chip8
+    }
+
+    #[test]
+    fn test_load_state_resumes_identically() {
+        let mut chip8 = machine();
+        chip8.run_frame(INSTRUCTIONS_PER_FRAME * 3).unwrap();
+        chip8.key_down(7);
+
+        let mut state = [0; SIZE];
+        chip8.save_state(&mut state);
+        chip8.run_frame(INSTRUCTIONS_PER_FRAME * 3).unwrap();
+        let expected = *chip8.framebuffer();
+
+        // A fresh machine, seeded differently, has to pick up where the saved one left off
+        let mut restored = Chip8::new(NullDisplayDriver::new());
+        restored.load_state(&state).unwrap();
+        assert_eq!(restored.key_pressed(), Some(7));
+        restored.run_frame(INSTRUCTIONS_PER_FRAME * 3).unwrap();
+
+        assert_eq!(restored.framebuffer(), &expected);
+        assert_eq!(restored.cpu().v_registers(), chip8.cpu().v_registers());
+    }
+
+    #[test]
+    fn test_load_state_rejects_other_data() {
+        let mut chip8 = machine();
+        assert!(chip8.load_state(&[0; SIZE]).is_err());
+    }
+
+    #[test]
+    fn test_load_state_rejects_values_out_of_range() {
+        let mut chip8 = machine();
+        chip8.run_frame(INSTRUCTIONS_PER_FRAME).unwrap();
+        let mut state = [0; SIZE];
+        chip8.save_state(&mut state);
+
+        // I, the program counter, the first stack entry and the held key
+        let i_register = HEADER.len() + 16;
+        let program_counter = i_register + 4;
+        for (offset, value) in [(i_register, 0x10), (program_counter, 0x10), (program_counter + 2, 0x10), (SIZE - 2, 0x10)] {
+            let mut corrupted = state;
+            corrupted[offset] = value;
+            assert!(chip8.load_state(&corrupted).is_err(), "offset {}", offset);
+
+            let mut unchanged = [0; SIZE];
+            chip8.save_state(&mut unchanged);
+            assert!(unchanged == state, "offset {}", offset);
+        }
+    }
+}
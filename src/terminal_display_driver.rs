@@ -0,0 +1,267 @@
+use std::io::{self, Stdout, Write};
+use std::time::{Duration, Instant};
+
+use crossterm::{cursor, event, execute, queue, terminal};
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+use crossterm::style::{Color, Print, SetBackgroundColor, SetForegroundColor, ResetColor};
+
+use rust_chip8::display::{DisplayDriver, WIDTH, HEIGHT};
+use rust_chip8::orientation::Orientation;
+use rust_chip8::palette::{Colour, Palette};
+use rust_chip8::persistence::Persistence;
+
+// Terminals rarely report key releases, so a key counts as held until it stops repeating
+const KEY_RELEASE_TIMEOUT: Duration = Duration::from_millis(150);
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TerminalMode {
+    HalfBlocks,
+    Braille,
+}
+
+impl TerminalMode {
+    fn cell_size(self) -> (usize, usize) {
+        match self {
+            TerminalMode::HalfBlocks => (1, 2),
+            TerminalMode::Braille => (2, 4),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+struct Cell {
+    glyph: char,
+    foreground: Color,
+    background: Color,
+}
+
+pub struct TerminalDisplayDriver {
+    stdout: Stdout,
+    mode: TerminalMode,
+    palette: Palette,
+    orientation: Orientation,
+    persistence: Option<Persistence>,
+    frame: [bool; WIDTH * HEIGHT],
+    cells: Vec<Option<Cell>>,
+}
+
+impl TerminalDisplayDriver {
+    pub fn new(mode: TerminalMode, palette: Palette, orientation: Orientation) -> TerminalDisplayDriver {
+        let mut stdout = io::stdout();
+
+        terminal::enable_raw_mode().unwrap();
+        execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide, terminal::Clear(terminal::ClearType::All)).unwrap();
+
+        let (cell_width, cell_height) = mode.cell_size();
+        let (width, height) = orientation.output_size(WIDTH, HEIGHT);
+        let cell_count = (width / cell_width) * (height / cell_height);
+
+        TerminalDisplayDriver {
+            stdout,
+            mode,
+            palette,
+            orientation,
+            persistence: None,
+            frame: [false; WIDTH * HEIGHT],
+            cells: vec![None; cell_count],
+        }
+    }
+
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+        self.cells.fill(None);
+    }
+
+    pub fn set_persistence(&mut self, persistence: Option<Persistence>) {
+        self.persistence = persistence;
+    }
+
+    // Shows a message on the line below the screen, replacing the last one
+    pub fn set_status(&mut self, message: &str) {
+        let (_, cell_height) = self.mode.cell_size();
+        let (_, height) = self.orientation.output_size(WIDTH, HEIGHT);
+        let row = (height / cell_height) as u16;
+
+        let _ = queue!(self.stdout, cursor::MoveTo(0, row), terminal::Clear(terminal::ClearType::CurrentLine), Print(message));
+        let _ = self.stdout.flush();
+    }
+
+    pub fn fade(&mut self) {
+        if let Some(persistence) = &mut self.persistence {
+            persistence.fade(&self.frame);
+        }
+    }
+
+    fn cell_at(&self, pixels: &[bool; WIDTH * HEIGHT], column: usize, row: usize) -> Cell {
+        let intensity = |x: usize, y: usize| {
+            let (x, y) = self.orientation.source_position(x, y, WIDTH, HEIGHT);
+            match &self.persistence {
+                Some(persistence) => persistence.intensity(x + y * WIDTH),
+                None => pixels[x + y * WIDTH] as u8 as f32,
+            }
+        };
+        let pixel = |x: usize, y: usize| intensity(x, y) > 0.0;
+        let colour = |pixel_on: bool| to_colour(self.palette.colour(pixel_on));
+        let blended = |intensity: f32| to_colour(self.palette.background().blend(self.palette.foreground(), intensity));
+
+        match self.mode {
+            TerminalMode::HalfBlocks => {
+                let top = intensity(column, row * 2);
+                let bottom = intensity(column, row * 2 + 1);
+
+                Cell { glyph: '▀', foreground: blended(top), background: blended(bottom) }
+            },
+            // Braille dots share one colour per cell, so fading dims the whole cell
+            TerminalMode::Braille => {
+                // Dot numbering for the U+2800 block, indexed by [y][x] within the cell
+                const DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+                let mut bits = 0;
+                for (dy, dots) in DOTS.iter().enumerate() {
+                    for (dx, dot) in dots.iter().enumerate() {
+                        if pixel(column * 2 + dx, row * 4 + dy) {
+                            bits |= dot;
+                        }
+                    }
+                }
+
+                let brightest = (0..8)
+                    .map(|i| intensity(column * 2 + i % 2, row * 4 + i / 2))
+                    .fold(0.0, f32::max);
+
+                Cell {
+                    glyph: char::from_u32(0x2800 + bits).unwrap(),
+                    foreground: blended(brightest),
+                    background: colour(false),
+                }
+            },
+        }
+    }
+}
+
+impl DisplayDriver for TerminalDisplayDriver {
+    fn draw_pixels(&mut self, pixels: &[bool; WIDTH * HEIGHT]) {
+        self.frame = *pixels;
+        if let Some(persistence) = &mut self.persistence {
+            persistence.light(pixels);
+        }
+
+        let (cell_width, cell_height) = self.mode.cell_size();
+        let (width, height) = self.orientation.output_size(WIDTH, HEIGHT);
+        let columns = width / cell_width;
+        let rows = height / cell_height;
+
+        for row in 0..rows {
+            for column in 0..columns {
+                let cell = self.cell_at(pixels, column, row);
+                let previous = &mut self.cells[column + row * columns];
+
+                if *previous == Some(cell) {
+                    continue;
+                }
+                *previous = Some(cell);
+
+                let _ = queue!(
+                    self.stdout,
+                    cursor::MoveTo(column as u16, row as u16),
+                    SetForegroundColor(cell.foreground),
+                    SetBackgroundColor(cell.background),
+                    Print(cell.glyph),
+                );
+            }
+        }
+
+        let _ = queue!(self.stdout, ResetColor);
+        let _ = self.stdout.flush();
+    }
+}
+
+impl Drop for TerminalDisplayDriver {
+    fn drop(&mut self) {
+        let _ = execute!(self.stdout, ResetColor, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+pub enum TerminalInput {
+    KeyDown(u8),
+    KeyUp,
+    Screenshot,
+    ToggleRecording,
+    NextPalette,
+    Quit,
+}
+
+// Reads the keypad from stdin, which must already be in raw mode
+pub struct TerminalKeypad {
+    held_key: Option<(u8, Instant)>,
+}
+
+impl TerminalKeypad {
+    pub fn new() -> TerminalKeypad {
+        TerminalKeypad { held_key: None }
+    }
+
+    pub fn poll(&mut self) -> Vec<TerminalInput> {
+        let mut inputs = Vec::new();
+
+        while event::poll(Duration::ZERO).unwrap_or(false) {
+            let key_event = match event::read() {
+                Ok(Event::Key(key_event)) if key_event.kind != KeyEventKind::Release => key_event,
+                _ => continue,
+            };
+
+            match key_event.code {
+                KeyCode::Esc => inputs.push(TerminalInput::Quit),
+                KeyCode::F(10) => inputs.push(TerminalInput::NextPalette),
+                KeyCode::F(11) => inputs.push(TerminalInput::ToggleRecording),
+                KeyCode::F(12) => inputs.push(TerminalInput::Screenshot),
+                KeyCode::Char(c) => {
+                    if let Some(key) = to_key(c) {
+                        if self.held_key.is_some_and(|(held, _)| held != key) {
+                            inputs.push(TerminalInput::KeyUp);
+                        }
+                        self.held_key = Some((key, Instant::now()));
+                        inputs.push(TerminalInput::KeyDown(key));
+                    }
+                },
+                _ => {}
+            }
+        }
+
+        if let Some((_, last_seen)) = self.held_key {
+            if last_seen.elapsed() > KEY_RELEASE_TIMEOUT {
+                self.held_key = None;
+                inputs.push(TerminalInput::KeyUp);
+            }
+        }
+
+        inputs
+    }
+}
+
+fn to_key(c: char) -> Option<u8> {
+    match c.to_ascii_lowercase() {
+        '1' => Some(0x01),
+        '2' => Some(0x02),
+        '3' => Some(0x03),
+        '4' => Some(0x0C),
+        'q' => Some(0x04),
+        'w' => Some(0x05),
+        'e' => Some(0x06),
+        'r' => Some(0x0D),
+        'a' => Some(0x07),
+        's' => Some(0x08),
+        'd' => Some(0x09),
+        'f' => Some(0x0E),
+        'z' => Some(0x0A),
+        'x' => Some(0x00),
+        'c' => Some(0x0B),
+        'v' => Some(0x0F),
+        _ => None,
+    }
+}
+
+fn to_colour(colour: Colour) -> Color {
+    Color::Rgb { r: colour.r, g: colour.g, b: colour.b }
+}
@@ -0,0 +1,152 @@
+// Differential tests for the JIT. ROMs and random programs are run both compiled and
+// interpreted, and the two machines have to match after every frame.
+#![cfg(feature = "jit")]
+
+use std::fs;
+use std::path::PathBuf;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use rust_chip8::chip8::{self, Chip8};
+use rust_chip8::memory;
+use rust_chip8::null_display_driver::NullDisplayDriver;
+use rust_chip8::quirks::Quirks;
+
+// These use opcodes from later CHIP-8 variants, which the interpreter panics on
+const UNSUPPORTED_ROMS: [&str; 4] = ["SCTEST", "eaty.ch8", "grad.ch8", "mondrian.ch8"];
+
+fn machine(rom: &[u8], quirks: Quirks, jit: bool) -> Chip8<NullDisplayDriver> {
+    let mut chip8 = Chip8::new(NullDisplayDriver::new());
+    chip8.set_jit(jit);
+    chip8.set_quirks(quirks);
+    chip8.seed_rng(1);
+    chip8.load_default_font();
+    chip8.load_rom(rom).unwrap();
+    chip8
+}
+
+fn assert_same(interpreted: &Chip8<NullDisplayDriver>, compiled: &Chip8<NullDisplayDriver>, context: &str) {
+    let (expected, actual) = (interpreted.cpu(), compiled.cpu());
+
+    assert_eq!(expected.program_counter(), actual.program_counter(), "PC differs in {}", context);
+    assert_eq!(expected.v_registers(), actual.v_registers(), "registers differ in {}", context);
+    assert_eq!(expected.i_register(), actual.i_register(), "I differs in {}", context);
+    assert_eq!(expected.delay_timer(), actual.delay_timer(), "delay timer differs in {}", context);
+    assert_eq!(expected.sound_timer(), actual.sound_timer(), "sound timer differs in {}", context);
+    assert!(
+        interpreted.memory().read_bytes(0, memory::SIZE) == compiled.memory().read_bytes(0, memory::SIZE),
+        "memory differs in {}", context
+    );
+    assert!(interpreted.framebuffer() == compiled.framebuffer(), "screen differs in {}", context);
+}
+
+fn run_side_by_side(rom: &[u8], quirks: Quirks, frames: u32, context: &str) {
+    let mut interpreted = machine(rom, quirks, false);
+    let mut compiled = machine(rom, quirks, true);
+
+    for frame in 0..frames {
+        for chip8 in [&mut interpreted, &mut compiled] {
+            // Hold each key in turn for a while, so games get past their title screens
+            match frame % 20 {
+                0 => chip8.key_down((frame / 20 % 16) as u8),
+                10 => chip8.key_up(),
+                _ => {},
+            }
+            chip8.run_frame(chip8::INSTRUCTIONS_PER_FRAME).unwrap();
+        }
+
+        assert_same(&interpreted, &compiled, &format!("{} at frame {}", context, frame));
+    }
+}
+
+// As run_side_by_side, with the keys changed by run_frame_holding like the environments do
+fn run_holding_side_by_side(rom: &[u8], frames: u32, context: &str) {
+    let mut interpreted = machine(rom, Quirks::default(), false);
+    let mut compiled = machine(rom, Quirks::default(), true);
+
+    for frame in 0..frames {
+        let key = (frame % 20 < 10).then_some((frame / 20 % 16) as u8);
+        for chip8 in [&mut interpreted, &mut compiled] {
+            chip8.run_frame_holding(key, chip8::INSTRUCTIONS_PER_FRAME).unwrap();
+        }
+
+        assert_same(&interpreted, &compiled, &format!("{} at frame {}", context, frame));
+    }
+}
+
+// Mostly instructions the JIT compiles, with a few it doesn't so blocks get cut short
+fn random_program(rng: &mut StdRng, length: u16) -> Vec<u8> {
+    let end = chip8::PROGRAM_START + 16 * 2 + length * 2;
+    let mut opcodes: Vec<u16> = (0..16).map(|x| 0x6000 | x << 8 | rng.gen::<u8>() as u16).collect();
+
+    for index in 0..length {
+        let x = rng.gen_range(0..16u16) << 8;
+        let y = rng.gen_range(0..16u16) << 4;
+
+        opcodes.push(match rng.gen_range(0..12) {
+            0 => 0x6000 | x | rng.gen::<u8>() as u16,
+            1 => 0x7000 | x | rng.gen::<u8>() as u16,
+            2 | 3 => 0x8000 | x | y | [0x0, 0x1, 0x2, 0x3, 0x4, 0x5, 0x6, 0x7, 0xE][rng.gen_range(0..9)],
+            4 => 0xA000 | rng.gen_range(0..0x1000),
+            5 => 0xF000 | x | [0x07, 0x15, 0x18, 0x1E, 0x29][rng.gen_range(0..5)],
+            6 => 0x3000 | x | rng.gen_range(0..4),
+            7 => 0x4000 | x | rng.gen_range(0..4),
+            8 => [0x5000, 0x9000][rng.gen_range(0..2)] | x | y,
+            9 => {
+                let remaining = length - index;
+                0x1000 | (end - rng.gen_range(0..remaining) * 2)
+            },
+            10 => 0xC000 | x | rng.gen::<u8>() as u16,
+            _ => 0x00E0,
+        });
+    }
+
+    // Jumps and skips can land on either of these, which loop forever
+    opcodes.extend([0x1000 | end, 0x1000 | (end + 2)]);
+    opcodes.iter().flat_map(|opcode| opcode.to_be_bytes()).collect()
+}
+
+#[test]
+fn test_roms_match_interpreter() {
+    let roms = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("roms");
+    let mut names: Vec<String> = fs::read_dir(&roms)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .filter(|name| !UNSUPPORTED_ROMS.contains(&name.as_str()))
+        .collect();
+    names.sort();
+
+    for name in names {
+        let rom = fs::read(roms.join(&name)).unwrap();
+        run_side_by_side(&rom, Quirks::default(), 300, &name);
+        run_holding_side_by_side(&rom, 300, &format!("{} holding keys", name));
+    }
+}
+
+#[test]
+fn test_random_programs_match_interpreter() {
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+
+    for (profile, quirks) in Quirks::PROFILES {
+        for program in 0..200 {
+            let length = rng.gen_range(1..64);
+            let rom = random_program(&mut rng, length);
+            run_side_by_side(&rom, quirks, 10, &format!("{} program {}", profile, program));
+        }
+    }
+}
+
+#[test]
+fn test_self_modifying_code_is_recompiled() {
+    let rom = [
+        0x61, 0x05, // V1 = 5, with the 5 patched each time round
+        0x82, 0x14, // V2 += V1
+        0xA2, 0x01, // I = 0x201
+        0x70, 0x01, // V0 += 1
+        0xF0, 0x55, // store V0 at 0x201
+        0x12, 0x00, // jump to start
+    ];
+
+    run_side_by_side(&rom, Quirks::default(), 30, "self-modifying program");
+}
@@ -0,0 +1,54 @@
+// Replays the input movies in tests/movies against games in roms, checking the
+// screen and memory at the frames each movie lists.
+#![cfg(feature = "std")]
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use rust_chip8::chip8::Chip8;
+use rust_chip8::movie::Movie;
+use rust_chip8::null_display_driver::NullDisplayDriver;
+
+fn play(rom: &str, movie: &str) {
+    play_on(Chip8::new(NullDisplayDriver::new()), rom, movie);
+}
+
+fn play_on(mut chip8: Chip8<NullDisplayDriver>, rom: &str, movie: &str) {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let data = fs::read(root.join("roms").join(rom)).unwrap();
+    let movie = Movie::load(root.join("tests").join("movies").join(movie).to_str().unwrap()).unwrap();
+
+    chip8.load_default_font();
+    chip8.load_rom(&data).unwrap();
+
+    if let Err(failures) = movie.play(&mut chip8) {
+        panic!("{} replay failed\n{}", rom, failures.join("\n"));
+    }
+}
+
+#[test]
+fn test_brix_movie() {
+    play("BRIX", "brix.movie");
+}
+
+#[test]
+fn test_pong_movie() {
+    play("PONG", "pong.movie");
+}
+
+#[test]
+fn test_tetris_movie() {
+    play("TETRIS", "tetris.movie");
+}
+
+// The JIT has to replay every movie exactly as the interpreter does
+#[test]
+#[cfg(feature = "jit")]
+fn test_movies_with_jit() {
+    for (rom, movie) in [("BRIX", "brix.movie"), ("PONG", "pong.movie"), ("TETRIS", "tetris.movie")] {
+        let mut chip8 = Chip8::new(NullDisplayDriver::new());
+        chip8.set_jit(true);
+        play_on(chip8, rom, movie);
+    }
+}
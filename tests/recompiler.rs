@@ -0,0 +1,123 @@
+// Checks the recompiled ROMs in examples/recompiled are current, and that they behave exactly
+// like the interpreter. Run with UPDATE_GOLDENS=1 to regenerate them.
+#![cfg(feature = "std")]
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use rust_chip8::chip8::{self, Chip8};
+use rust_chip8::cpu::Fault;
+use rust_chip8::memory;
+use rust_chip8::null_display_driver::NullDisplayDriver;
+use rust_chip8::quirks::Quirks;
+use rust_chip8::recompiler;
+
+#[path = "../examples/recompiled/brix.rs"]
+mod brix;
+// Falls back to the interpreter for BNNN, and reads keys and random numbers
+#[path = "../examples/recompiled/outlaw.rs"]
+mod outlaw;
+// Covers the arithmetic, BCD and register loads and stores BRIX doesn't use
+#[path = "../examples/recompiled/test_opcode.rs"]
+mod test_opcode;
+
+// Each ROM and the module it's recompiled to
+const RECOMPILED: [(&str, &str); 3] = [("BRIX", "brix"), ("outlaw.ch8", "outlaw"), ("test_opcode.ch8", "test_opcode")];
+
+type Machine = Chip8<NullDisplayDriver>;
+type RunFrame = fn(&mut Machine, u32) -> Result<(), Fault>;
+
+fn root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+// Runs the interpreter and the recompiled module side by side, pressing and releasing `keys`
+// in turn so the ROM gets somewhere
+fn run_side_by_side(rom: &[u8], load: fn(&mut Machine), run_frame: RunFrame, keys: &[u8]) {
+    // Blocks only run recompiled when they fit in what's left of the frame, so the long ones
+    // need longer frames
+    for instructions in [chip8::INSTRUCTIONS_PER_FRAME, 100] {
+        run_frames_side_by_side(rom, load, run_frame, keys, instructions);
+    }
+}
+
+fn run_frames_side_by_side(rom: &[u8], load: fn(&mut Machine), run_frame: RunFrame, keys: &[u8], instructions: u32) {
+    let mut interpreted = Chip8::new(NullDisplayDriver::new());
+    interpreted.seed_rng(1);
+    interpreted.load_default_font();
+    interpreted.load_rom(rom).unwrap();
+
+    let mut recompiled = Chip8::new(NullDisplayDriver::new());
+    recompiled.seed_rng(1);
+    load(&mut recompiled);
+
+    for frame in 0..1200 {
+        for chip8 in [&mut interpreted, &mut recompiled] {
+            match frame % 60 {
+                0 => chip8.key_down(keys[frame / 60 % keys.len()]),
+                50 => chip8.key_up(),
+                _ => {},
+            }
+        }
+
+        let expected = interpreted.run_frame(instructions);
+        assert_eq!(expected, run_frame(&mut recompiled, instructions), "result differs at frame {}", frame);
+
+        let (expected, actual) = (interpreted.cpu(), recompiled.cpu());
+        assert_eq!(expected.program_counter(), actual.program_counter(), "PC differs at frame {}", frame);
+        assert_eq!(expected.v_registers(), actual.v_registers(), "registers differ at frame {}", frame);
+        assert_eq!(expected.i_register(), actual.i_register(), "I differs at frame {}", frame);
+        assert_eq!(expected.delay_timer(), actual.delay_timer(), "delay timer differs at frame {}", frame);
+        assert_eq!(expected.sound_timer(), actual.sound_timer(), "sound timer differs at frame {}", frame);
+        assert!(
+            interpreted.memory().read_bytes(0, memory::SIZE) == recompiled.memory().read_bytes(0, memory::SIZE),
+            "memory differs at frame {}", frame
+        );
+        assert!(interpreted.framebuffer() == recompiled.framebuffer(), "screen differs at frame {}", frame);
+
+        if expected.fault().is_some() {
+            break;
+        }
+    }
+}
+
+#[test]
+fn test_recompiled_source_is_current() {
+    for (rom, module) in RECOMPILED {
+        let source = recompiler::recompile(&fs::read(root().join("roms").join(rom)).unwrap(), rom, Quirks::default());
+        let path = root().join("examples").join("recompiled").join(format!("{}.rs", module));
+
+        if env::var_os("UPDATE_GOLDENS").is_some() {
+            fs::write(&path, &source).unwrap();
+            continue;
+        }
+
+        assert!(fs::read_to_string(&path).unwrap() == source, "{} is out of date, run with UPDATE_GOLDENS=1", path.display());
+    }
+}
+
+#[test]
+fn test_recompiled_matches_interpreter() {
+    run_side_by_side(&brix::ROM, brix::load, brix::run_frame, &[0x4, 0x6]);
+    run_side_by_side(&outlaw::ROM, outlaw::load, outlaw::run_frame, &[0x5, 0x7, 0x8, 0x9, 0xA, 0x2]);
+    run_side_by_side(&test_opcode::ROM, test_opcode::load, test_opcode::run_frame, &[0x0]);
+}
+
+#[test]
+fn test_recompiled_falls_back_when_quirks_change() {
+    let mut interpreted = Chip8::new(NullDisplayDriver::new());
+    interpreted.load_default_font();
+    interpreted.load_rom(&test_opcode::ROM).unwrap();
+    interpreted.set_quirks(Quirks::COSMAC_VIP);
+
+    let mut recompiled = Chip8::new(NullDisplayDriver::new());
+    test_opcode::load(&mut recompiled);
+    recompiled.set_quirks(Quirks::COSMAC_VIP);
+
+    for _ in 0..60 {
+        interpreted.run_frame(chip8::INSTRUCTIONS_PER_FRAME).unwrap();
+        test_opcode::run_frame(&mut recompiled, chip8::INSTRUCTIONS_PER_FRAME).unwrap();
+    }
+    assert!(interpreted.framebuffer() == recompiled.framebuffer());
+}
@@ -0,0 +1,104 @@
+// Runs the bundled test ROMs headlessly and compares the final screen against the
+// ASCII-art goldens in tests/golden, and every ROM in roms against its screen hash in
+// tests/golden/screen_hashes.txt. Run with UPDATE_GOLDENS=1 to rewrite them.
+#![cfg(feature = "std")]
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use rust_chip8::chip8::{self, Chip8};
+use rust_chip8::display;
+use rust_chip8::null_display_driver::NullDisplayDriver;
+
+// Long enough for most games to get past their title screens
+const SCREEN_HASH_FRAMES: u32 = 300;
+
+fn run_rom(rom: &str, frames: u32) -> String {
+    let data = fs::read(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("roms").join(rom)).unwrap();
+
+    let mut chip8 = Chip8::new(NullDisplayDriver::new());
+    chip8.load_default_font();
+    chip8.load_rom(&data).unwrap();
+
+    for _ in 0..frames {
+        chip8.run_frame(chip8::INSTRUCTIONS_PER_FRAME).unwrap();
+    }
+
+    display::to_ascii(chip8.framebuffer())
+}
+
+// Expected and actual side by side, with differing rows marked
+fn visual_diff(expected: &str, actual: &str) -> String {
+    let mut diff = format!("   {:<64}   {}\n", "expected", "actual");
+
+    for (expected_row, actual_row) in expected.lines().zip(actual.lines()) {
+        let marker = if expected_row == actual_row { ' ' } else { '>' };
+        diff.push_str(&format!(" {} {} | {}\n", marker, expected_row, actual_row));
+    }
+
+    diff
+}
+
+fn check_golden(rom: &str, frames: u32, golden: &str) {
+    let actual = run_rom(rom, frames);
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden").join(golden);
+
+    if env::var_os("UPDATE_GOLDENS").is_some() {
+        fs::write(&path, &actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("Missing golden {}, run with UPDATE_GOLDENS=1 to create it", path.display()));
+
+    if expected != actual {
+        panic!("{} differs from {} after {} frames\n{}", rom, golden, frames, visual_diff(&expected, &actual));
+    }
+}
+
+#[test]
+fn test_opcode_rom() {
+    check_golden("test_opcode.ch8", 120, "test_opcode.txt");
+}
+
+#[test]
+fn test_bc_test_rom() {
+    check_golden("bc_test.ch8", 120, "bc_test.txt");
+}
+
+// Each ROM with the default quirks and speed and a fixed seed. A ROM that halts is
+// recorded as halting, along with the screen it stopped on.
+#[test]
+fn test_every_rom_matches_its_screen_hash() {
+    let roms = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("roms");
+    let mut names: Vec<String> = fs::read_dir(&roms).unwrap().map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned()).collect();
+    names.sort();
+
+    let mut actual = String::new();
+    for name in names {
+        let mut chip8 = Chip8::new(NullDisplayDriver::new());
+        chip8.seed_rng(1);
+        chip8.load_default_font();
+        chip8.load_rom(&fs::read(roms.join(&name)).unwrap()).unwrap();
+
+        let halted = (0..SCREEN_HASH_FRAMES).any(|_| chip8.run_frame(chip8::INSTRUCTIONS_PER_FRAME).is_err());
+        let hash = display::screen_hash(chip8.framebuffer());
+        actual.push_str(&format!("{:016x} {}{}\n", hash, name, if halted { " (halts)" } else { "" }));
+    }
+
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden").join("screen_hashes.txt");
+    if env::var_os("UPDATE_GOLDENS").is_some() {
+        fs::write(&path, &actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap();
+    let differing: Vec<&str> = actual.lines().filter(|line| !expected.lines().any(|expected| expected == *line)).collect();
+    assert!(
+        expected == actual,
+        "Screens differ after {} frames, or ROMs were added or removed:\n{}",
+        SCREEN_HASH_FRAMES,
+        differing.join("\n")
+    );
+}